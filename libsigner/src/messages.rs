@@ -30,8 +30,9 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
 
+use blockstack_lib::burnchains::Txid;
 use blockstack_lib::chainstate::nakamoto::signer_set::NakamotoSigners;
-use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
+use blockstack_lib::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockVote};
 use blockstack_lib::chainstate::stacks::events::StackerDBChunksEvent;
 use blockstack_lib::chainstate::stacks::{StacksTransaction, ThresholdSignature};
 use blockstack_lib::net::api::postblock_proposal::{
@@ -41,6 +42,7 @@ use blockstack_lib::util_lib::boot::boot_code_id;
 use clarity::vm::types::serialization::SerializationError;
 use clarity::vm::types::QualifiedContractIdentifier;
 use hashbrown::{HashMap, HashSet};
+use libstackerdb::STACKERDB_MAX_CHUNK_SIZE;
 use serde::{Deserialize, Serialize};
 use stacks_common::codec::{
     read_next, read_next_at_most, read_next_exact, write_next, Error as CodecError,
@@ -61,6 +63,7 @@ use wsts::net::{
 };
 use wsts::schnorr::ID;
 use wsts::state_machine::{signer, SignError};
+use wsts::taproot::SchnorrProof;
 
 use crate::http::{decode_http_body, decode_http_request};
 use crate::EventError;
@@ -192,7 +195,10 @@ define_u8_enum!(RejectCodeTypePrefix{
     MissingTransactions = 3,
     ConnectivityIssues = 4,
     NonceTimeout = 5,
-    AggregatorError = 6
+    AggregatorError = 6,
+    NoAggregateKey = 7,
+    MissingTransactionIds = 8,
+    InvalidMerkleRoot = 9
 });
 
 impl TryFrom<u8> for RejectCodeTypePrefix {
@@ -214,6 +220,9 @@ impl From<&RejectCode> for RejectCodeTypePrefix {
             RejectCode::ConnectivityIssues => RejectCodeTypePrefix::ConnectivityIssues,
             RejectCode::NonceTimeout(_) => RejectCodeTypePrefix::NonceTimeout,
             RejectCode::AggregatorError(_) => RejectCodeTypePrefix::AggregatorError,
+            RejectCode::NoAggregateKey => RejectCodeTypePrefix::NoAggregateKey,
+            RejectCode::MissingTransactionIds(_) => RejectCodeTypePrefix::MissingTransactionIds,
+            RejectCode::InvalidMerkleRoot => RejectCodeTypePrefix::InvalidMerkleRoot,
         }
     }
 }
@@ -1016,6 +1025,8 @@ pub enum BlockResponse {
     Accepted((Sha512Trunc256Sum, ThresholdSignature)),
     /// The Nakamoto block was rejected and therefore not signed
     Rejected(BlockRejection),
+    /// The signers' vote on the Nakamoto block was signed with a taproot signature
+    SignedTaproot((NakamotoBlockVote, SchnorrProof)),
 }
 
 impl std::fmt::Display for BlockResponse {
@@ -1035,6 +1046,13 @@ impl std::fmt::Display for BlockResponse {
                     r.reason_code, r.reason, r.signer_signature_hash
                 )
             }
+            BlockResponse::SignedTaproot((vote, proof)) => {
+                write!(
+                    f,
+                    "BlockSignedTaproot: signer_sighash = {}, rejected = {}, proof = ({},{})",
+                    vote.signer_signature_hash, vote.rejected, proof.r, proof.s
+                )
+            }
         }
     }
 }
@@ -1052,6 +1070,11 @@ impl BlockResponse {
             RejectCode::SignedRejection(ThresholdSignature(sig)),
         ))
     }
+
+    /// Create a new taproot signed BlockResponse for the provided block vote and schnorr proof
+    pub fn signed_taproot(vote: NakamotoBlockVote, proof: SchnorrProof) -> Self {
+        Self::SignedTaproot((vote, proof))
+    }
 }
 
 impl StacksMessageCodec for BlockResponse {
@@ -1066,6 +1089,12 @@ impl StacksMessageCodec for BlockResponse {
                 write_next(fd, &1u8)?;
                 write_next(fd, rejection)?;
             }
+            BlockResponse::SignedTaproot((vote, proof)) => {
+                write_next(fd, &2u8)?;
+                write_next(fd, vote)?;
+                fd.write_all(&proof.to_bytes())
+                    .map_err(CodecError::WriteError)?;
+            }
         };
         Ok(())
     }
@@ -1082,6 +1111,13 @@ impl StacksMessageCodec for BlockResponse {
                 let rejection = read_next::<BlockRejection, _>(fd)?;
                 BlockResponse::Rejected(rejection)
             }
+            2 => {
+                let vote = read_next::<NakamotoBlockVote, _>(fd)?;
+                let mut proof_bytes = [0u8; 64];
+                fd.read_exact(&mut proof_bytes)
+                    .map_err(CodecError::ReadError)?;
+                BlockResponse::SignedTaproot((vote, SchnorrProof::from(proof_bytes)))
+            }
             _ => {
                 return Err(CodecError::DeserializeError(format!(
                     "Unknown block response type prefix: {}",
@@ -1112,6 +1148,53 @@ impl BlockRejection {
             signer_signature_hash,
         }
     }
+
+    /// Create a new BlockRejection for the provided block and missing transactions.
+    /// If `compact` is set and the signer message carrying the full transactions would not fit
+    /// in a StackerDB chunk, only their txids are included.
+    pub fn missing_transactions(
+        signer_signature_hash: Sha512Trunc256Sum,
+        missing_transactions: Vec<StacksTransaction>,
+        compact: bool,
+    ) -> Self {
+        Self::missing_transactions_with_limit(
+            signer_signature_hash,
+            missing_transactions,
+            compact,
+            STACKERDB_MAX_CHUNK_SIZE as usize,
+        )
+    }
+
+    fn missing_transactions_with_limit(
+        signer_signature_hash: Sha512Trunc256Sum,
+        missing_transactions: Vec<StacksTransaction>,
+        compact: bool,
+        max_size: usize,
+    ) -> Self {
+        if !compact {
+            return Self::new(
+                signer_signature_hash,
+                RejectCode::MissingTransactions(missing_transactions),
+            );
+        }
+        let txids = missing_transactions.iter().map(|tx| tx.txid()).collect();
+        let rejection = Self::new(
+            signer_signature_hash,
+            RejectCode::MissingTransactions(missing_transactions),
+        );
+        if SignerMessage::from(rejection.clone())
+            .serialize_to_vec()
+            .len()
+            > max_size
+        {
+            Self::new(
+                signer_signature_hash,
+                RejectCode::MissingTransactionIds(txids),
+            )
+        } else {
+            rejection
+        }
+    }
 }
 
 impl StacksMessageCodec for BlockRejection {
@@ -1164,6 +1247,12 @@ pub enum RejectCode {
     MissingTransactions(Vec<StacksTransaction>),
     /// The block was rejected due to connectivity issues with the signer
     ConnectivityIssues,
+    /// The signer has no approved aggregate key and the block carries no aggregate key votes
+    NoAggregateKey,
+    /// Missing the following expected transactions, identified by their txids
+    MissingTransactionIds(Vec<Txid>),
+    /// The block's tx merkle root does not match its transactions
+    InvalidMerkleRoot,
 }
 
 impl From<&SignError> for RejectCode {
@@ -1193,6 +1282,8 @@ impl StacksMessageCodec for RejectCode {
             }
             RejectCode::AggregatorError(reason) => write_next(fd, &reason.as_bytes().to_vec())?,
             RejectCode::ConnectivityIssues => write_next(fd, &4u8)?,
+            RejectCode::NoAggregateKey | RejectCode::InvalidMerkleRoot => (),
+            RejectCode::MissingTransactionIds(txids) => write_next(fd, txids)?,
         };
         Ok(())
     }
@@ -1222,6 +1313,11 @@ impl StacksMessageCodec for RejectCode {
                 RejectCode::NonceTimeout(read_next::<Vec<u32>, _>(fd)?)
             }
             RejectCodeTypePrefix::ConnectivityIssues => RejectCode::ConnectivityIssues,
+            RejectCodeTypePrefix::NoAggregateKey => RejectCode::NoAggregateKey,
+            RejectCodeTypePrefix::InvalidMerkleRoot => RejectCode::InvalidMerkleRoot,
+            RejectCodeTypePrefix::MissingTransactionIds => {
+                RejectCode::MissingTransactionIds(read_next::<Vec<Txid>, _>(fd)?)
+            }
             RejectCodeTypePrefix::AggregatorError => {
                 let reason_bytes = read_next::<Vec<u8>, _>(fd)?;
                 let reason = String::from_utf8(reason_bytes).map_err(|e| {
@@ -1268,6 +1364,19 @@ impl std::fmt::Display for RejectCode {
                 "An internal error occurred in the signer when aggregating the signaure: {:?}",
                 reason
             ),
+            RejectCode::NoAggregateKey => write!(
+                f,
+                "The signer has no approved aggregate key to sign the block with."
+            ),
+            RejectCode::MissingTransactionIds(txids) => write!(
+                f,
+                "Missing the following expected transactions: {:?}",
+                txids
+            ),
+            RejectCode::InvalidMerkleRoot => write!(
+                f,
+                "The block's transaction merkle root does not match its transactions."
+            ),
         }
     }
 }
@@ -1381,6 +1490,86 @@ mod test {
         let deserialized_code = read_next::<RejectCode, _>(&mut &serialized_code[..])
             .expect("Failed to deserialize RejectCode");
         assert_eq!(code, deserialized_code);
+
+        let code = RejectCode::NoAggregateKey;
+        let serialized_code = code.serialize_to_vec();
+        let deserialized_code = read_next::<RejectCode, _>(&mut &serialized_code[..])
+            .expect("Failed to deserialize RejectCode");
+        assert_eq!(code, deserialized_code);
+
+        let code = RejectCode::MissingTransactionIds(vec![Txid([1u8; 32]), Txid([2u8; 32])]);
+        let serialized_code = code.serialize_to_vec();
+        let deserialized_code = read_next::<RejectCode, _>(&mut &serialized_code[..])
+            .expect("Failed to deserialize RejectCode");
+        assert_eq!(code, deserialized_code);
+
+        let code = RejectCode::InvalidMerkleRoot;
+        let serialized_code = code.serialize_to_vec();
+        let deserialized_code = read_next::<RejectCode, _>(&mut &serialized_code[..])
+            .expect("Failed to deserialize RejectCode");
+        assert_eq!(code, deserialized_code);
+    }
+
+    #[test]
+    fn missing_transactions_should_only_be_compacted_past_size_limit() {
+        let sk = StacksPrivateKey::new();
+        let tx = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: CHAIN_ID_TESTNET,
+            auth: TransactionAuth::from_p2pkh(&sk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::SmartContract(
+                TransactionSmartContract {
+                    name: "test-contract".into(),
+                    code_body: StacksString::from_str("(/ 1 0)").unwrap(),
+                },
+                None,
+            ),
+        };
+        let mut other_tx = tx.clone();
+        other_tx.set_origin_nonce(1);
+        let txs = vec![tx, other_tx];
+        let txids: Vec<_> = txs.iter().map(|tx| tx.txid()).collect();
+        let hash = Sha512Trunc256Sum([0u8; 32]);
+        let full_rejection =
+            BlockRejection::new(hash, RejectCode::MissingTransactions(txs.clone()));
+        // The limit applies to the whole signer message, not just the reject code
+        let full_size = SignerMessage::from(full_rejection.clone())
+            .serialize_to_vec()
+            .len();
+
+        // Full transactions are always sent unless compact rejections are enabled
+        assert_eq!(
+            BlockRejection::missing_transactions_with_limit(
+                hash,
+                txs.clone(),
+                false,
+                full_size - 1
+            ),
+            full_rejection
+        );
+        assert_eq!(
+            BlockRejection::missing_transactions_with_limit(hash, txs.clone(), true, full_size),
+            full_rejection
+        );
+        assert_eq!(
+            BlockRejection::missing_transactions_with_limit(hash, txs.clone(), true, full_size - 1),
+            BlockRejection::new(hash, RejectCode::MissingTransactionIds(txids.clone()))
+        );
+        // The reject code alone fitting is not enough
+        let code_size = full_rejection.reason_code.serialize_to_vec().len();
+        assert!(code_size < full_size);
+        assert_eq!(
+            BlockRejection::missing_transactions_with_limit(hash, txs.clone(), true, code_size),
+            BlockRejection::new(hash, RejectCode::MissingTransactionIds(txids))
+        );
+        // A couple of transactions fit in a StackerDB chunk
+        assert_eq!(
+            BlockRejection::missing_transactions(hash, txs, true),
+            full_rejection
+        );
     }
 
     #[test]
@@ -1448,6 +1637,18 @@ mod test {
         let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
             .expect("Failed to deserialize BlockResponse");
         assert_eq!(response, deserialized_response);
+
+        let response = BlockResponse::signed_taproot(
+            NakamotoBlockVote {
+                signer_signature_hash: Sha512Trunc256Sum([2u8; 32]),
+                rejected: false,
+            },
+            SchnorrProof::from([1u8; 64]),
+        );
+        let serialized_response = response.serialize_to_vec();
+        let deserialized_response = read_next::<BlockResponse, _>(&mut &serialized_response[..])
+            .expect("Failed to deserialize BlockResponse");
+        assert_eq!(response, deserialized_response);
     }
 
     #[test]
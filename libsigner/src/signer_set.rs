@@ -123,15 +123,13 @@ impl SignerEntries {
             .map_err(|_| Error::SignerCountOverflow)
     }
 
-    /// Return the number of Key IDs required to sign a message with the WSTS group signature
-    pub fn get_signing_threshold(&self) -> Result<u32, Error> {
-        let num_keys = self.count_keys()?;
-        Ok((num_keys as f64 * 7_f64 / 10_f64).ceil() as u32)
-    }
-
-    /// Return the number of Key IDs required to sign a message with the WSTS group signature
-    pub fn get_dkg_threshold(&self) -> Result<u32, Error> {
-        let num_keys = self.count_keys()?;
-        Ok((num_keys as f64 * 9_f64 / 10_f64).ceil() as u32)
+    /// Return the number of Key IDs that make up at least `ratio` of all Key IDs.
+    /// The ratio is rounded to millionths first, so that floating point error in e.g. a ratio of
+    /// 0.7 cannot push the threshold up by a key.
+    pub fn get_threshold(&self, ratio: f64) -> Result<u32, Error> {
+        let num_keys = u64::from(self.count_keys()?);
+        let ratio_millionths = (ratio * 1_000_000_f64).round() as u64;
+        let threshold = (num_keys * ratio_millionths + 999_999) / 1_000_000;
+        u32::try_from(threshold).map_err(|_| Error::SignerCountOverflow)
     }
 }
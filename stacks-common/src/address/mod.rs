@@ -96,24 +96,30 @@ impl error::Error for Error {
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Hash, Eq, Copy, Serialize, Deserialize)]
 pub enum AddressHashMode {
-    // We support four different modes due to legacy compatibility with Stacks v1 addresses:
+    // We support four different modes due to legacy compatibility with Stacks v1 addresses,
+    // plus taproot key-path spends:
     SerializeP2PKH = 0x00,  // hash160(public-key), same as bitcoin's p2pkh
     SerializeP2SH = 0x01,   // hash160(multisig-redeem-script), same as bitcoin's multisig p2sh
     SerializeP2WPKH = 0x02, // hash160(segwit-program-00(p2pkh)), same as bitcoin's p2sh-p2wpkh
     SerializeP2WSH = 0x03,  // hash160(segwit-program-00(public-keys)), same as bitcoin's p2sh-p2wsh
+    SerializeP2TR = 0x06,   // hash160(x-only-public-key), for taproot key-path spends
 }
 
 impl AddressHashMode {
     pub fn to_version_mainnet(&self) -> u8 {
         match *self {
-            AddressHashMode::SerializeP2PKH => C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+            AddressHashMode::SerializeP2PKH | AddressHashMode::SerializeP2TR => {
+                C32_ADDRESS_VERSION_MAINNET_SINGLESIG
+            }
             _ => C32_ADDRESS_VERSION_MAINNET_MULTISIG,
         }
     }
 
     pub fn to_version_testnet(&self) -> u8 {
         match *self {
-            AddressHashMode::SerializeP2PKH => C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            AddressHashMode::SerializeP2PKH | AddressHashMode::SerializeP2TR => {
+                C32_ADDRESS_VERSION_TESTNET_SINGLESIG
+            }
             _ => C32_ADDRESS_VERSION_TESTNET_MULTISIG,
         }
     }
@@ -141,6 +147,8 @@ impl TryFrom<u8> for AddressHashMode {
                 Ok(AddressHashMode::SerializeP2WPKH)
             }
             x if x == AddressHashMode::SerializeP2WSH as u8 => Ok(AddressHashMode::SerializeP2WSH),
+            // SerializeP2TR is deliberately not decoded here: this conversion is used to parse PoX
+            // address versions, where 0x06 denotes a 32-byte taproot output
             _ => Err(Error::InvalidVersion(value)),
         }
     }
@@ -200,6 +208,13 @@ fn to_bits_p2sh_p2wsh<K: PublicKey>(num_sigs: usize, pubkeys: &Vec<K>) -> Hash16
     Hash160::from_data(ws.as_bytes())
 }
 
+/// A taproot key-path spend (p2tr) commits to the BIP-340 x-only encoding of a single key,
+/// which is its x-coordinate
+fn to_bits_p2tr<K: PublicKey>(pubk: &K) -> Hash160 {
+    let key_bytes = pubk.to_bytes();
+    Hash160::from_data(&key_bytes[1..33])
+}
+
 /// Convert a number of required signatures and a list of public keys into a byte-vec to hash to an
 /// address.  Validity of the hash_flag vis a vis the num_sigs and pubkeys will _NOT_ be checked.
 /// This is a low-level method.  Consider using StacksAdress::from_public_keys() if you can.
@@ -213,6 +228,7 @@ pub fn public_keys_to_address_hash<K: PublicKey>(
         AddressHashMode::SerializeP2SH => to_bits_p2sh(num_sigs, pubkeys),
         AddressHashMode::SerializeP2WPKH => to_bits_p2sh_p2wpkh(&pubkeys[0]),
         AddressHashMode::SerializeP2WSH => to_bits_p2sh_p2wsh(num_sigs, pubkeys),
+        AddressHashMode::SerializeP2TR => to_bits_p2tr(&pubkeys[0]),
     }
 }
 
@@ -296,4 +312,23 @@ mod test {
             assert_eq!(result, pubkey_fixture.result);
         }
     }
+
+    #[test]
+    fn test_public_key_to_p2tr_address_hash() {
+        // the x-only key is the same regardless of the parity of the key's y-coordinate
+        for key in [
+            "020fadbbcea0ff3b05f03195b41cd991d7a0af8bd38559943aec99cbdaf0b22cc8",
+            "030fadbbcea0ff3b05f03195b41cd991d7a0af8bd38559943aec99cbdaf0b22cc8",
+        ] {
+            let result_hash = public_keys_to_address_hash(
+                &AddressHashMode::SerializeP2TR,
+                1,
+                &vec![PubKey::from_hex(key).unwrap()],
+            );
+            assert_eq!(
+                result_hash.as_bytes().to_vec(),
+                hex_bytes("dddc57f6e24a4cdc6551de39be4a71c4d5b7372d").unwrap()
+            );
+        }
+    }
 }
@@ -212,7 +212,9 @@ impl StacksAddress {
 
         // address hash mode must be consistent with the number of keys
         match *hash_mode {
-            AddressHashMode::SerializeP2PKH | AddressHashMode::SerializeP2WPKH => {
+            AddressHashMode::SerializeP2PKH
+            | AddressHashMode::SerializeP2WPKH
+            | AddressHashMode::SerializeP2TR => {
                 // must be a single public key, and must require one signature
                 if num_sigs != 1 || pubkeys.len() != 1 {
                     return None;
@@ -221,9 +223,11 @@ impl StacksAddress {
             _ => {}
         }
 
-        // if segwit, then keys must all be compressed
+        // if segwit or taproot, then keys must all be compressed
         match *hash_mode {
-            AddressHashMode::SerializeP2WPKH | AddressHashMode::SerializeP2WSH => {
+            AddressHashMode::SerializeP2WPKH
+            | AddressHashMode::SerializeP2WSH
+            | AddressHashMode::SerializeP2TR => {
                 for pubkey in pubkeys {
                     if !pubkey.compressed() {
                         return None;
@@ -21,6 +21,7 @@ pub(crate) mod stacks_client;
 
 use std::time::Duration;
 
+use blockstack_lib::util_lib::db::Error as DBError;
 use clarity::vm::errors::Error as ClarityError;
 use clarity::vm::types::serialization::SerializationError;
 use libstackerdb::Error as StackerDBError;
@@ -83,6 +84,9 @@ pub enum ClientError {
     /// Stacks node does not support a feature we need
     #[error("Stacks node does not support a required feature: {0}")]
     UnsupportedStacksFeature(String),
+    /// Signer database error
+    #[error("Signer database error: {0}")]
+    SignerDBError(#[from] DBError),
 }
 
 /// Retry a function F with an exponential backoff and notification on transient failure
@@ -122,6 +126,7 @@ pub(crate) mod tests {
     use clarity::vm::Value as ClarityValue;
     use hashbrown::{HashMap, HashSet};
     use libsigner::SignerEntries;
+    use libstackerdb::StackerDBChunkAckData;
     use rand::distributions::Standard;
     use rand::{thread_rng, Rng};
     use rand_core::{OsRng, RngCore};
@@ -200,6 +205,43 @@ pub(crate) mod tests {
         request_bytes
     }
 
+    /// Reply to the next request to the mock server with the given StackerDB chunk ack, once the
+    /// whole request has been read, and return the request bytes
+    pub fn write_ack_response(mock_server: TcpListener, ack: &StackerDBChunkAckData) -> Vec<u8> {
+        let mut response_bytes = b"HTTP/1.1 200 OK\n\n".to_vec();
+        response_bytes.extend(
+            serde_json::to_string(ack)
+                .expect("Failed to serialize ack")
+                .as_bytes(),
+        );
+        let mut stream = mock_server.accept().unwrap().0;
+        let mut request_bytes = vec![];
+        let mut buf = [0u8; 1024];
+        loop {
+            let read = stream.read(&mut buf).unwrap();
+            if read == 0 {
+                break;
+            }
+            request_bytes.extend_from_slice(&buf[..read]);
+            let Some(headers_end) = request_bytes
+                .windows(4)
+                .position(|window| window == b"\r\n\r\n")
+            else {
+                continue;
+            };
+            let headers = String::from_utf8_lossy(&request_bytes[..headers_end]).to_lowercase();
+            let content_length = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map_or(0, |len| len.trim().parse().unwrap());
+            if request_bytes.len() >= headers_end + 4 + content_length {
+                break;
+            }
+        }
+        stream.write_all(&response_bytes).unwrap();
+        request_bytes
+    }
+
     pub fn generate_random_consensus_hash() -> ConsensusHash {
         let rng = rand::thread_rng();
         let bytes: Vec<u8> = rng.sample_iter(Standard).take(20).collect();
@@ -510,12 +552,25 @@ pub(crate) mod tests {
             node_host: config.node_host.to_string(),
             mainnet: config.network.is_mainnet(),
             dkg_end_timeout: config.dkg_end_timeout,
+            dkg_retry_timeout: config.dkg_retry_timeout,
             dkg_private_timeout: config.dkg_private_timeout,
             dkg_public_timeout: config.dkg_public_timeout,
             nonce_timeout: config.nonce_timeout,
             sign_timeout: config.sign_timeout,
             tx_fee_ustx: config.tx_fee_ustx,
             db_path: config.db_path.clone(),
+            compact_rejections: config.compact_rejections,
+            log_packets: config.log_packets,
+            compress_block_info: config.compress_block_info,
+            db_busy_timeout: config.db_busy_timeout,
+            nonce_cache_ttl: config.nonce_cache_ttl,
+            idle_poll_interval: config.idle_poll_interval,
+            active_poll_interval: config.active_poll_interval,
+            signing_threshold_ratio: config.signing_threshold_ratio,
+            dkg_threshold_ratio: config.dkg_threshold_ratio,
+            max_pending_commands: config.max_pending_commands,
+            max_block_size: config.max_block_size,
+            transient_reject_codes: config.transient_reject_codes.clone(),
         }
     }
 
@@ -99,12 +99,34 @@ impl StackerDB {
         self.send_message_bytes_with_retry(&msg_id, message_bytes)
     }
 
+    /// Sends messages to the .signers stacker-db, making a single attempt to reach the stacks node
+    /// instead of blocking on an exponential backoff retry
+    pub fn send_message(
+        &mut self,
+        message: SignerMessage,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        let msg_id = message.msg_id();
+        let message_bytes = message.serialize_to_vec();
+        self.send_message_bytes(&msg_id, message_bytes, false)
+    }
+
     /// Sends message (as a raw msg ID and bytes) to the .signers stacker-db with an
     ///  exponential backoff retry
     pub fn send_message_bytes_with_retry(
         &mut self,
         msg_id: &MessageSlotID,
         message_bytes: Vec<u8>,
+    ) -> Result<StackerDBChunkAckData, ClientError> {
+        self.send_message_bytes(msg_id, message_bytes, true)
+    }
+
+    /// Sends message (as a raw msg ID and bytes) to the .signers stacker-db, retrying requests
+    /// to the stacks node with an exponential backoff if `retry` is set
+    fn send_message_bytes(
+        &mut self,
+        msg_id: &MessageSlotID,
+        message_bytes: Vec<u8>,
+        retry: bool,
     ) -> Result<StackerDBChunkAckData, ClientError> {
         let slot_id = self.signer_slot_id;
         loop {
@@ -134,8 +156,15 @@ impl StackerDB {
                 &session.stackerdb_contract_id
             );
 
-            let send_request = || session.put_chunk(&chunk).map_err(backoff::Error::transient);
-            let chunk_ack: StackerDBChunkAckData = retry_with_exponential_backoff(send_request)?;
+            let chunk_ack: StackerDBChunkAckData = if retry {
+                let send_request = || session.put_chunk(&chunk).map_err(backoff::Error::transient);
+                retry_with_exponential_backoff(send_request)?
+            } else {
+                session.put_chunk(&chunk).map_err(|e| {
+                    debug!("Failed to send a chunk to stackerdb: {e:?}");
+                    ClientError::NotConnected
+                })?
+            };
 
             if let Some(versions) = self.slot_versions.get_mut(msg_id) {
                 // NOTE: per the above, this is always executed
@@ -14,13 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::fs;
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::path::PathBuf;
 use std::time::Duration;
 
-use blockstack_lib::chainstate::stacks::TransactionVersion;
+use blockstack_lib::chainstate::stacks::{TransactionVersion, MAX_BLOCK_LEN};
+use blockstack_lib::net::api::postblock_proposal::ValidateRejectCode;
 use libsigner::SignerEntries;
 use serde::Deserialize;
 use stacks_common::address::{
@@ -29,11 +31,28 @@ use stacks_common::address::{
 use stacks_common::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
 use stacks_common::types::chainstate::{StacksAddress, StacksPrivateKey, StacksPublicKey};
 use stacks_common::types::PrivateKey;
+use stacks_common::util::hash::Sha512Trunc256Sum;
 use wsts::curve::scalar::Scalar;
 
 use crate::signer::SignerSlotID;
 
 const EVENT_TIMEOUT_MS: u64 = 5000;
+// Default time to wait for a DKG vote to reach its threshold before retriggering DKG
+const DKG_RETRY_TIMEOUT_MS: u64 = 300_000;
+// Default maximum number of commands a signer queues before dropping the oldest ones
+const MAX_PENDING_COMMANDS: usize = 128;
+// Default share of the key ids needed to sign a block
+const SIGNING_THRESHOLD_RATIO: f64 = 0.7;
+// Default share of the key ids needed to complete DKG
+const DKG_THRESHOLD_RATIO: f64 = 0.9;
+// Default time to reuse a fetched account nonce while no new block arrives
+const NONCE_CACHE_TTL_MS: u64 = 10_000;
+// Default time to wait for a locked signer database before giving up
+const DB_BUSY_TIMEOUT_MS: u64 = 5_000;
+// Default time to wait for an event while a signer has an operation or command pending
+const ACTIVE_POLL_INTERVAL_MS: u64 = 5_000;
+// Default block validation rejection codes that are treated as transient
+const TRANSIENT_REJECT_CODES: &[ValidateRejectCode] = &[ValidateRejectCode::ChainstateError];
 // Default transaction fee in microstacks (if unspecificed in the config file)
 // TODO: Use the fee estimation endpoint to get the default fee.
 const TX_FEE_USTX: u64 = 10_000;
@@ -53,6 +72,9 @@ pub enum ConfigError {
     /// An unsupported address version
     #[error("Failed to convert private key to address: unsupported address version.")]
     UnsupportedAddressVersion,
+    /// A key id is assigned to more than one signer
+    #[error("Key id {0} is assigned to more than one signer.")]
+    OverlappingKeyId(u32),
 }
 
 #[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -140,6 +162,8 @@ pub struct SignerConfig {
     pub dkg_private_timeout: Option<Duration>,
     /// timeout to gather DkgEnd messages
     pub dkg_end_timeout: Option<Duration>,
+    /// time to wait for a DKG vote to reach its threshold before retriggering DKG
+    pub dkg_retry_timeout: Duration,
     /// timeout to gather nonces
     pub nonce_timeout: Option<Duration>,
     /// timeout to gather signature shares
@@ -148,6 +172,97 @@ pub struct SignerConfig {
     pub tx_fee_ustx: u64,
     /// The path to the signer's database file
     pub db_path: PathBuf,
+    /// Whether to only send txids in missing transaction rejections that exceed the chunk limit
+    pub compact_rejections: bool,
+    /// Whether to record every inbound WSTS packet in the signer database
+    pub log_packets: bool,
+    /// Whether to gzip the block info stored in the signer database
+    pub compress_block_info: bool,
+    /// How long to wait for a locked signer database before giving up
+    pub db_busy_timeout: Duration,
+    /// How long to reuse an account nonce fetched from the stacks node
+    pub nonce_cache_ttl: Duration,
+    /// How long to wait for an event while idle with no queued commands
+    pub idle_poll_interval: Duration,
+    /// How long to wait for an event while an operation or command is pending
+    pub active_poll_interval: Duration,
+    /// The share of the key ids needed to sign a block
+    pub signing_threshold_ratio: f64,
+    /// The share of the key ids needed to complete DKG
+    pub dkg_threshold_ratio: f64,
+    /// The maximum number of commands to queue before dropping the oldest non-DKG ones
+    pub max_pending_commands: usize,
+    /// The maximum serialized size, in bytes, of a block embedded in a nonce request
+    pub max_block_size: usize,
+    /// The block validation rejection codes that are resubmitted for validation instead of rejected
+    pub transient_reject_codes: Vec<ValidateRejectCode>,
+}
+
+impl SignerConfig {
+    /// Hash the parts of this config that all signers in the reward cycle must agree on: the
+    /// network, the signer set's addresses, public keys and key ids, and the thresholds derived
+    /// from them.  Operators can compare digests across hosts to catch misconfigured signers.
+    pub fn config_digest(&self) -> Sha512Trunc256Sum {
+        let entries = &self.signer_entries;
+        let mut bytes = vec![u8::from(self.mainnet)];
+
+        let mut signer_ids: Vec<_> = entries.signer_ids.iter().collect();
+        signer_ids.sort_by_key(|(_, signer_id)| **signer_id);
+        bytes.extend((signer_ids.len() as u64).to_be_bytes());
+        for (address, signer_id) in signer_ids {
+            bytes.extend(signer_id.to_be_bytes());
+            bytes.push(address.version);
+            bytes.extend(address.bytes.0);
+        }
+
+        for public_keys in [&entries.public_keys.signers, &entries.public_keys.key_ids] {
+            let mut public_keys: Vec<_> = public_keys.iter().collect();
+            public_keys.sort_by_key(|(id, _)| **id);
+            bytes.extend((public_keys.len() as u64).to_be_bytes());
+            for (id, public_key) in public_keys {
+                bytes.extend(id.to_be_bytes());
+                bytes.extend(public_key.to_bytes());
+            }
+        }
+
+        let mut signer_key_ids: Vec<_> = entries.signer_key_ids.iter().collect();
+        signer_key_ids.sort_by_key(|(signer_id, _)| **signer_id);
+        bytes.extend((signer_key_ids.len() as u64).to_be_bytes());
+        for (signer_id, key_ids) in signer_key_ids {
+            let mut key_ids = key_ids.clone();
+            key_ids.sort();
+            bytes.extend(signer_id.to_be_bytes());
+            bytes.extend((key_ids.len() as u64).to_be_bytes());
+            for key_id in key_ids {
+                bytes.extend(key_id.to_be_bytes());
+            }
+        }
+
+        // the thresholds can only fail to compute if there are more than u32::MAX keys
+        let signing_threshold = entries
+            .get_threshold(self.signing_threshold_ratio)
+            .unwrap_or(u32::MAX);
+        let dkg_threshold = entries
+            .get_threshold(self.dkg_threshold_ratio)
+            .unwrap_or(u32::MAX);
+        bytes.extend(signing_threshold.to_be_bytes());
+        bytes.extend(dkg_threshold.to_be_bytes());
+
+        Sha512Trunc256Sum::from_data(&bytes)
+    }
+
+    /// Check that no key id is owned by more than one of the given signers
+    pub fn validate_key_id_disjointness(all_signers: &[SignerConfig]) -> Result<(), ConfigError> {
+        let mut seen_key_ids = HashSet::new();
+        for signer_config in all_signers {
+            for key_id in signer_config.key_ids.iter() {
+                if !seen_key_ids.insert(*key_id) {
+                    return Err(ConfigError::OverlappingKeyId(*key_id));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 /// The parsed configuration for the signer
@@ -173,6 +288,8 @@ pub struct GlobalConfig {
     pub dkg_private_timeout: Option<Duration>,
     /// timeout to gather DkgEnd messages
     pub dkg_end_timeout: Option<Duration>,
+    /// time to wait for a DKG vote to reach its threshold before retriggering DKG
+    pub dkg_retry_timeout: Duration,
     /// timeout to gather nonces
     pub nonce_timeout: Option<Duration>,
     /// timeout to gather signature shares
@@ -183,6 +300,32 @@ pub struct GlobalConfig {
     pub auth_password: String,
     /// The path to the signer's database file
     pub db_path: PathBuf,
+    /// Whether to only send txids in missing transaction rejections that exceed the chunk limit
+    pub compact_rejections: bool,
+    /// Whether to record every inbound WSTS packet in the signer database
+    pub log_packets: bool,
+    /// Whether to gzip the block info stored in the signer database
+    pub compress_block_info: bool,
+    /// How long to wait for a locked signer database before giving up
+    pub db_busy_timeout: Duration,
+    /// How long to reuse an account nonce fetched from the stacks node
+    pub nonce_cache_ttl: Duration,
+    /// How long to wait for an event while idle with no queued commands
+    pub idle_poll_interval: Duration,
+    /// How long to wait for an event while an operation or command is pending
+    pub active_poll_interval: Duration,
+    /// The share of the key ids needed to sign a block
+    pub signing_threshold_ratio: f64,
+    /// The share of the key ids needed to complete DKG
+    pub dkg_threshold_ratio: f64,
+    /// The address to serve Prometheus metrics on, if any
+    pub metrics_endpoint: Option<SocketAddr>,
+    /// The maximum number of commands to queue before dropping the oldest non-DKG ones
+    pub max_pending_commands: usize,
+    /// The maximum serialized size, in bytes, of a block embedded in a nonce request
+    pub max_block_size: usize,
+    /// The block validation rejection codes that are resubmitted for validation instead of rejected
+    pub transient_reject_codes: Vec<ValidateRejectCode>,
 }
 
 /// Internal struct for loading up the config file
@@ -205,6 +348,8 @@ struct RawConfigFile {
     pub dkg_private_timeout_ms: Option<u64>,
     /// timeout in (millisecs) to gather DkgEnd messages
     pub dkg_end_timeout_ms: Option<u64>,
+    /// time (in millisecs) to wait for a DKG vote to reach its threshold before retriggering DKG
+    pub dkg_retry_timeout_ms: Option<u64>,
     /// timeout in (millisecs) to gather nonces
     pub nonce_timeout_ms: Option<u64>,
     /// timeout in (millisecs) to gather signature shares
@@ -215,6 +360,49 @@ struct RawConfigFile {
     pub auth_password: String,
     /// The path to the signer's database file or :memory: for an in-memory database
     pub db_path: String,
+    /// Whether to only send the txids of missing transactions in a block rejection when the
+    /// full transactions would exceed the StackerDB chunk limit. Defaults to false.
+    pub compact_rejections: Option<bool>,
+    /// Whether to record every inbound WSTS packet in the signer database so that failed DKG
+    /// and signing rounds can be replayed for debugging. Defaults to false.
+    pub log_packets: Option<bool>,
+    /// Whether to gzip the serialized block info before storing it in the signer database, to
+    /// reduce its size on disk. Defaults to false.
+    pub compress_block_info: Option<bool>,
+    /// How long (in millisecs) to wait for a locked signer database to become available before
+    /// failing the query. Defaults to 5 seconds.
+    pub db_busy_timeout_ms: Option<u64>,
+    /// How long (in millisecs) to reuse an account nonce fetched from the stacks node before
+    /// fetching it again. A cached nonce is dropped once the signer submits a transaction from
+    /// that account, and every cached nonce is dropped when a new burn block or block proposal
+    /// arrives. Defaults to 10 seconds.
+    pub nonce_cache_ttl_ms: Option<u64>,
+    /// How long (in millisecs) to wait for an event while every signer is idle with no queued
+    /// commands. Never longer than `event_timeout_ms`, which it defaults to.
+    pub idle_poll_interval_ms: Option<u64>,
+    /// How long (in millisecs) to wait for an event while any signer has an operation in
+    /// progress or a queued command. Never longer than `event_timeout_ms`. Defaults to 5 seconds.
+    pub active_poll_interval_ms: Option<u64>,
+    /// The share of the key ids needed to sign a block, in (0, 1]. Must not exceed
+    /// `dkg_threshold_ratio`, and must match the miners' `miner.signing_threshold_ratio`.
+    /// Defaults to 0.7.
+    pub signing_threshold_ratio: Option<f64>,
+    /// The share of the key ids needed to complete DKG, in (0, 1]. Defaults to 0.9.
+    pub dkg_threshold_ratio: Option<f64>,
+    /// The address to serve Prometheus metrics on. Metrics are only served if this is set and
+    /// the signer was built with the `monitoring_prom` feature.
+    pub metrics_endpoint: Option<String>,
+    /// The maximum number of commands a signer queues before it drops the oldest sign commands.
+    /// A queued DKG command is never dropped. Defaults to 128.
+    pub max_pending_commands: Option<usize>,
+    /// The maximum serialized size, in bytes, of a block embedded in a nonce request. Larger
+    /// nonce requests are rejected without parsing the block. Defaults to the maximum block size.
+    pub max_block_size: Option<usize>,
+    /// The block validation rejection codes that the stacks node may return for a block that
+    /// could later turn out to be valid, such as when its chainstate is not ready. Blocks
+    /// rejected with one of these codes are resubmitted for validation instead of rejected.
+    /// Defaults to `["ChainstateError"]`.
+    pub transient_reject_codes: Option<Vec<ValidateRejectCode>>,
 }
 
 impl RawConfigFile {
@@ -286,12 +474,59 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
         let event_timeout =
             Duration::from_millis(raw_data.event_timeout_ms.unwrap_or(EVENT_TIMEOUT_MS));
         let dkg_end_timeout = raw_data.dkg_end_timeout_ms.map(Duration::from_millis);
+        let dkg_retry_timeout = Duration::from_millis(
+            raw_data
+                .dkg_retry_timeout_ms
+                .unwrap_or(DKG_RETRY_TIMEOUT_MS),
+        );
         let dkg_public_timeout = raw_data.dkg_public_timeout_ms.map(Duration::from_millis);
         let dkg_private_timeout = raw_data.dkg_private_timeout_ms.map(Duration::from_millis);
         let nonce_timeout = raw_data.nonce_timeout_ms.map(Duration::from_millis);
         let sign_timeout = raw_data.sign_timeout_ms.map(Duration::from_millis);
         let db_path = raw_data.db_path.into();
 
+        let signing_threshold_ratio = raw_data
+            .signing_threshold_ratio
+            .unwrap_or(SIGNING_THRESHOLD_RATIO);
+        let dkg_threshold_ratio = raw_data.dkg_threshold_ratio.unwrap_or(DKG_THRESHOLD_RATIO);
+        for (field, ratio) in [
+            ("signing_threshold_ratio", signing_threshold_ratio),
+            ("dkg_threshold_ratio", dkg_threshold_ratio),
+        ] {
+            if !(ratio > 0.0 && ratio <= 1.0) {
+                return Err(ConfigError::BadField(field.to_string(), ratio.to_string()));
+            }
+        }
+        if signing_threshold_ratio > dkg_threshold_ratio {
+            return Err(ConfigError::BadField(
+                "signing_threshold_ratio".to_string(),
+                format!(
+                    "{signing_threshold_ratio} exceeds dkg_threshold_ratio {dkg_threshold_ratio}"
+                ),
+            ));
+        }
+
+        let metrics_endpoint = match raw_data.metrics_endpoint {
+            Some(metrics_endpoint) => Some(
+                metrics_endpoint
+                    .to_socket_addrs()
+                    .map_err(|_| {
+                        ConfigError::BadField(
+                            "metrics_endpoint".to_string(),
+                            metrics_endpoint.clone(),
+                        )
+                    })?
+                    .next()
+                    .ok_or_else(|| {
+                        ConfigError::BadField(
+                            "metrics_endpoint".to_string(),
+                            metrics_endpoint.clone(),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+
         Ok(Self {
             node_host: raw_data.node_host,
             endpoint,
@@ -301,6 +536,7 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
             network: raw_data.network,
             event_timeout,
             dkg_end_timeout,
+            dkg_retry_timeout,
             dkg_public_timeout,
             dkg_private_timeout,
             nonce_timeout,
@@ -308,6 +544,34 @@ impl TryFrom<RawConfigFile> for GlobalConfig {
             tx_fee_ustx: raw_data.tx_fee_ustx.unwrap_or(TX_FEE_USTX),
             auth_password: raw_data.auth_password,
             db_path,
+            compact_rejections: raw_data.compact_rejections.unwrap_or(false),
+            log_packets: raw_data.log_packets.unwrap_or(false),
+            compress_block_info: raw_data.compress_block_info.unwrap_or(false),
+            db_busy_timeout: Duration::from_millis(
+                raw_data.db_busy_timeout_ms.unwrap_or(DB_BUSY_TIMEOUT_MS),
+            ),
+            nonce_cache_ttl: Duration::from_millis(
+                raw_data.nonce_cache_ttl_ms.unwrap_or(NONCE_CACHE_TTL_MS),
+            ),
+            idle_poll_interval: raw_data
+                .idle_poll_interval_ms
+                .map(Duration::from_millis)
+                .unwrap_or(event_timeout),
+            active_poll_interval: Duration::from_millis(
+                raw_data
+                    .active_poll_interval_ms
+                    .unwrap_or(ACTIVE_POLL_INTERVAL_MS),
+            ),
+            signing_threshold_ratio,
+            dkg_threshold_ratio,
+            metrics_endpoint,
+            max_pending_commands: raw_data
+                .max_pending_commands
+                .unwrap_or(MAX_PENDING_COMMANDS),
+            max_block_size: raw_data.max_block_size.unwrap_or(MAX_BLOCK_LEN as usize),
+            transient_reject_codes: raw_data
+                .transient_reject_codes
+                .unwrap_or_else(|| TRANSIENT_REJECT_CODES.to_vec()),
         })
     }
 }
@@ -419,6 +683,7 @@ event_timeout = {event_timeout_ms}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::tests::generate_signer_config;
 
     #[test]
     fn build_signer_config_tomls_should_produce_deserializable_strings() {
@@ -466,4 +731,123 @@ DKG transaction fee: 0.01 uSTX
             )
         );
     }
+
+    #[test]
+    fn metrics_endpoint_should_be_optional() {
+        let config_toml =
+            fs::read_to_string("./src/tests/conf/signer-0.toml").expect("Failed to read config");
+        let config = GlobalConfig::load_from_str(&config_toml).unwrap();
+        assert_eq!(config.metrics_endpoint, None);
+
+        let config = GlobalConfig::load_from_str(&format!(
+            "{config_toml}\nmetrics_endpoint = \"127.0.0.1:9154\"\n"
+        ))
+        .unwrap();
+        assert_eq!(
+            config.metrics_endpoint,
+            Some("127.0.0.1:9154".parse().unwrap())
+        );
+
+        assert!(GlobalConfig::load_from_str(&format!(
+            "{config_toml}\nmetrics_endpoint = \"not an address\"\n"
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn threshold_ratios_should_be_validated() {
+        let config_toml =
+            fs::read_to_string("./src/tests/conf/signer-0.toml").expect("Failed to read config");
+        let config = GlobalConfig::load_from_str(&config_toml).unwrap();
+        assert_eq!(config.signing_threshold_ratio, 0.7);
+        assert_eq!(config.dkg_threshold_ratio, 0.9);
+
+        let config = GlobalConfig::load_from_str(&format!(
+            "{config_toml}\nsigning_threshold_ratio = 0.5\ndkg_threshold_ratio = 1.0\n"
+        ))
+        .unwrap();
+        assert_eq!(config.signing_threshold_ratio, 0.5);
+        assert_eq!(config.dkg_threshold_ratio, 1.0);
+
+        for ratios in [
+            "signing_threshold_ratio = 0.0",
+            "dkg_threshold_ratio = 1.1",
+            "signing_threshold_ratio = -0.5",
+            "signing_threshold_ratio = 0.95",
+        ] {
+            assert!(
+                GlobalConfig::load_from_str(&format!("{config_toml}\n{ratios}\n")).is_err(),
+                "Expected {ratios} to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn config_digest_should_only_depend_on_shared_fields() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let digest = signer_config.config_digest();
+        assert_eq!(signer_config.clone().config_digest(), digest);
+
+        // another signer in the same signer set computes the same digest
+        let other_signer_config = SignerConfig {
+            signer_id: signer_config.signer_id + 1,
+            key_ids: vec![],
+            node_host: "127.0.0.2:20443".to_string(),
+            ..signer_config.clone()
+        };
+        assert_eq!(other_signer_config.config_digest(), digest);
+
+        // local-only settings do not affect the digest
+        let local_config = SignerConfig {
+            idle_poll_interval: signer_config.idle_poll_interval * 2,
+            active_poll_interval: signer_config.active_poll_interval * 2,
+            ..signer_config.clone()
+        };
+        assert_eq!(local_config.config_digest(), digest);
+
+        // the thresholds are shared, so changing the signing threshold ratio alone changes it
+        let threshold_config = SignerConfig {
+            signing_threshold_ratio: 0.9,
+            ..signer_config.clone()
+        };
+        assert_ne!(
+            signer_config.signer_entries.get_threshold(0.9).unwrap(),
+            signer_config
+                .signer_entries
+                .get_threshold(signer_config.signing_threshold_ratio)
+                .unwrap()
+        );
+        assert_ne!(threshold_config.config_digest(), digest);
+
+        let mainnet_config = SignerConfig {
+            mainnet: !signer_config.mainnet,
+            ..signer_config
+        };
+        assert_ne!(mainnet_config.config_digest(), digest);
+    }
+
+    #[test]
+    fn validate_key_id_disjointness() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 1, 1);
+        let signer_configs: Vec<_> = [vec![1, 2], vec![3], vec![4, 5, 6]]
+            .into_iter()
+            .enumerate()
+            .map(|(signer_id, key_ids)| SignerConfig {
+                signer_id: signer_id as u32,
+                key_ids,
+                ..signer_config.clone()
+            })
+            .collect();
+        SignerConfig::validate_key_id_disjointness(&signer_configs)
+            .expect("Disjoint key ids should be valid");
+
+        let mut overlapping_configs = signer_configs;
+        overlapping_configs[2].key_ids = vec![4, 2];
+        match SignerConfig::validate_key_id_disjointness(&overlapping_configs) {
+            Err(ConfigError::OverlappingKeyId(key_id)) => assert_eq!(key_id, 2),
+            res => panic!("Expected an overlapping key id, got {res:?}"),
+        }
+    }
 }
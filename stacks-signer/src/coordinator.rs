@@ -143,6 +143,24 @@ impl CoordinatorSelector {
         )
     }
 
+    /// Get the sequence of coordinator ids that will coordinate the next `num_rounds` signing rounds,
+    /// starting with the current coordinator and following the same rotation as `update_coordinator`
+    pub fn coordinator_rotation(&self, num_rounds: usize) -> Vec<u32> {
+        (0..num_rounds)
+            .map(|round| {
+                let index = if ROTATE_COORDINATORS {
+                    self.coordinator_index.saturating_add(round) % self.coordinator_ids.len()
+                } else {
+                    self.coordinator_index
+                };
+                *self
+                    .coordinator_ids
+                    .get(index)
+                    .expect("FATAL: Invalid number of registered signers")
+            })
+            .collect()
+    }
+
     /// Calculate the ordered list of coordinator ids by comparing the provided public keys
     pub fn calculate_coordinator_ids(
         public_keys: &PublicKeys,
@@ -220,6 +238,35 @@ mod tests {
         results
     }
 
+    #[test]
+    fn coordinator_rotation_should_distribute_rounds_across_coordinators() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let public_keys = generate_signer_config(&config, 10, 4000)
+            .signer_entries
+            .public_keys;
+        let selector = CoordinatorSelector::from(public_keys.clone());
+        let coordinator_ids =
+            CoordinatorSelector::calculate_coordinator_ids(&public_keys, &ConsensusHash::empty());
+        let (coordinator_id, _) = selector.get_coordinator();
+
+        let num_rounds = coordinator_ids.len() * 3;
+        let rotation = selector.coordinator_rotation(num_rounds);
+        assert_eq!(rotation.len(), num_rounds);
+        assert_eq!(rotation.first(), Some(&coordinator_id));
+        for id in &coordinator_ids {
+            let load = rotation.iter().filter(|&rotated| rotated == id).count();
+            let expected_load = if ROTATE_COORDINATORS {
+                3
+            } else if *id == coordinator_id {
+                num_rounds
+            } else {
+                0
+            };
+            assert_eq!(load, expected_load, "Unexpected load for coordinator {id}");
+        }
+        assert!(selector.coordinator_rotation(0).is_empty());
+    }
+
     #[test]
     fn calculate_coordinator_results_should_vary_or_match_based_on_hash() {
         let results_with_random_hash = generate_calculate_coordinator_test_results(true, 5);
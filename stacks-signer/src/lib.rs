@@ -28,6 +28,8 @@ pub mod client;
 pub mod config;
 /// The coordinator selector for the signer
 pub mod coordinator;
+/// The metrics module for the signer
+pub mod metrics;
 /// The primary runloop for the signer
 pub mod runloop;
 /// The signer module for processing events
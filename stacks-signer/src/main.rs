@@ -30,6 +30,7 @@ use std::fs::File;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
 use std::time::Duration;
 
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
@@ -49,6 +50,7 @@ use stacks_signer::cli::{
     GetLatestChunkArgs, PutChunkArgs, RunDkgArgs, RunSignerArgs, SignArgs, StackerDBArgs,
 };
 use stacks_signer::config::{build_signer_config_tomls, GlobalConfig};
+use stacks_signer::metrics::start_serving_metrics;
 use stacks_signer::runloop::{RunLoop, RunLoopCommand};
 use stacks_signer::signer::Command as SignerCommand;
 use tracing_subscriber::prelude::*;
@@ -86,6 +88,16 @@ fn spawn_running_signer(path: &PathBuf) -> SpawnedSigner {
     let config = GlobalConfig::try_from(path).unwrap();
     let endpoint = config.endpoint;
     info!("Starting signer with config: {}", config);
+    if let Some(metrics_endpoint) = config.metrics_endpoint {
+        thread::Builder::new()
+            .name("metrics".to_string())
+            .spawn(move || {
+                if let Err(e) = start_serving_metrics(metrics_endpoint) {
+                    error!("Failed to serve metrics at {metrics_endpoint}: {e:?}");
+                }
+            })
+            .expect("FATAL: failed to start metrics thread");
+    }
     let (cmd_send, cmd_recv) = channel();
     let (res_send, res_recv) = channel();
     let ev = SignerEventReceiver::new(config.network.is_mainnet());
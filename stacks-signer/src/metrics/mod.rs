@@ -0,0 +1,102 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+#![allow(unused_variables)]
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use libsigner::RejectCode;
+#[cfg(not(feature = "monitoring_prom"))]
+use slog::slog_warn;
+#[cfg(not(feature = "monitoring_prom"))]
+use stacks_common::warn;
+
+#[cfg(feature = "monitoring_prom")]
+mod prometheus;
+
+/// Errors from starting the metrics endpoint
+#[derive(Debug)]
+pub enum MetricsError {
+    /// Unable to bind to the given address
+    AlreadyBound,
+    /// Unable to get the local address of the bound socket
+    UnableToGetAddress,
+}
+
+/// Increment the number of DKG rounds this signer started as coordinator
+pub fn increment_dkg_rounds_started() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::DKG_ROUNDS_STARTED.inc();
+}
+
+/// Increment the number of DKG rounds that produced an aggregate key
+pub fn increment_dkg_rounds_succeeded() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::DKG_ROUNDS_SUCCEEDED.inc();
+}
+
+/// Increment the number of DKG rounds that ended in an error
+pub fn increment_dkg_rounds_failed() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::DKG_ROUNDS_FAILED.inc();
+}
+
+/// Increment the number of signing rounds this signer started as coordinator
+pub fn increment_sign_rounds_started() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::SIGN_ROUNDS_STARTED.inc();
+}
+
+/// Increment the number of signing rounds that produced a signature
+pub fn increment_sign_rounds_succeeded() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::SIGN_ROUNDS_SUCCEEDED.inc();
+}
+
+/// Increment the number of signing rounds that ended in an error
+pub fn increment_sign_rounds_failed() {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::SIGN_ROUNDS_FAILED.inc();
+}
+
+/// Record how long the signer spent with an operation in progress
+pub fn record_operation_duration(duration: Duration) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::OPERATION_DURATION_HISTOGRAM.observe(duration.as_secs_f64());
+}
+
+/// Increment the number of blocks rejected with the given reject code
+pub fn increment_block_rejections(reject_code: &RejectCode) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::BLOCK_REJECTIONS
+        .with_label_values(&[prometheus::reject_code_label(reject_code)])
+        .inc();
+}
+
+/// Serve the signer's metrics over HTTP at the given address.
+/// This blocks the calling thread for as long as the endpoint is up.
+#[cfg(feature = "monitoring_prom")]
+pub fn start_serving_metrics(bind_address: SocketAddr) -> Result<(), MetricsError> {
+    prometheus::start_serving_prometheus_metrics(bind_address)
+}
+
+/// Serve the signer's metrics over HTTP at the given address.
+/// This blocks the calling thread for as long as the endpoint is up.
+#[cfg(not(feature = "monitoring_prom"))]
+pub fn start_serving_metrics(bind_address: SocketAddr) -> Result<(), MetricsError> {
+    warn!("Attempted to start metrics endpoint at bind_address = {bind_address}, but stacks-signer was built without `monitoring_prom` feature.");
+    Ok(())
+}
@@ -0,0 +1,225 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use blockstack_lib::prometheus::{
+    gather, histogram_opts, opts, register_histogram, register_int_counter,
+    register_int_counter_vec, Encoder, Histogram, IntCounter, IntCounterVec, TextEncoder,
+};
+use lazy_static::lazy_static;
+use libsigner::RejectCode;
+use slog::{slog_debug, slog_error, slog_info, slog_warn};
+use stacks_common::{debug, error, info, warn};
+
+use super::MetricsError;
+
+/// The largest HTTP request head we will read from a metrics scraper
+const MAX_REQUEST_HEAD_LEN: usize = 8192;
+
+/// How long to wait on a metrics scraper to send its request or read the response, in milliseconds
+const STREAM_TIMEOUT_MS: u64 = 5_000;
+
+lazy_static! {
+    pub static ref DKG_ROUNDS_STARTED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_dkg_rounds_started_total",
+        "Total number of DKG rounds started by this signer as coordinator"
+    ))
+    .unwrap();
+    pub static ref DKG_ROUNDS_SUCCEEDED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_dkg_rounds_succeeded_total",
+        "Total number of DKG rounds that produced an aggregate key"
+    ))
+    .unwrap();
+    pub static ref DKG_ROUNDS_FAILED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_dkg_rounds_failed_total",
+        "Total number of DKG rounds that ended in an error"
+    ))
+    .unwrap();
+    pub static ref SIGN_ROUNDS_STARTED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_sign_rounds_started_total",
+        "Total number of signing rounds started by this signer as coordinator"
+    ))
+    .unwrap();
+    pub static ref SIGN_ROUNDS_SUCCEEDED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_sign_rounds_succeeded_total",
+        "Total number of signing rounds that produced a signature"
+    ))
+    .unwrap();
+    pub static ref SIGN_ROUNDS_FAILED: IntCounter = register_int_counter!(opts!(
+        "stacks_signer_sign_rounds_failed_total",
+        "Total number of signing rounds that ended in an error"
+    ))
+    .unwrap();
+    pub static ref OPERATION_DURATION_HISTOGRAM: Histogram = register_histogram!(histogram_opts!(
+        "stacks_signer_operation_duration_seconds",
+        "Time (seconds) the signer spent with a DKG or signing operation in progress",
+        vec![0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0]
+    ))
+    .unwrap();
+    pub static ref BLOCK_REJECTIONS: IntCounterVec = register_int_counter_vec!(
+        opts!(
+            "stacks_signer_block_rejections_total",
+            "Total number of blocks rejected by this signer"
+        ),
+        &["reject_code"]
+    )
+    .unwrap();
+}
+
+/// The label to count a block rejection under
+pub fn reject_code_label(reject_code: &RejectCode) -> &'static str {
+    match reject_code {
+        RejectCode::ValidationFailed(_) => "validation_failed",
+        RejectCode::SignedRejection(_) => "signed_rejection",
+        RejectCode::NonceTimeout(_) => "nonce_timeout",
+        RejectCode::InsufficientSigners(_) => "insufficient_signers",
+        RejectCode::AggregatorError(_) => "aggregator_error",
+        RejectCode::MissingTransactions(_) => "missing_transactions",
+        RejectCode::ConnectivityIssues => "connectivity_issues",
+        RejectCode::NoAggregateKey => "no_aggregate_key",
+        RejectCode::MissingTransactionIds(_) => "missing_transaction_ids",
+        RejectCode::InvalidMerkleRoot => "invalid_merkle_root",
+    }
+}
+
+/// Bind to the given address and serve the metrics endpoint until the listener fails
+pub fn start_serving_prometheus_metrics(bind_address: SocketAddr) -> Result<(), MetricsError> {
+    let listener = TcpListener::bind(bind_address).map_err(|_| {
+        warn!("Prometheus monitoring: unable to bind address, will not spawn prometheus endpoint service.");
+        MetricsError::AlreadyBound
+    })?;
+    let local_addr = listener.local_addr().map_err(|_| {
+        warn!("Prometheus monitoring: unable to get local bind address, will not spawn prometheus endpoint service.");
+        MetricsError::UnableToGetAddress
+    })?;
+    info!("Prometheus monitoring: server listening on http://{local_addr}");
+    serve(listener, Duration::from_millis(STREAM_TIMEOUT_MS));
+    Ok(())
+}
+
+/// Answer every connection on the listener with the text exposition of all registered metrics.
+/// Connections are served one at a time, so a scraper that stalls for longer than
+/// `stream_timeout` is dropped rather than left to block the others.
+fn serve(listener: TcpListener, stream_timeout: Duration) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                error!("Prometheus monitoring: unable to open socket and serve metrics - {err:?}");
+                continue;
+            }
+        };
+        if let Err(err) = accept(stream, stream_timeout) {
+            error!("Prometheus monitoring: failed to serve metrics - {err:?}");
+        }
+    }
+}
+
+fn accept(mut stream: TcpStream, timeout: Duration) -> std::io::Result<()> {
+    debug!("Handle Prometheus polling ({})", stream.peer_addr()?);
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    // Every request gets the same answer, so just read up to the end of the request head
+    let mut request = vec![];
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n")
+        && request.len() < MAX_REQUEST_HEAD_LEN
+    {
+        let num_read = stream.read(&mut buf)?;
+        if num_read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..num_read]);
+    }
+
+    let encoder = TextEncoder::new();
+    let metric_families = gather();
+    let mut body = vec![];
+    encoder
+        .encode(&metric_families, &mut body)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("{e:?}")))?;
+
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        encoder.format_type(),
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn metrics_should_be_scraped_in_text_format() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Duration::from_millis(STREAM_TIMEOUT_MS)));
+
+        crate::metrics::increment_dkg_rounds_started();
+        crate::metrics::increment_block_rejections(&RejectCode::InvalidMerkleRoot);
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("# TYPE stacks_signer_dkg_rounds_started_total counter"));
+        // other tests may have started DKG rounds too, so only check that ours was counted
+        let dkg_rounds_started: u64 = response
+            .lines()
+            .find_map(|line| line.strip_prefix("stacks_signer_dkg_rounds_started_total "))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(dkg_rounds_started >= 1);
+        assert!(response.lines().any(|line| line.starts_with(
+            "stacks_signer_block_rejections_total{reject_code=\"invalid_merkle_root\"} "
+        )));
+    }
+
+    #[test]
+    fn stalled_scraper_should_not_block_others() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Duration::from_millis(100)));
+
+        // connects, but never sends its request
+        let _stalled = TcpStream::connect(addr).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
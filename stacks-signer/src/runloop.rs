@@ -24,6 +24,7 @@ use hashbrown::HashMap;
 use libsigner::{SignerEntries, SignerEvent, SignerRunLoop};
 use slog::{slog_debug, slog_error, slog_info, slog_warn};
 use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::util::get_epoch_time_secs;
 use stacks_common::{debug, error, info, warn};
 use wsts::state_machine::OperationResult;
 
@@ -31,6 +32,15 @@ use crate::client::{retry_with_exponential_backoff, ClientError, StacksClient};
 use crate::config::{GlobalConfig, SignerConfig};
 use crate::signer::{Command as SignerCommand, Signer, SignerSlotID};
 
+/// How long a block the signer has already accepted or rejected is kept in the signer db
+pub const EXPIRED_BLOCK_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
+/// How far below the highest stored block a block is kept in the signer db, decided or not
+pub const STALE_BLOCK_MAX_DEPTH: u64 = 10_000;
+
+/// How long a logged packet is kept in the signer db's packet log
+pub const PACKET_LOG_MAX_AGE_SECS: u64 = 24 * 60 * 60;
+
 /// Which operation to perform
 #[derive(PartialEq, Clone, Debug)]
 pub struct RunLoopCommand {
@@ -208,12 +218,25 @@ impl RunLoop {
             node_host: self.config.node_host.to_string(),
             mainnet: self.config.network.is_mainnet(),
             dkg_end_timeout: self.config.dkg_end_timeout,
+            dkg_retry_timeout: self.config.dkg_retry_timeout,
             dkg_private_timeout: self.config.dkg_private_timeout,
             dkg_public_timeout: self.config.dkg_public_timeout,
             nonce_timeout: self.config.nonce_timeout,
             sign_timeout: self.config.sign_timeout,
             tx_fee_ustx: self.config.tx_fee_ustx,
             db_path: self.config.db_path.clone(),
+            compact_rejections: self.config.compact_rejections,
+            log_packets: self.config.log_packets,
+            compress_block_info: self.config.compress_block_info,
+            db_busy_timeout: self.config.db_busy_timeout,
+            nonce_cache_ttl: self.config.nonce_cache_ttl,
+            idle_poll_interval: self.config.idle_poll_interval,
+            active_poll_interval: self.config.active_poll_interval,
+            signing_threshold_ratio: self.config.signing_threshold_ratio,
+            dkg_threshold_ratio: self.config.dkg_threshold_ratio,
+            max_pending_commands: self.config.max_pending_commands,
+            max_block_size: self.config.max_block_size,
+            transient_reject_codes: self.config.transient_reject_codes.clone(),
         })
     }
 
@@ -262,6 +285,9 @@ impl RunLoop {
             self.refresh_signer_config(current_reward_cycle.saturating_add(1));
         }
         self.current_reward_cycle_info = Some(reward_cycle_info);
+        for signer in self.stacks_signers.values_mut() {
+            signer.recover_commands(&self.stacks_client, current_reward_cycle);
+        }
         if self.stacks_signers.is_empty() {
             self.state = State::NoRegisteredSigners;
         } else {
@@ -328,7 +354,13 @@ impl SignerRunLoop<Vec<OperationResult>, RunLoopCommand> for RunLoop {
     }
 
     fn get_event_timeout(&self) -> Duration {
-        self.config.event_timeout
+        self.stacks_signers
+            .values()
+            .map(Signer::poll_interval)
+            .min()
+            .map_or(self.config.event_timeout, |interval| {
+                interval.min(self.config.event_timeout)
+            })
     }
 
     fn run_one_pass(
@@ -357,6 +389,12 @@ impl SignerRunLoop<Vec<OperationResult>, RunLoopCommand> for RunLoop {
                 error!("Failed to refresh signer runloop: {e}.");
                 warn!("Signer may have an outdated view of the network.");
             }
+            let now = get_epoch_time_secs();
+            for signer in self.stacks_signers.values_mut() {
+                signer.gc_expired_blocks(EXPIRED_BLOCK_MAX_AGE_SECS, now);
+                signer.gc_stale_blocks(STALE_BLOCK_MAX_DEPTH);
+                signer.gc_packet_log(PACKET_LOG_MAX_AGE_SECS, now);
+            }
         }
         let current_reward_cycle = self
             .current_reward_cycle_info
@@ -389,16 +427,21 @@ impl SignerRunLoop<Vec<OperationResult>, RunLoopCommand> for RunLoop {
                 continue;
             }
 
-            if signer.approved_aggregate_public_key.is_none() {
+            if signer.approved_aggregate_public_key.is_none() && signer.dkg_update_due() {
                 if let Err(e) = retry_with_exponential_backoff(|| {
                     signer
                         .update_dkg(&self.stacks_client, current_reward_cycle)
                         .map_err(backoff::Error::transient)
                 }) {
                     error!("{signer}: failed to update DKG: {e}");
+                } else {
+                    // The mismatch is logged by the check itself
+                    let _ = signer.verify_key_consistency();
                 }
             }
             signer.refresh_coordinator();
+            signer.flush_pending_responses();
+            signer.resubmit_transient_rejections(&self.stacks_client);
             if let Err(e) = signer.process_event(
                 &self.stacks_client,
                 event.as_ref(),
@@ -422,7 +465,7 @@ impl SignerRunLoop<Vec<OperationResult>, RunLoopCommand> for RunLoop {
                             .signers
                             .get(&signer.signer_id)
                     );
-                    signer.commands.push_back(command.command);
+                    signer.queue_command(command.command);
                 }
             }
             // After processing event, run the next command for each signer
@@ -433,10 +476,17 @@ impl SignerRunLoop<Vec<OperationResult>, RunLoopCommand> for RunLoop {
 }
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use blockstack_lib::chainstate::stacks::boot::NakamotoSignerEntry;
-    use libsigner::SignerEntries;
+    use libsigner::{SignerEntries, SignerRunLoop};
     use stacks_common::types::chainstate::{StacksPrivateKey, StacksPublicKey};
 
+    use super::RunLoop;
+    use crate::client::tests::generate_signer_config;
+    use crate::config::GlobalConfig;
+    use crate::signer::Signer;
+
     #[test]
     fn parse_nakamoto_signer_entries_test() {
         let nmb_signers = 10;
@@ -462,4 +512,19 @@ mod tests {
             (0..nmb_signers).map(|id| id as u32).collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn event_timeout_should_not_exceed_configured_timeout() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let mut runloop = RunLoop::from(config.clone());
+        assert_eq!(runloop.get_event_timeout(), config.event_timeout);
+
+        let signer = Signer::from(generate_signer_config(&config, 5, 20));
+        let poll_interval = signer.poll_interval();
+        runloop.stacks_signers.insert(0, signer);
+        assert_eq!(runloop.get_event_timeout(), poll_interval);
+
+        runloop.set_event_timeout(Duration::from_millis(100));
+        assert_eq!(runloop.get_event_timeout(), Duration::from_millis(100));
+    }
 }
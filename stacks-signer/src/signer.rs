@@ -13,17 +13,18 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use blockstack_lib::chainstate::burn::ConsensusHashExtensions;
 use blockstack_lib::chainstate::nakamoto::signer_set::NakamotoSigners;
 use blockstack_lib::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockVote};
 use blockstack_lib::chainstate::stacks::boot::SIGNERS_VOTING_FUNCTION_NAME;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
-use blockstack_lib::net::api::postblock_proposal::BlockValidateResponse;
+use blockstack_lib::net::api::postblock_proposal::{BlockValidateResponse, ValidateRejectCode};
 use hashbrown::HashSet;
 use libsigner::{
     BlockProposalSigners, BlockRejection, BlockResponse, MessageSlotID, RejectCode, SignerEvent,
@@ -34,7 +35,8 @@ use slog::{slog_debug, slog_error, slog_info, slog_warn};
 use stacks_common::codec::{read_next, StacksMessageCodec};
 use stacks_common::types::chainstate::{ConsensusHash, StacksAddress};
 use stacks_common::types::StacksEpochId;
-use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::hash::{MerkleTree, Sha512Trunc256Sum};
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 use stacks_common::{debug, error, info, warn};
 use wsts::common::{MerkleRoot, Signature};
 use wsts::curve::keys::PublicKey;
@@ -46,14 +48,36 @@ use wsts::state_machine::coordinator::{
 };
 use wsts::state_machine::signer::Signer as SignerStateMachine;
 use wsts::state_machine::{OperationResult, SignError};
+use wsts::taproot::SchnorrProof;
 use wsts::traits::Signer as _;
 use wsts::v2;
 
 use crate::client::{retry_with_exponential_backoff, ClientError, StackerDB, StacksClient};
 use crate::config::SignerConfig;
 use crate::coordinator::CoordinatorSelector;
+use crate::metrics;
 use crate::signerdb::SignerDb;
 
+/// How long to wait before resending a block response that failed to send, in milliseconds.
+/// The wait doubles after each further failed attempt.
+pub const PENDING_RESPONSE_RETRY_INTERVAL_MS: u64 = 1_000;
+
+/// How many times to try resending a block response before dropping it
+pub const MAX_PENDING_RESPONSE_ATTEMPTS: u32 = 8;
+
+/// How long to wait before resubmitting a block for validation after a second transient
+/// validation failure, in milliseconds. The first resubmission is immediate, and the wait doubles
+/// after each further transient failure.
+pub const TRANSIENT_RESUBMIT_INTERVAL_MS: u64 = 1_000;
+
+/// How many times to resubmit a block whose validation failed with a transient error before
+/// rejecting it
+pub const MAX_TRANSIENT_RESUBMISSIONS: u32 = 5;
+
+/// How long to wait between checks of the DKG state on chain while there is no approved
+/// aggregate key, in milliseconds
+pub const DKG_UPDATE_INTERVAL_MS: u64 = 10_000;
+
 /// The signer StackerDB slot ID, purposefully wrapped to prevent conflation with SignerID
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, PartialOrd, Ord)]
 pub struct SignerSlotID(pub u32);
@@ -64,6 +88,21 @@ impl std::fmt::Display for SignerSlotID {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+/// An error occurred checking the signer's internal state
+pub enum SignerError {
+    /// The approved aggregate key, the coordinator's aggregate key, and the signer's group key disagree
+    #[error("Inconsistent aggregate public keys: approved = {approved}, coordinator = {coordinator:?}, signer = {signer}")]
+    InconsistentAggregateKey {
+        /// The aggregate key approved in the signers voting contract
+        approved: Point,
+        /// The aggregate key set in the coordinator
+        coordinator: Option<Point>,
+        /// The group key stored in the signer state machine
+        signer: Point,
+    },
+}
+
 /// Additional Info about a proposed block
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct BlockInfo {
@@ -72,40 +111,91 @@ pub struct BlockInfo {
     /// Our vote on the block if we have one yet
     pub vote: Option<NakamotoBlockVote>,
     /// Whether the block contents are valid
-    valid: Option<bool>,
+    pub valid: Option<bool>,
     /// The associated packet nonce request if we have one
     nonce_request: Option<NonceRequest>,
     /// Whether this block is already being signed over
     pub signed_over: bool,
+    /// Time at which this signer first saw the block (epoch time in milliseconds)
+    #[serde(default)]
+    pub proposed_at: u64,
+    /// Time in milliseconds between first seeing the block and receiving its validation response
+    #[serde(default)]
+    pub validation_latency_ms: Option<u64>,
+    /// Time at which the block's validity was determined (epoch time in seconds)
+    #[serde(default)]
+    pub validated_at: Option<u64>,
+    /// Time at which this signer cast its vote on the block (epoch time in seconds)
+    #[serde(default)]
+    pub voted_at: Option<u64>,
 }
 
 impl BlockInfo {
     /// Create a new BlockInfo
-    pub const fn new(block: NakamotoBlock) -> Self {
+    pub fn new(block: NakamotoBlock) -> Self {
         Self {
             block,
             vote: None,
             valid: None,
             nonce_request: None,
             signed_over: false,
+            proposed_at: get_epoch_time_ms() as u64,
+            validation_latency_ms: None,
+            validated_at: None,
+            voted_at: None,
         }
     }
 
     /// Create a new BlockInfo with an associated nonce request packet
-    pub const fn new_with_request(block: NakamotoBlock, nonce_request: NonceRequest) -> Self {
+    pub fn new_with_request(block: NakamotoBlock, nonce_request: NonceRequest) -> Self {
         Self {
             block,
             vote: None,
             valid: None,
             nonce_request: Some(nonce_request),
             signed_over: true,
+            proposed_at: get_epoch_time_ms() as u64,
+            validation_latency_ms: None,
+            validated_at: None,
+            voted_at: None,
         }
     }
 
+    /// Whether this signer has already accepted or rejected the block
+    pub fn is_decided(&self) -> bool {
+        self.vote.is_some() || self.valid == Some(false)
+    }
+
+    /// Record the validity of the block, when it was determined and the latency of its validation
+    pub(crate) fn set_validated(&mut self, valid: bool) {
+        self.valid = Some(valid);
+        self.validated_at = Some(get_epoch_time_secs());
+        self.validation_latency_ms =
+            Some((get_epoch_time_ms() as u64).saturating_sub(self.proposed_at));
+    }
+
+    /// Record this signer's vote on the block and when it was cast. The vote answers any
+    /// cached nonce request, which is dropped.
+    pub(crate) fn set_vote(&mut self, vote: NakamotoBlockVote) {
+        self.vote = Some(vote);
+        self.voted_at = Some(get_epoch_time_secs());
+        self.nonce_request = None;
+    }
+
     /// Return the block's signer signature hash
     pub fn signer_signature_hash(&self) -> Sha512Trunc256Sum {
         self.block.header.signer_signature_hash()
     }
+
+    /// Return the exact message signed over for this block: the serialized block a signing
+    /// round is started with, or, once this signer has voted, its serialized vote, which
+    /// replaces the block in the nonce requests of the subsequent rounds
+    pub fn signed_message_bytes(&self) -> Vec<u8> {
+        match self.vote {
+            Some(ref vote) => vote.serialize_to_vec(),
+            None => self.block.serialize_to_vec(),
+        }
+    }
 }
 
 /// Which signer operation to perform
@@ -125,7 +215,7 @@ pub enum Command {
 }
 
 /// The Signer state
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub enum State {
     /// The signer is idle, waiting for messages and commands
     Idle,
@@ -133,6 +223,19 @@ pub enum State {
     OperationInProgress,
 }
 
+/// Which of the signer sets known to a signer an address belongs to
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SignerSetMembership {
+    /// The address is a signer in the signer's reward cycle only
+    Current,
+    /// The address is a signer in the next reward cycle only
+    Next,
+    /// The address is a signer in both reward cycles
+    Both,
+    /// The address is not a signer in either reward cycle
+    None,
+}
+
 /// The stacks signer registered for the reward cycle
 pub struct Signer {
     /// The coordinator for inbound messages for a specific reward cycle
@@ -161,6 +264,10 @@ pub struct Signer {
     pub reward_cycle: u64,
     /// The tx fee in uSTX to use if the epoch is pre Nakamoto (Epoch 3.0)
     pub tx_fee_ustx: u64,
+    /// Whether to only send txids in missing transaction rejections that exceed the chunk limit
+    pub compact_rejections: bool,
+    /// Whether to record every inbound packet in the signer database
+    pub log_packets: bool,
     /// The coordinator info for the signer
     pub coordinator_selector: CoordinatorSelector,
     /// The approved key registered to the contract
@@ -171,6 +278,99 @@ pub struct Signer {
     pub db_path: PathBuf,
     /// SignerDB for state management
     pub signer_db: SignerDb,
+    /// The number of times the coordinator changed while an operation was in progress
+    pub coordinator_changes_mid_round: u64,
+    /// When this signer last queued a DKG command as the coordinator
+    pub last_dkg_command_time: Option<Instant>,
+    /// How long to wait for a DKG vote to reach its threshold before retriggering DKG
+    pub dkg_retry_timeout: Duration,
+    /// When the signer's current operation started, if one is in progress
+    pub operation_start_time: Option<Instant>,
+    /// The maximum number of commands to queue before dropping the oldest non-DKG ones
+    pub max_pending_commands: usize,
+    /// The maximum serialized size, in bytes, of a block embedded in a nonce request
+    pub max_block_size: usize,
+    /// The block validation rejection codes that are resubmitted for validation instead of rejected
+    pub transient_reject_codes: Vec<ValidateRejectCode>,
+    /// How long to reuse an account nonce fetched from the stacks node
+    pub nonce_cache_ttl: Duration,
+    /// How long to wait for an event while idle with no queued commands
+    pub idle_poll_interval: Duration,
+    /// How long to wait for an event while an operation or command is pending
+    pub active_poll_interval: Duration,
+    /// The account nonces fetched from the stacks node, and when they were fetched
+    account_nonce_cache: RefCell<std::collections::HashMap<StacksAddress, (u64, Instant)>>,
+    /// How many times the pending block response failed to be resent
+    pending_response_attempts: u32,
+    /// When to next try resending the pending block response, if one may be pending
+    next_pending_response_flush: Option<Instant>,
+    /// The blocks resubmitted for validation after a transient validation failure, keyed by
+    /// signer signature hash: how many times each was resubmitted, and when to resubmit it
+    /// next if its resubmission is still due
+    transient_resubmissions: std::collections::HashMap<Sha512Trunc256Sum, (u32, Option<Instant>)>,
+    /// When to next check the DKG state on chain
+    next_dkg_update: Instant,
+}
+
+/// A snapshot of a signer's metrics
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SignerMetrics {
+    /// The number of times the coordinator changed while an operation was in progress
+    pub coordinator_changes_mid_round: u64,
+}
+
+/// The kind of a queued command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandKind {
+    /// A DKG command
+    Dkg,
+    /// A sign command
+    Sign,
+}
+
+impl From<&Command> for CommandKind {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::Dkg => CommandKind::Dkg,
+            Command::Sign { .. } => CommandKind::Sign,
+        }
+    }
+}
+
+/// A serializable snapshot of a signer's in-memory state, for comparing signers that disagree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignerSnapshot {
+    /// The state of the signer
+    pub state: State,
+    /// The reward cycle the signer belongs to
+    pub reward_cycle: u64,
+    /// The signer id
+    pub signer_id: u32,
+    /// The approved key registered to the contract
+    pub approved_aggregate_public_key: Option<Point>,
+    /// The kinds of the queued commands, in queue order
+    pub commands: Vec<CommandKind>,
+    /// The id of the current coordinator
+    pub coordinator_id: u32,
+    /// The signer signature hashes of the blocks queued to be signed, in queue order
+    pub queued_sign_blocks: Vec<Sha512Trunc256Sum>,
+}
+
+/// A serializable summary of a signer's health, reported on status checks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerStatus {
+    /// The state of the signer
+    pub state: State,
+    /// The reward cycle the signer belongs to
+    pub reward_cycle: u64,
+    /// Whether an aggregate key has been approved for the reward cycle
+    pub has_approved_aggregate_key: bool,
+    /// The id of the current coordinator
+    pub coordinator_id: u32,
+    /// The number of queued commands
+    pub pending_commands: usize,
+    /// How long the current operation has been in progress, in milliseconds, if one is
+    pub operation_elapsed_ms: Option<u64>,
 }
 
 impl std::fmt::Display for Signer {
@@ -205,6 +405,81 @@ impl Signer {
             return (Some(selected.0), selected.1);
         }
     }
+
+    /// Whether this signer is the current coordinator
+    fn is_coordinator(&self, current_reward_cycle: u64) -> bool {
+        self.get_coordinator(current_reward_cycle).0 == Some(self.signer_id)
+    }
+
+    /// Get the `signer_signature_hash` of every block this signer is signing over and has not
+    /// rejected. Blocks it voted to accept are still in flight until their signing round ends.
+    pub fn blocks_in_flight(&self) -> Result<Vec<Sha512Trunc256Sum>, ClientError> {
+        Ok(self
+            .signer_db
+            .get_blocks_for_reward_cycle(self.reward_cycle)?
+            .iter()
+            .filter(|block_info| {
+                block_info.signed_over
+                    && block_info.valid != Some(false)
+                    && !block_info.vote.as_ref().map_or(false, |vote| vote.rejected)
+            })
+            .map(|block_info| block_info.signer_signature_hash())
+            .collect())
+    }
+
+    /// Compute how many signers can be offline while DKG can still reach its threshold.
+    /// This is exact for the reward cycle's key id distribution, and assumes the worst case:
+    /// that the signers holding the most key ids are the ones offline.
+    pub fn max_offline_signers(&self) -> u32 {
+        let config = self.coordinator.get_config();
+        Self::max_offline_signers_for(
+            config.signer_key_ids.values().map(|key_ids| key_ids.len()),
+            config.dkg_threshold,
+        )
+    }
+
+    /// Compute how many of the signers with the given key id counts can be offline while the
+    /// remaining signers still hold at least `threshold` key ids
+    fn max_offline_signers_for(key_counts: impl Iterator<Item = usize>, threshold: u32) -> u32 {
+        let mut key_counts: Vec<_> = key_counts.collect();
+        key_counts.sort_unstable_by(|a, b| b.cmp(a));
+        let mut online_keys: usize = key_counts.iter().sum();
+        let mut max_offline = 0;
+        for key_count in key_counts {
+            online_keys = online_keys.saturating_sub(key_count);
+            if online_keys < threshold as usize {
+                break;
+            }
+            max_offline += 1;
+        }
+        max_offline
+    }
+
+    /// Check whether the given online signers together hold enough key ids to meet the
+    /// signing threshold, i.e. whether it is worth attempting a signing round
+    pub fn effective_online_threshold(&self, online_signer_ids: &HashSet<u32>) -> bool {
+        let config = self.coordinator.get_config();
+        let online_weight: usize = online_signer_ids
+            .iter()
+            .filter_map(|signer_id| config.signer_key_ids.get(signer_id))
+            .map(|key_ids| key_ids.len())
+            .sum();
+        online_weight >= config.threshold as usize
+    }
+
+    /// Check whether the given address belongs to this signer's reward cycle signer set,
+    /// the next reward cycle's signer set, or both
+    pub fn is_signer_address(&self, addr: &StacksAddress) -> SignerSetMembership {
+        match (
+            self.signer_addresses.contains(addr),
+            self.next_signer_addresses.contains(addr),
+        ) {
+            (true, true) => SignerSetMembership::Both,
+            (true, false) => SignerSetMembership::Current,
+            (false, true) => SignerSetMembership::Next,
+            (false, false) => SignerSetMembership::None,
+        }
+    }
 }
 
 impl From<SignerConfig> for Signer {
@@ -221,11 +496,11 @@ impl From<SignerConfig> for Signer {
             .expect("FATAL: Too many key ids to fit in a u32");
         let threshold = signer_config
             .signer_entries
-            .get_signing_threshold()
+            .get_threshold(signer_config.signing_threshold_ratio)
             .expect("FATAL: Too many key ids to fit in a u32");
         let dkg_threshold = signer_config
             .signer_entries
-            .get_dkg_threshold()
+            .get_threshold(signer_config.dkg_threshold_ratio)
             .expect("FATAL: Too many key ids to fit in a u32");
 
         let coordinator_config = CoordinatorConfig {
@@ -253,8 +528,12 @@ impl From<SignerConfig> for Signer {
             signer_config.signer_id,
             coordinator_selector.get_coordinator().0
         );
-        let signer_db =
+        let mut signer_db =
             SignerDb::new(&signer_config.db_path).expect("Failed to connect to signer Db");
+        signer_db.set_compression(signer_config.compress_block_info);
+        signer_db
+            .set_busy_timeout(signer_config.db_busy_timeout)
+            .expect("Failed to set signer Db busy timeout");
 
         let mut state_machine = SignerStateMachine::new(
             threshold,
@@ -270,11 +549,19 @@ impl From<SignerConfig> for Signer {
             .get_signer_state(signer_config.reward_cycle)
             .expect("Failed to load signer state")
         {
-            debug!(
-                "Reward cycle #{} Signer #{}: Loading signer",
-                signer_config.reward_cycle, signer_config.signer_id
-            );
-            state_machine.signer = v2::Signer::load(&state);
+            // The signer set may have changed since the state was persisted
+            if state.num_keys != num_keys || state.num_parties != num_signers {
+                warn!(
+                    "Reward cycle #{} Signer #{}: Discarding persisted signer state with {} keys and {} parties, which does not match the configured {num_keys} keys and {num_signers} signers",
+                    signer_config.reward_cycle, signer_config.signer_id, state.num_keys, state.num_parties
+                );
+            } else {
+                debug!(
+                    "Reward cycle #{} Signer #{}: Loading signer",
+                    signer_config.reward_cycle, signer_config.signer_id
+                );
+                state_machine.signer = v2::Signer::load(&state);
+            }
         }
 
         Self {
@@ -295,11 +582,29 @@ impl From<SignerConfig> for Signer {
             next_signer_addresses: vec![],
             reward_cycle: signer_config.reward_cycle,
             tx_fee_ustx: signer_config.tx_fee_ustx,
+            compact_rejections: signer_config.compact_rejections,
+            log_packets: signer_config.log_packets,
             coordinator_selector,
             approved_aggregate_public_key: None,
             miner_key: None,
             db_path: signer_config.db_path,
             signer_db,
+            coordinator_changes_mid_round: 0,
+            last_dkg_command_time: None,
+            dkg_retry_timeout: signer_config.dkg_retry_timeout,
+            operation_start_time: None,
+            max_pending_commands: signer_config.max_pending_commands,
+            max_block_size: signer_config.max_block_size,
+            transient_reject_codes: signer_config.transient_reject_codes,
+            nonce_cache_ttl: signer_config.nonce_cache_ttl,
+            idle_poll_interval: signer_config.idle_poll_interval,
+            active_poll_interval: signer_config.active_poll_interval,
+            account_nonce_cache: RefCell::new(std::collections::HashMap::new()),
+            pending_response_attempts: 0,
+            // A response may have been left pending by a previous run
+            next_pending_response_flush: Some(Instant::now()),
+            transient_resubmissions: std::collections::HashMap::new(),
+            next_dkg_update: Instant::now(),
         }
     }
 }
@@ -320,8 +625,87 @@ impl Signer {
                 "updated_coordinator_id" => {updated_coordinator_id},
                 "pox_consensus_hash" => %pox_consensus_hash
             );
-            self.coordinator.state = CoordinatorState::Idle;
-            self.state = State::Idle;
+            self.reset_for_new_coordinator(old_coordinator_id, updated_coordinator_id);
+        }
+    }
+
+    /// Reset the signer state after the coordinator changed, keeping track of changes that
+    /// interrupted an operation in progress
+    fn reset_for_new_coordinator(&mut self, old_coordinator_id: u32, updated_coordinator_id: u32) {
+        if self.state == State::OperationInProgress {
+            self.coordinator_changes_mid_round =
+                self.coordinator_changes_mid_round.saturating_add(1);
+            warn!(
+                "{self}: Coordinator changed during an operation in progress.";
+                "old_coordinator_id" => {old_coordinator_id},
+                "updated_coordinator_id" => {updated_coordinator_id},
+                "coordinator_changes_mid_round" => self.coordinator_changes_mid_round,
+            );
+        }
+        self.coordinator.state = CoordinatorState::Idle;
+        self.state = State::Idle;
+        self.record_operation_duration();
+    }
+
+    /// Take a snapshot of the signer's metrics
+    pub fn metrics(&self) -> SignerMetrics {
+        SignerMetrics {
+            coordinator_changes_mid_round: self.coordinator_changes_mid_round,
+        }
+    }
+
+    /// Take a snapshot of the signer's in-memory state
+    pub fn debug_snapshot(&self) -> SignerSnapshot {
+        SignerSnapshot {
+            state: self.state.clone(),
+            reward_cycle: self.reward_cycle,
+            signer_id: self.signer_id,
+            approved_aggregate_public_key: self.approved_aggregate_public_key,
+            commands: self.commands.iter().map(CommandKind::from).collect(),
+            coordinator_id: self.coordinator_selector.get_coordinator().0,
+            queued_sign_blocks: self
+                .commands
+                .iter()
+                .filter_map(|command| match command {
+                    Command::Sign { block, .. } => Some(block.header.signer_signature_hash()),
+                    Command::Dkg => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// How long the event loop should wait for an event before running another pass for this
+    /// signer: the idle interval if it is idle with no queued commands, else the active interval
+    pub fn poll_interval(&self) -> Duration {
+        if self.state == State::Idle && self.commands.is_empty() {
+            self.idle_poll_interval
+        } else {
+            self.active_poll_interval
+        }
+    }
+
+    /// Whether the DKG state on chain is due to be checked again. If so, the next check is
+    /// scheduled `DKG_UPDATE_INTERVAL_MS` from now.
+    pub fn dkg_update_due(&mut self) -> bool {
+        let now = Instant::now();
+        if now < self.next_dkg_update {
+            return false;
+        }
+        self.next_dkg_update = now + Duration::from_millis(DKG_UPDATE_INTERVAL_MS);
+        true
+    }
+
+    /// Summarize the signer's health for a status check
+    pub fn status(&self) -> SignerStatus {
+        SignerStatus {
+            state: self.state.clone(),
+            reward_cycle: self.reward_cycle,
+            has_approved_aggregate_key: self.approved_aggregate_public_key.is_some(),
+            coordinator_id: self.coordinator_selector.get_coordinator().0,
+            pending_commands: self.commands.len(),
+            operation_elapsed_ms: self
+                .operation_start_time
+                .map(|start| u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX)),
         }
     }
 
@@ -329,16 +713,30 @@ impl Signer {
     fn finish_operation(&mut self) {
         self.state = State::Idle;
         self.coordinator_selector.last_message_time = None;
+        self.record_operation_duration();
     }
 
     /// Update operation
     fn update_operation(&mut self) {
         self.state = State::OperationInProgress;
         self.coordinator_selector.last_message_time = Some(Instant::now());
+        self.operation_start_time.get_or_insert_with(Instant::now);
+    }
+
+    /// Record how long the operation that just ended was in progress
+    fn record_operation_duration(&mut self) {
+        if let Some(operation_start_time) = self.operation_start_time.take() {
+            metrics::record_operation_duration(operation_start_time.elapsed());
+        }
     }
 
     /// Execute the given command and update state accordingly
-    fn execute_command(&mut self, stacks_client: &StacksClient, command: &Command) {
+    fn execute_command(
+        &mut self,
+        stacks_client: &StacksClient,
+        command: &Command,
+        current_reward_cycle: u64,
+    ) {
         match command {
             Command::Dkg => {
                 if self.approved_aggregate_public_key.is_some() {
@@ -367,6 +765,7 @@ impl Signer {
                     Ok(msg) => {
                         let ack = self.stackerdb.send_message_with_retry(msg.into());
                         debug!("{self}: ACK: {ack:?}",);
+                        metrics::increment_dkg_rounds_started();
                     }
                     Err(e) => {
                         error!("{self}: Failed to start DKG: {e:?}",);
@@ -379,6 +778,10 @@ impl Signer {
                 is_taproot,
                 merkle_root,
             } => {
+                if !self.is_coordinator(current_reward_cycle) {
+                    debug!("{self}: No longer the coordinator. Ignoring sign command.");
+                    return;
+                }
                 if self.approved_aggregate_public_key.is_none() {
                     debug!("{self}: Cannot sign a block without an approved aggregate public key. Ignore it.");
                     return;
@@ -393,6 +796,25 @@ impl Signer {
                     debug!("{self}: Received a sign command for a block we are already signing over. Ignore it.");
                     return;
                 }
+                match self
+                    .signer_db
+                    .signed_block_at(&block.header.consensus_hash, block.header.chain_length)
+                {
+                    Ok(Some(signed_hash)) if signed_hash != signer_signature_hash => {
+                        warn!("{self}: Received a sign command for a block conflicting with one we already signed over. Ignore it.";
+                            "signer_sighash" => %signer_signature_hash,
+                            "signed_signer_sighash" => %signed_hash,
+                            "block_consensus_hash" => %block.header.consensus_hash,
+                            "block_height" => block.header.chain_length,
+                        );
+                        return;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("{self}: Failed to look up blocks already signed over: {e:?}");
+                        return;
+                    }
+                }
                 info!("{self}: Signing block";
                          "block_consensus_hash" => %block.header.consensus_hash,
                          "block_height" => block.header.chain_length,
@@ -406,6 +828,7 @@ impl Signer {
                     Ok(msg) => {
                         let ack = self.stackerdb.send_message_with_retry(msg.into());
                         debug!("{self}: ACK: {ack:?}",);
+                        metrics::increment_sign_rounds_started();
                         block_info.signed_over = true;
                         self.signer_db
                             .insert_block(self.reward_cycle, &block_info)
@@ -439,7 +862,7 @@ impl Signer {
                     return;
                 }
                 if let Some(command) = self.commands.pop_front() {
-                    self.execute_command(stacks_client, &command);
+                    self.execute_command(stacks_client, &command, current_reward_cycle);
                 } else {
                     debug!("{self}: Nothing to process. Waiting for command...",);
                 }
@@ -479,11 +902,12 @@ impl Signer {
                         return;
                     }
                 };
+                self.transient_resubmissions.remove(&signer_signature_hash);
                 let is_valid = self.verify_block_transactions(stacks_client, &block_info.block);
-                block_info.valid = Some(is_valid);
+                block_info.set_validated(is_valid);
                 self.signer_db
-                    .insert_block(self.reward_cycle, &block_info)
-                    .unwrap_or_else(|_| panic!("{self}: Failed to insert block in DB"));
+                    .set_block_validity(self.reward_cycle, &signer_signature_hash, is_valid)
+                    .unwrap_or_else(|_| panic!("{self}: Failed to update block in DB"));
                 info!(
                     "{self}: Treating block validation for block {} as valid: {:?}",
                     &block_info.block.block_id(),
@@ -508,16 +932,53 @@ impl Signer {
                         return;
                     }
                 };
-                block_info.valid = Some(false);
+                if self
+                    .transient_reject_codes
+                    .contains(&block_validate_reject.reason_code)
+                {
+                    let resubmissions = self
+                        .transient_resubmissions
+                        .get(&signer_signature_hash)
+                        .map_or(0, |(resubmissions, _)| *resubmissions);
+                    if resubmissions < MAX_TRANSIENT_RESUBMISSIONS {
+                        // The node may accept this block later, so don't tell the miner it's invalid
+                        warn!(
+                            "{self}: Stacks node failed to validate block due to a transient error. Resubmitting it for validation...";
+                            "signer_sighash" => %signer_signature_hash,
+                            "reason" => &block_validate_reject.reason,
+                            "reason_code" => ?block_validate_reject.reason_code,
+                            "resubmissions" => resubmissions,
+                        );
+                        let wait_ms = match resubmissions {
+                            0 => 0,
+                            n => TRANSIENT_RESUBMIT_INTERVAL_MS << (n - 1).min(16),
+                        };
+                        self.transient_resubmissions.insert(
+                            signer_signature_hash,
+                            (
+                                resubmissions + 1,
+                                Some(Instant::now() + Duration::from_millis(wait_ms)),
+                            ),
+                        );
+                        self.resubmit_transient_rejections(stacks_client);
+                        return;
+                    }
+                    warn!(
+                        "{self}: Stacks node failed to validate block due to a transient error after {resubmissions} resubmissions. Rejecting it...";
+                        "signer_sighash" => %signer_signature_hash,
+                    );
+                }
+                self.transient_resubmissions.remove(&signer_signature_hash);
+                block_info.set_validated(false);
+                self.signer_db
+                    .set_block_validity(self.reward_cycle, &signer_signature_hash, false)
+                    .unwrap_or_else(|_| panic!("{self}: Failed to update block in DB"));
                 // Submit a rejection response to the .signers contract for miners
                 // to observe so they know to send another block and to prove signers are doing work);
                 warn!("{self}: Broadcasting a block rejection due to stacks node validation failure...");
-                if let Err(e) = self
-                    .stackerdb
-                    .send_message_with_retry(block_validate_reject.clone().into())
-                {
-                    warn!("{self}: Failed to send block rejection to stacker-db: {e:?}",);
-                }
+                self.send_block_response(BlockResponse::Rejected(
+                    block_validate_reject.clone().into(),
+                ));
                 block_info
             }
         };
@@ -525,6 +986,11 @@ impl Signer {
             debug!("{self}: Received a block validate response from the stacks node for a block we already received a nonce request for. Responding to the nonce request...");
             // We have received validation from the stacks node. Determine our vote and update the request message
             self.determine_vote(&mut block_info, &mut nonce_request);
+            if let Some(vote) = block_info.vote.as_ref() {
+                self.signer_db
+                    .set_block_vote(self.reward_cycle, &block_info.signer_signature_hash(), vote)
+                    .unwrap_or_else(|_| panic!("{self}: Failed to update block in DB"));
+            }
             // Send the nonce request through with our vote
             let packet = Packet {
                 msg: Message::NonceRequest(nonce_request),
@@ -542,7 +1008,7 @@ impl Signer {
                     "signer_sighash" => %block_info.block.header.signer_signature_hash(),
                     "block_hash" => %block_info.block.header.block_hash(),
                 );
-                self.commands.push_back(Command::Sign {
+                self.queue_command(Command::Sign {
                     block: block_info.block.clone(),
                     is_taproot: false,
                     merkle_root: None,
@@ -557,9 +1023,6 @@ impl Signer {
                 );
             }
         }
-        self.signer_db
-            .insert_block(self.reward_cycle, &block_info)
-            .unwrap_or_else(|_| panic!("{self}: Failed to insert block in DB"));
     }
 
     /// Handle signer messages submitted to signers stackerdb
@@ -570,6 +1033,17 @@ impl Signer {
         messages: &[SignerMessage],
         current_reward_cycle: u64,
     ) {
+        if self.log_packets {
+            for message in messages {
+                let SignerMessage::Packet(packet) = message else {
+                    continue;
+                };
+                if let Err(e) = self.signer_db.insert_packet(packet) {
+                    warn!("{self}: Failed to record packet in the packet log: {e:?}");
+                }
+            }
+        }
+
         let coordinator_pubkey = self.get_coordinator(current_reward_cycle).1;
         let packets: Vec<Packet> = messages
             .iter()
@@ -592,6 +1066,7 @@ impl Signer {
         stacks_client: &StacksClient,
         proposals: &[BlockProposalSigners],
     ) {
+        let mut new_blocks: Vec<BlockInfo> = Vec::with_capacity(proposals.len());
         for proposal in proposals {
             if proposal.reward_cycle != self.reward_cycle {
                 debug!(
@@ -602,6 +1077,15 @@ impl Signer {
                 continue;
             }
             let sig_hash = proposal.block.header.signer_signature_hash();
+            if new_blocks
+                .iter()
+                .any(|block_info| block_info.signer_signature_hash() == sig_hash)
+            {
+                debug!("{self}: Received duplicate proposal for block, ignoring.";
+                    "signer_sighash" => %sig_hash,
+                );
+                continue;
+            }
             match self.signer_db.block_lookup(self.reward_cycle, &sig_hash) {
                 Ok(Some(block)) => {
                     debug!(
@@ -620,12 +1104,14 @@ impl Signer {
                     continue;
                 }
                 Ok(None) => {
+                    if let Some(reject_code) = self.check_proposed_block(&proposal.block) {
+                        let mut block_info = BlockInfo::new(proposal.block.clone());
+                        self.reject_block(&mut block_info, reject_code);
+                        new_blocks.push(block_info);
+                        continue;
+                    }
                     // Store the block in our cache
-                    self.signer_db
-                        .insert_block(self.reward_cycle, &BlockInfo::new(proposal.block.clone()))
-                        .unwrap_or_else(|e| {
-                            error!("{self}: Failed to insert block in DB: {e:?}");
-                        });
+                    new_blocks.push(BlockInfo::new(proposal.block.clone()));
                     // Submit the block for validation
                     stacks_client
                         .submit_block_for_validation_with_retry(proposal.block.clone())
@@ -641,6 +1127,14 @@ impl Signer {
                 }
             }
         }
+        if new_blocks.is_empty() {
+            return;
+        }
+        self.signer_db
+            .insert_blocks(self.reward_cycle, &new_blocks)
+            .unwrap_or_else(|e| {
+                error!("{self}: Failed to insert blocks in DB: {e:?}");
+            });
     }
 
     /// Process inbound packets as both a signer and a coordinator
@@ -751,6 +1245,14 @@ impl Signer {
         stacks_client: &StacksClient,
         nonce_request: &mut NonceRequest,
     ) -> Option<BlockInfo> {
+        if nonce_request.message.len() > self.max_block_size {
+            // Do not even try to parse an oversized block
+            warn!("{self}: Received a nonce request with a message larger than the max block size. Reject it.";
+                "message_len" => nonce_request.message.len(),
+                "max_block_size" => self.max_block_size,
+            );
+            return None;
+        }
         let Some(block) =
             NakamotoBlock::consensus_deserialize(&mut nonce_request.message.as_slice()).ok()
         else {
@@ -768,6 +1270,12 @@ impl Signer {
                 "{self}: We have received a block sign request for a block we have not seen before. Cache the nonce request and submit the block for validation...";
                 "signer_sighash" => %block.header.signer_signature_hash(),
             );
+            if let Some(reject_code) = self.check_proposed_block(&block) {
+                let mut block_info = BlockInfo::new(block);
+                self.reject_block(&mut block_info, reject_code);
+                self.determine_vote(&mut block_info, nonce_request);
+                return Some(block_info);
+            }
             let block_info = BlockInfo::new_with_request(block.clone(), nonce_request.clone());
             stacks_client
                 .submit_block_for_validation_with_retry(block)
@@ -777,17 +1285,71 @@ impl Signer {
             return Some(block_info);
         };
 
-        if block_info.valid.is_none() {
-            // We have not yet received validation from the stacks node. Cache the request and wait for validation
-            debug!("{self}: We have yet to receive validation from the stacks node for a nonce request. Cache the nonce request and wait for block validation...");
-            block_info.nonce_request = Some(nonce_request.clone());
-            return Some(block_info);
+        match block_info.valid {
+            None => {
+                // We have not yet received validation from the stacks node. Cache the request and wait for validation
+                debug!("{self}: We have yet to receive validation from the stacks node for a nonce request. Cache the nonce request and wait for block validation...");
+                block_info.nonce_request = Some(nonce_request.clone());
+            }
+            Some(_) => {
+                // Only a validated block can be voted on
+                self.determine_vote(&mut block_info, nonce_request);
+            }
         }
-
-        self.determine_vote(&mut block_info, nonce_request);
         Some(block_info)
     }
 
+    /// Cheaply check whether a block we have not seen before should be rejected outright,
+    /// before submitting it to the stacks node for validation
+    fn check_proposed_block(&self, block: &NakamotoBlock) -> Option<RejectCode> {
+        if !Self::verify_tx_merkle_root(block) {
+            warn!("{self}: Block's tx merkle root does not match its transactions";
+                "signer_sighash" => %block.header.signer_signature_hash(),
+            );
+            return Some(RejectCode::InvalidMerkleRoot);
+        }
+        self.check_aggregate_key(block)
+    }
+
+    /// Check that the block's tx merkle root is computed from its transactions
+    fn verify_tx_merkle_root(block: &NakamotoBlock) -> bool {
+        let txid_vecs = block
+            .txs
+            .iter()
+            .map(|tx| tx.txid().as_bytes().to_vec())
+            .collect();
+        let merkle_tree = MerkleTree::<Sha512Trunc256Sum>::new(&txid_vecs);
+        merkle_tree.root() == block.header.tx_merkle_root
+    }
+
+    /// Check whether a block can be considered for signing at all. Before an aggregate key is
+    /// approved, only blocks carrying aggregate key votes (i.e. prepare phase blocks) are expected.
+    fn check_aggregate_key(&self, block: &NakamotoBlock) -> Option<RejectCode> {
+        if self.approved_aggregate_public_key.is_some() {
+            return None;
+        }
+        let has_vote = block
+            .txs
+            .iter()
+            .any(|tx| NakamotoSigners::parse_vote_for_aggregate_public_key(tx).is_some());
+        if has_vote {
+            None
+        } else {
+            Some(RejectCode::NoAggregateKey)
+        }
+    }
+
+    /// Mark the block as invalid and broadcast its rejection to the miners
+    fn reject_block(&mut self, block_info: &mut BlockInfo, reject_code: RejectCode) {
+        block_info.set_validated(false);
+        debug!("{self}: Broadcasting a block rejection: {reject_code}";
+            "signer_sighash" => %block_info.signer_signature_hash(),
+        );
+        metrics::increment_block_rejections(&reject_code);
+        let block_rejection = BlockRejection::new(block_info.signer_signature_hash(), reject_code);
+        self.send_block_response(BlockResponse::Rejected(block_rejection));
+    }
+
     /// Verify the transactions in a block are as expected
     fn verify_block_transactions(
         &mut self,
@@ -819,17 +1381,14 @@ impl Signer {
             let is_valid = missing_transactions.is_empty();
             if !is_valid {
                 debug!("{self}: Broadcasting a block rejection due to missing expected transactions...");
-                let block_rejection = BlockRejection::new(
+                let block_rejection = BlockRejection::missing_transactions(
                     block.header.signer_signature_hash(),
-                    RejectCode::MissingTransactions(missing_transactions),
+                    missing_transactions,
+                    self.compact_rejections,
                 );
+                metrics::increment_block_rejections(&block_rejection.reason_code);
                 // Submit signature result to miners to observe
-                if let Err(e) = self
-                    .stackerdb
-                    .send_message_with_retry(block_rejection.into())
-                {
-                    warn!("{self}: Failed to send block rejection to stacker-db: {e:?}",);
-                }
+                self.send_block_response(BlockResponse::Rejected(block_rejection));
             }
             is_valid
         } else {
@@ -839,13 +1398,9 @@ impl Signer {
                 block.header.signer_signature_hash(),
                 RejectCode::ConnectivityIssues,
             );
+            metrics::increment_block_rejections(&block_rejection.reason_code);
             // Submit signature result to miners to observe
-            if let Err(e) = self
-                .stackerdb
-                .send_message_with_retry(block_rejection.into())
-            {
-                warn!("{self}: Failed to send block submission to stacker-db: {e:?}",);
-            }
+            self.send_block_response(BlockResponse::Rejected(block_rejection));
             false
         }
     }
@@ -909,6 +1464,7 @@ impl Signer {
         let block_vote_bytes = block_vote.serialize_to_vec();
         // Cache our vote
         block_info.vote = Some(block_vote);
+        block_info.voted_at = Some(get_epoch_time_secs());
         nonce_request.message = block_vote_bytes;
     }
 
@@ -969,24 +1525,29 @@ impl Signer {
         operation_results: &[OperationResult],
     ) {
         for operation_result in operation_results {
-            // Signers only every trigger non-taproot signing rounds over blocks. Ignore SignTaproot results
             match operation_result {
                 OperationResult::Sign(signature) => {
                     debug!("{self}: Received signature result");
+                    metrics::increment_sign_rounds_succeeded();
                     self.process_signature(signature);
                 }
-                OperationResult::SignTaproot(_) => {
-                    debug!("{self}: Received a signature result for a taproot signature. Nothing to broadcast as we currently sign blocks with a FROST signature.");
+                OperationResult::SignTaproot(proof) => {
+                    debug!("{self}: Received taproot signature result");
+                    metrics::increment_sign_rounds_succeeded();
+                    self.process_taproot_signature(proof);
                 }
                 OperationResult::Dkg(aggregate_key) => {
+                    metrics::increment_dkg_rounds_succeeded();
                     self.process_dkg(stacks_client, aggregate_key);
                 }
                 OperationResult::SignError(e) => {
                     warn!("{self}: Received a Sign error: {e:?}");
+                    metrics::increment_sign_rounds_failed();
                     self.process_sign_error(e);
                 }
                 OperationResult::DkgError(e) => {
                     warn!("{self}: Received a DKG error: {e:?}");
+                    metrics::increment_dkg_rounds_failed();
                     // TODO: process these errors and track malicious signers to report
                 }
             }
@@ -1038,11 +1599,12 @@ impl Signer {
             warn!("{self}: Unable to get signer transactions: {e:?}");
         })
         .unwrap_or_default();
-        // If we have a transaction in the stackerdb slot, we need to increment the nonce hence the +1, else should use the account nonce
-        let next_nonce = signer_transactions
-            .first()
-            .map(|tx| tx.get_origin_nonce().wrapping_add(1))
-            .unwrap_or(*account_nonce);
+        let Some(next_nonce) = Self::next_vote_nonce(&signer_transactions, *account_nonce) else {
+            error!(
+                "{self}: Unable to build DKG public key vote ({dkg_public_key:?}) transaction. Next nonce would overflow."
+            );
+            return;
+        };
         match stacks_client.build_vote_for_aggregate_public_key(
             self.stackerdb.get_signer_slot_id().0,
             self.coordinator.current_dkg_id,
@@ -1071,58 +1633,236 @@ impl Signer {
         }
     }
 
-    // Get the account nonces for the provided list of signer addresses
-    fn get_account_nonces(
-        &self,
-        stacks_client: &StacksClient,
-        signer_addresses: &[StacksAddress],
-    ) -> std::collections::HashMap<StacksAddress, u64> {
-        let mut account_nonces = std::collections::HashMap::with_capacity(signer_addresses.len());
-        for address in signer_addresses {
-            let Ok(account_nonce) = stacks_client.get_account_nonce(address) else {
-                warn!("{self}: Unable to get account nonce for address: {address}.");
+    /// Re-queue the commands lost on restart from the persisted state: a sign command for every
+    /// validated block that has not been signed over yet (only if coordinator), and a DKG command
+    /// if there is no approved aggregate key yet.
+    pub fn recover_commands(&mut self, stacks_client: &StacksClient, current_reward_cycle: u64) {
+        if self.approved_aggregate_public_key.is_none() {
+            if let Err(e) = self.update_dkg(stacks_client, current_reward_cycle) {
+                warn!("{self}: Failed to check DKG state while recovering commands: {e:?}");
+            }
+        }
+        if !self.is_coordinator(current_reward_cycle) {
+            debug!("{self}: Not the coordinator. No sign commands to recover.");
+            return;
+        }
+        let blocks = match self
+            .signer_db
+            .get_blocks_for_reward_cycle(self.reward_cycle)
+        {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warn!("{self}: Failed to load blocks while recovering commands: {e:?}");
+                return;
+            }
+        };
+        for block_info in blocks {
+            if block_info.valid != Some(true) || block_info.signed_over {
                 continue;
+            }
+            let signer_signature_hash = block_info.signer_signature_hash();
+            let command = Command::Sign {
+                block: block_info.block,
+                is_taproot: false,
+                merkle_root: None,
             };
-            account_nonces.insert(*address, account_nonce);
+            if self.commands.contains(&command) {
+                continue;
+            }
+            debug!("{self}: Recovered a sign command for a validated block";
+                "signer_sighash" => %signer_signature_hash,
+            );
+            self.queue_command(command);
         }
-        account_nonces
     }
 
-    /// broadcast the dkg vote transaction according to the current epoch
-    fn broadcast_dkg_vote(
-        &mut self,
-        stacks_client: &StacksClient,
-        epoch: StacksEpochId,
-        mut signer_transactions: Vec<StacksTransaction>,
-        new_transaction: StacksTransaction,
-    ) -> Result<(), ClientError> {
-        let txid = new_transaction.txid();
-        if self.approved_aggregate_public_key.is_some() {
-            // We do not enforce a block contain any transactions except the aggregate votes when it is NOT already set
-            info!(
-                "{self}: Already has an approved aggregate key. Do not broadcast the transaction ({txid:?})."
-            );
-            return Ok(());
+    /// Remove blocks proposed more than `max_age_secs` before `now` (epoch time in seconds) that
+    /// this signer has already accepted or rejected, and checkpoint the signer DB to free the space.
+    pub fn gc_expired_blocks(&mut self, max_age_secs: u64, now: u64) {
+        let blocks = match self
+            .signer_db
+            .get_blocks_for_reward_cycle(self.reward_cycle)
+        {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warn!("{self}: Failed to load blocks for garbage collection: {e:?}");
+                return;
+            }
+        };
+        let mut removed = 0;
+        for block_info in blocks {
+            let proposed_at_secs = block_info.proposed_at / 1000;
+            if !block_info.is_decided() || now.saturating_sub(proposed_at_secs) <= max_age_secs {
+                continue;
+            }
+            let signer_signature_hash = block_info.signer_signature_hash();
+            if let Err(e) = self
+                .signer_db
+                .remove_block(self.reward_cycle, &signer_signature_hash)
+            {
+                warn!("{self}: Failed to remove expired block {signer_signature_hash}: {e:?}");
+                continue;
+            }
+            removed += 1;
         }
-        if epoch >= StacksEpochId::Epoch30 {
-            debug!("{self}: Received a DKG result while in epoch 3.0. Broadcast the transaction only to stackerDB.");
-        } else if epoch == StacksEpochId::Epoch25 {
-            debug!("{self}: Received a DKG result while in epoch 2.5. Broadcast the transaction to the mempool.");
-            stacks_client.submit_transaction_with_retry(&new_transaction)?;
-            info!("{self}: Submitted DKG vote transaction ({txid:?}) to the mempool");
-        } else {
-            debug!("{self}: Received a DKG result, but are in an unsupported epoch. Do not broadcast the transaction ({}).", new_transaction.txid());
-            return Ok(());
+        if removed == 0 {
+            return;
+        }
+        debug!("{self}: Removed {removed} expired blocks from the signer db");
+        if let Err(e) = self.signer_db.checkpoint() {
+            warn!("{self}: Failed to checkpoint the signer db: {e:?}");
         }
-        // For all Pox-4 epochs onwards, broadcast the results also to stackerDB for other signers/miners to observe
-        signer_transactions.push(new_transaction);
-        let signer_message = SignerMessage::Transactions(signer_transactions);
-        self.stackerdb.send_message_with_retry(signer_message)?;
-        info!("{self}: Broadcasted DKG vote transaction ({txid}) to stacker DB");
-        Ok(())
     }
 
-    /// Process a signature from a signing round by deserializing the signature and
+    /// Remove every block more than `max_depth` blocks below the highest block stored in the
+    /// signer db, whether or not it was decided, and checkpoint the signer db to free the space.
+    pub fn gc_stale_blocks(&self, max_depth: u64) {
+        let highest_chain_length = match self.signer_db.highest_chain_length() {
+            Ok(Some(highest_chain_length)) => highest_chain_length,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("{self}: Failed to load the highest stored block height: {e:?}");
+                return;
+            }
+        };
+        let min_chain_length = highest_chain_length.saturating_sub(max_depth);
+        let removed = match self.signer_db.remove_blocks_older_than(min_chain_length) {
+            Ok(removed) => removed,
+            Err(e) => {
+                warn!("{self}: Failed to remove blocks below height {min_chain_length}: {e:?}");
+                return;
+            }
+        };
+        if removed == 0 {
+            return;
+        }
+        debug!(
+            "{self}: Removed {removed} blocks below height {min_chain_length} from the signer db"
+        );
+        if let Err(e) = self.signer_db.checkpoint() {
+            warn!("{self}: Failed to checkpoint the signer db: {e:?}");
+        }
+    }
+
+    /// Remove the packets logged more than `max_age_secs` before `now` (epoch time in seconds)
+    /// from the packet log.
+    pub fn gc_packet_log(&self, max_age_secs: u64, now: u64) {
+        let received_before = now.saturating_sub(max_age_secs).saturating_mul(1000);
+        match self.signer_db.remove_packets_before(received_before) {
+            Ok(0) => {}
+            Ok(removed) => debug!("{self}: Removed {removed} expired packets from the packet log"),
+            Err(e) => warn!("{self}: Failed to remove expired packets from the packet log: {e:?}"),
+        }
+    }
+
+    /// Remove the blocks of every reward cycle before the previous one, and checkpoint the
+    /// signer DB to free the space.
+    pub fn expire_old_blocks(&self) {
+        let oldest_reward_cycle = self.reward_cycle.saturating_sub(1);
+        let removed = match self
+            .signer_db
+            .remove_blocks_before_reward_cycle(oldest_reward_cycle)
+        {
+            Ok(removed) => removed,
+            Err(e) => {
+                warn!("{self}: Failed to remove blocks older than reward cycle {oldest_reward_cycle}: {e:?}");
+                return;
+            }
+        };
+        if removed == 0 {
+            return;
+        }
+        debug!("{self}: Removed {removed} blocks older than reward cycle {oldest_reward_cycle} from the signer db");
+        if let Err(e) = self.signer_db.checkpoint() {
+            warn!("{self}: Failed to checkpoint the signer db: {e:?}");
+        }
+    }
+
+    /// Determine the nonce to use for a new DKG vote transaction.
+    /// If we have a transaction in the stackerdb slot, we need to increment its nonce, else should use the account nonce.
+    /// Returns None if incrementing the nonce would overflow.
+    fn next_vote_nonce(
+        signer_transactions: &[StacksTransaction],
+        account_nonce: u64,
+    ) -> Option<u64> {
+        match signer_transactions.first() {
+            Some(tx) => tx.get_origin_nonce().checked_add(1),
+            None => Some(account_nonce),
+        }
+    }
+
+    // Get the account nonces for the provided list of signer addresses.
+    // Nonces fetched less than `nonce_cache_ttl` ago, and since the last block event, are reused
+    // instead of fetched again.
+    fn get_account_nonces(
+        &self,
+        stacks_client: &StacksClient,
+        signer_addresses: &[StacksAddress],
+    ) -> std::collections::HashMap<StacksAddress, u64> {
+        let mut account_nonces = std::collections::HashMap::with_capacity(signer_addresses.len());
+        let mut account_nonce_cache = self.account_nonce_cache.borrow_mut();
+        for address in signer_addresses {
+            if let Some((account_nonce, fetched_at)) = account_nonce_cache.get(address) {
+                if fetched_at.elapsed() < self.nonce_cache_ttl {
+                    account_nonces.insert(*address, *account_nonce);
+                    continue;
+                }
+            }
+            let Ok(account_nonce) = stacks_client.get_account_nonce(address) else {
+                warn!("{self}: Unable to get account nonce for address: {address}.");
+                continue;
+            };
+            account_nonce_cache.insert(*address, (account_nonce, Instant::now()));
+            account_nonces.insert(*address, account_nonce);
+        }
+        account_nonces
+    }
+
+    /// Drop every cached account nonce, as the blocks processed since a new burn block may have
+    /// changed any of them. Block proposals are not processed yet, so they leave the cache alone.
+    fn clear_account_nonce_cache(&self) {
+        self.account_nonce_cache.borrow_mut().clear();
+    }
+
+    /// broadcast the dkg vote transaction according to the current epoch
+    fn broadcast_dkg_vote(
+        &mut self,
+        stacks_client: &StacksClient,
+        epoch: StacksEpochId,
+        mut signer_transactions: Vec<StacksTransaction>,
+        new_transaction: StacksTransaction,
+    ) -> Result<(), ClientError> {
+        let txid = new_transaction.txid();
+        if self.approved_aggregate_public_key.is_some() {
+            // We do not enforce a block contain any transactions except the aggregate votes when it is NOT already set
+            info!(
+                "{self}: Already has an approved aggregate key. Do not broadcast the transaction ({txid:?})."
+            );
+            return Ok(());
+        }
+        if epoch >= StacksEpochId::Epoch30 {
+            debug!("{self}: Received a DKG result while in epoch 3.0. Broadcast the transaction only to stackerDB.");
+        } else if epoch == StacksEpochId::Epoch25 {
+            debug!("{self}: Received a DKG result while in epoch 2.5. Broadcast the transaction to the mempool.");
+            stacks_client.submit_transaction_with_retry(&new_transaction)?;
+            info!("{self}: Submitted DKG vote transaction ({txid:?}) to the mempool");
+        } else {
+            debug!("{self}: Received a DKG result, but are in an unsupported epoch. Do not broadcast the transaction ({}).", new_transaction.txid());
+            return Ok(());
+        }
+        // Our account's nonce is about to change, so fetch it again next time
+        self.account_nonce_cache
+            .borrow_mut()
+            .remove(stacks_client.get_signer_address());
+        // For all Pox-4 epochs onwards, broadcast the results also to stackerDB for other signers/miners to observe
+        signer_transactions.push(new_transaction);
+        let signer_message = SignerMessage::Transactions(signer_transactions);
+        self.stackerdb.send_message_with_retry(signer_message)?;
+        info!("{self}: Broadcasted DKG vote transaction ({txid}) to stacker DB");
+        Ok(())
+    }
+
+    /// Process a signature from a signing round by deserializing the signature and
     /// broadcasting an appropriate Reject or Approval message to stackerdb
     fn process_signature(&mut self, signature: &Signature) {
         // Deserialize the signature result and broadcast an appropriate Reject or Approval message to stackerdb
@@ -1141,15 +1881,146 @@ impl Signer {
         };
 
         // Submit signature result to miners to observe
-        info!("{self}: Submit block response: {block_submission}");
-        if let Err(e) = self
-            .stackerdb
-            .send_message_with_retry(block_submission.into())
-        {
-            warn!("{self}: Failed to send block submission to stacker-db: {e:?}");
+        self.send_block_response(block_submission);
+    }
+
+    /// Process a taproot signature from a signing round by deserializing the signed block vote
+    /// and broadcasting it alongside the schnorr proof to stackerdb
+    fn process_taproot_signature(&mut self, proof: &SchnorrProof) {
+        let message = self.coordinator.get_message();
+        let Some(block_vote): Option<NakamotoBlockVote> = read_next(&mut &message[..]).ok() else {
+            debug!("{self}: Received a taproot signature result for a non-block. Nothing to broadcast.");
+            return;
+        };
+
+        let block_submission = BlockResponse::signed_taproot(block_vote, proof.clone());
+
+        // Submit signature result to miners to observe
+        self.send_block_response(block_submission);
+    }
+
+    /// Send a block response to stackerdb. If it cannot be sent, persist it so that
+    /// `flush_pending_responses` can resend it later. Sending never blocks on a retry backoff.
+    /// Every block response is written to the same stackerdb slot, so a newer response
+    /// supersedes any response still pending.
+    fn send_block_response(&mut self, block_response: BlockResponse) {
+        info!("{self}: Submit block response: {block_response}");
+        match self.stackerdb.send_message(block_response.clone().into()) {
+            Ok(_) => {
+                if let Err(e) = self.signer_db.remove_pending_responses(self.reward_cycle) {
+                    error!("{self}: Failed to remove superseded block responses: {e:?}");
+                }
+                self.clear_pending_response_retries();
+            }
+            Err(e) => {
+                warn!("{self}: Failed to send block submission to stacker-db: {e:?}. Persisting it to resend later.");
+                if let Err(e) = self
+                    .signer_db
+                    .set_pending_response(self.reward_cycle, &block_response)
+                {
+                    error!("{self}: Failed to persist block response: {e:?}");
+                }
+                self.pending_response_attempts = 0;
+                self.schedule_pending_response_flush();
+            }
         }
     }
 
+    /// Resend the pending block response, if any and if its retry is due.
+    /// The response is dropped after `MAX_PENDING_RESPONSE_ATTEMPTS` failed attempts.
+    pub fn flush_pending_responses(&mut self) {
+        let Some(flush_at) = self.next_pending_response_flush else {
+            return;
+        };
+        if Instant::now() < flush_at {
+            return;
+        }
+        let block_response = match self.signer_db.pending_response(self.reward_cycle) {
+            Ok(Some(block_response)) => block_response,
+            Ok(None) => {
+                self.clear_pending_response_retries();
+                return;
+            }
+            Err(e) => {
+                error!("{self}: Failed to load pending block response: {e:?}");
+                self.schedule_pending_response_flush();
+                return;
+            }
+        };
+        info!("{self}: Resubmit block response: {block_response}");
+        if let Err(e) = self.stackerdb.send_message(block_response.into()) {
+            self.pending_response_attempts = self.pending_response_attempts.saturating_add(1);
+            if self.pending_response_attempts < MAX_PENDING_RESPONSE_ATTEMPTS {
+                warn!("{self}: Failed to resend block submission to stacker-db: {e:?}");
+                self.schedule_pending_response_flush();
+                return;
+            }
+            warn!(
+                "{self}: Failed to resend block submission to stacker-db: {e:?}. Dropping it after {} attempts.",
+                self.pending_response_attempts
+            );
+        }
+        if let Err(e) = self.signer_db.remove_pending_responses(self.reward_cycle) {
+            error!("{self}: Failed to remove pending block response: {e:?}");
+        }
+        self.clear_pending_response_retries();
+    }
+
+    /// Resubmit for validation every block whose resubmission after a transient validation
+    /// failure is due
+    pub fn resubmit_transient_rejections(&mut self, stacks_client: &StacksClient) {
+        let now = Instant::now();
+        let due: Vec<_> = self
+            .transient_resubmissions
+            .iter()
+            .filter(|(_, (_, resubmit_at))| resubmit_at.map_or(false, |at| at <= now))
+            .map(|(signer_signature_hash, _)| *signer_signature_hash)
+            .collect();
+        for signer_signature_hash in due {
+            let block_info = match self
+                .signer_db
+                .block_lookup(self.reward_cycle, &signer_signature_hash)
+            {
+                Ok(Some(block_info)) => block_info,
+                Ok(None) => {
+                    debug!("{self}: Block {signer_signature_hash} is no longer stored. Not resubmitting it for validation.");
+                    self.transient_resubmissions.remove(&signer_signature_hash);
+                    continue;
+                }
+                Err(e) => {
+                    error!("{self}: Failed to lookup block in signer db: {e:?}");
+                    continue;
+                }
+            };
+            if let Some((_, resubmit_at)) =
+                self.transient_resubmissions.get_mut(&signer_signature_hash)
+            {
+                *resubmit_at = None;
+            }
+            stacks_client
+                .submit_block_for_validation_with_retry(block_info.block)
+                .unwrap_or_else(|e| {
+                    warn!("{self}: Failed to resubmit block for validation: {e:?}",);
+                });
+        }
+    }
+
+    /// Schedule the next attempt at resending the pending block response,
+    /// backing off exponentially with the number of failed attempts
+    fn schedule_pending_response_flush(&mut self) {
+        let backoff = 1u64 << self.pending_response_attempts.min(16);
+        self.next_pending_response_flush = Some(
+            Instant::now()
+                + Duration::from_millis(PENDING_RESPONSE_RETRY_INTERVAL_MS.saturating_mul(backoff)),
+        );
+    }
+
+    /// Stop trying to resend a pending block response, as none is left
+    fn clear_pending_response_retries(&mut self) {
+        self.pending_response_attempts = 0;
+        self.next_pending_response_flush = None;
+    }
+
     /// Process a sign error from a signing round, broadcasting a rejection message to stackerdb accordingly
     fn process_sign_error(&mut self, e: &SignError) {
         let message = self.coordinator.get_message();
@@ -1181,13 +2052,9 @@ impl Signer {
         let block_rejection =
             BlockRejection::new(block.header.signer_signature_hash(), RejectCode::from(e));
         debug!("{self}: Broadcasting block rejection: {block_rejection:?}");
+        metrics::increment_block_rejections(&block_rejection.reason_code);
         // Submit signature result to miners to observe
-        if let Err(e) = self
-            .stackerdb
-            .send_message_with_retry(block_rejection.into())
-        {
-            warn!("{self}: Failed to send block rejection submission to stacker-db: {e:?}");
-        }
+        self.send_block_response(BlockResponse::Rejected(block_rejection));
     }
 
     /// Persist state needed to ensure the signer can continue to perform
@@ -1256,6 +2123,7 @@ impl Signer {
                     "{self}: updated DKG value to {:?}.",
                     self.approved_aggregate_public_key
                 );
+                self.record_aggregate_key_adoption(get_epoch_time_ms() as u64);
             }
             return Ok(());
         };
@@ -1306,10 +2174,19 @@ impl Signer {
             };
             let threshold_weight = stacks_client.get_vote_threshold_weight(self.reward_cycle)?;
             if round_weight < threshold_weight {
-                // The threshold weight has not been met yet. We should wait for more votes to arrive.
-                // TODO: this should be on a timeout of some kind. We should not wait forever for the threshold to be met.
-                // See https://github.com/stacks-network/stacks-core/issues/4568
-                debug!("{self}: Not triggering a DKG round. Weight threshold has not been met yet. Waiting for more votes to arrive.";
+                // The threshold weight has not been met yet. Wait for more votes to arrive, unless
+                // we have waited too long, in which case retrigger DKG in the next round.
+                if !self.dkg_retry_timed_out() {
+                    debug!("{self}: Not triggering a DKG round. Weight threshold has not been met yet. Waiting for more votes to arrive.";
+                        "voting_round" => self.coordinator.current_dkg_id,
+                        "aggregate_key" => %aggregate_key,
+                        "round_weight" => round_weight,
+                        "threshold_weight" => threshold_weight
+                    );
+                    return Ok(());
+                }
+                self.retry_dkg();
+                info!("{self}: Weight threshold was not met in time. Retriggering a DKG round.";
                     "voting_round" => self.coordinator.current_dkg_id,
                     "aggregate_key" => %aggregate_key,
                     "round_weight" => round_weight,
@@ -1326,12 +2203,123 @@ impl Signer {
         } else {
             debug!("{self}: Triggering a DKG round.");
         }
+        self.queue_dkg_command();
+        Ok(())
+    }
+
+    /// Queue a DKG command at the front of the command queue, unless one is already there,
+    /// and record the time it was queued
+    fn queue_dkg_command(&mut self) {
         if self.commands.front() != Some(&Command::Dkg) {
             info!("{self} is the current coordinator and must trigger DKG. Queuing DKG command...");
             self.commands.push_front(Command::Dkg);
+            self.last_dkg_command_time = Some(Instant::now());
+            self.drop_excess_commands();
         } else {
             debug!("{self}: DKG command already queued...");
         }
+    }
+
+    /// Queue a command at the back of the command queue. If this overfills the queue, the
+    /// oldest non-DKG commands are dropped; a queued DKG command is always kept.
+    pub fn queue_command(&mut self, command: Command) {
+        self.commands.push_back(command);
+        self.drop_excess_commands();
+    }
+
+    /// Drop the oldest non-DKG commands until the queue holds at most `max_pending_commands`
+    fn drop_excess_commands(&mut self) {
+        while self.commands.len() > self.max_pending_commands {
+            let Some(index) = self
+                .commands
+                .iter()
+                .position(|command| command != &Command::Dkg)
+            else {
+                break;
+            };
+            if let Some(Command::Sign { block, .. }) = self.commands.remove(index) {
+                warn!("{self}: Command queue is full. Dropping the oldest sign command.";
+                    "signer_sighash" => %block.header.signer_signature_hash(),
+                    "max_pending_commands" => self.max_pending_commands,
+                );
+            }
+        }
+    }
+
+    /// Whether more than `dkg_retry_timeout` has elapsed since this signer last queued a DKG
+    /// command without an aggregate key being approved. Never times out while an operation is
+    /// in progress, or if this signer has not queued a DKG command.
+    fn dkg_retry_timed_out(&self) -> bool {
+        if self.approved_aggregate_public_key.is_some() || self.state != State::Idle {
+            return false;
+        }
+        self.last_dkg_command_time.map_or(false, |queued_at| {
+            queued_at.elapsed() > self.dkg_retry_timeout
+        })
+    }
+
+    /// Move on to the next DKG round and queue a DKG command for it
+    fn retry_dkg(&mut self) {
+        self.coordinator.current_dkg_id = self.coordinator.current_dkg_id.wrapping_add(1);
+        self.queue_dkg_command();
+    }
+
+    /// Record `now` (in milliseconds) as the time the approved aggregate key was adopted, unless
+    /// the signer db already records its adoption, e.g. from before a restart
+    fn record_aggregate_key_adoption(&self, now: u64) {
+        let Some(approved) = self.approved_aggregate_public_key else {
+            return;
+        };
+        match self.signer_db.get_aggregate_key_adoption(self.reward_cycle) {
+            Ok(Some((adopted, _))) if adopted == approved => {}
+            Ok(_) => {
+                if let Err(e) =
+                    self.signer_db
+                        .insert_aggregate_key_adoption(self.reward_cycle, &approved, now)
+                {
+                    warn!("{self}: Failed to record the aggregate key adoption time: {e:?}");
+                }
+            }
+            Err(e) => warn!("{self}: Failed to load the aggregate key adoption time: {e:?}"),
+        }
+    }
+
+    /// How long (in milliseconds) before `now` this signer adopted its approved aggregate key.
+    /// Returns None if there is no approved key, or its adoption time is unknown.
+    pub fn aggregate_key_age(&self, now: u64) -> Option<u64> {
+        let approved = self.approved_aggregate_public_key?;
+        match self.signer_db.get_aggregate_key_adoption(self.reward_cycle) {
+            Ok(Some((adopted, adopted_at))) if adopted == approved => {
+                Some(now.saturating_sub(adopted_at))
+            }
+            Ok(_) => None,
+            Err(e) => {
+                warn!("{self}: Failed to load the aggregate key adoption time: {e:?}");
+                None
+            }
+        }
+    }
+
+    /// Check that the approved aggregate key, the coordinator's aggregate key, and the signer state
+    /// machine's group key all agree. Nothing is checked until an aggregate key is approved.
+    pub fn verify_key_consistency(&self) -> Result<(), SignerError> {
+        let Some(approved) = self.approved_aggregate_public_key else {
+            return Ok(());
+        };
+        let coordinator = self.coordinator.aggregate_public_key;
+        let signer = self.state_machine.signer.save().group_key;
+        if coordinator != Some(approved) || signer != approved {
+            error!("{self}: FATAL: aggregate public key mismatch";
+                "approved" => %approved,
+                "coordinator" => ?coordinator,
+                "signer" => %signer
+            );
+            return Err(SignerError::InconsistentAggregateKey {
+                approved,
+                coordinator,
+                signer,
+            });
+        }
         Ok(())
     }
 
@@ -1386,10 +2374,17 @@ impl Signer {
                 self.handle_proposed_blocks(stacks_client, blocks);
             }
             Some(SignerEvent::StatusCheck) => {
-                debug!("{self}: Received a status check event.")
+                match serde_json::to_string(&self.status()) {
+                    Ok(status) => {
+                        info!("{self}: Received a status check event."; "status" => status)
+                    }
+                    Err(e) => warn!("{self}: Failed to serialize signer status: {e:?}"),
+                }
+                self.expire_old_blocks();
             }
             Some(SignerEvent::NewBurnBlock(height)) => {
-                debug!("{self}: Receved a new burn block event for block height {height}")
+                debug!("{self}: Receved a new burn block event for block height {height}");
+                self.clear_account_nonce_cache();
             }
             None => {
                 // No event. Do nothing.
@@ -1399,3 +2394,1388 @@ impl Signer {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use std::thread::spawn;
+
+    use blockstack_lib::chainstate::nakamoto::NakamotoBlockHeader;
+    use blockstack_lib::chainstate::stacks::{
+        ThresholdSignature, TransactionAnchorMode, TransactionAuth, TransactionPayload,
+        TransactionPostConditionMode, TransactionSmartContract, TransactionVersion,
+    };
+    use blockstack_lib::net::api::postblock_proposal::BlockValidateReject;
+    use blockstack_lib::util_lib::strings::StacksString;
+    use libstackerdb::StackerDBChunkAckData;
+    use stacks_common::bitvec::BitVec;
+    use stacks_common::types::chainstate::{StacksBlockId, StacksPrivateKey, TrieHash};
+    use stacks_common::util::hash::to_hex;
+    use stacks_common::util::secp256k1::MessageSignature;
+    use wsts::curve::scalar::Scalar;
+    use wsts::net::{DkgEnd, DkgStatus};
+
+    use super::*;
+    use crate::client::tests::{
+        build_account_nonce_response, generate_signer_config, write_ack_response, write_response,
+        MockServerClient,
+    };
+    use crate::config::GlobalConfig;
+
+    fn build_transaction(nonce: u64) -> StacksTransaction {
+        let sk = StacksPrivateKey::new();
+        let mut tx = StacksTransaction {
+            version: TransactionVersion::Testnet,
+            chain_id: 0,
+            auth: TransactionAuth::from_p2pkh(&sk).unwrap(),
+            anchor_mode: TransactionAnchorMode::Any,
+            post_condition_mode: TransactionPostConditionMode::Allow,
+            post_conditions: vec![],
+            payload: TransactionPayload::SmartContract(
+                TransactionSmartContract {
+                    name: "test-contract".into(),
+                    code_body: StacksString::from_str("(/ 1 0)").unwrap(),
+                },
+                None,
+            ),
+        };
+        tx.set_origin_nonce(nonce);
+        tx
+    }
+
+    fn build_block(chain_length: u64) -> NakamotoBlock {
+        let header = NakamotoBlockHeader {
+            version: 1,
+            chain_length,
+            burn_spent: 3,
+            consensus_hash: ConsensusHash([0x04; 20]),
+            parent_block_id: StacksBlockId([0x05; 32]),
+            tx_merkle_root: MerkleTree::<Sha512Trunc256Sum>::new(&vec![]).root(),
+            state_index_root: TrieHash([0x07; 32]),
+            miner_signature: MessageSignature::empty(),
+            signer_signature: ThresholdSignature::empty(),
+            signer_bitvec: BitVec::zeros(1).unwrap(),
+        };
+        NakamotoBlock {
+            header,
+            txs: vec![],
+        }
+    }
+
+    #[test]
+    fn next_vote_nonce_should_use_account_nonce_without_pending_transaction() {
+        assert_eq!(Signer::next_vote_nonce(&[], 5), Some(5));
+        assert_eq!(Signer::next_vote_nonce(&[], u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn next_vote_nonce_should_increment_pending_transaction_nonce() {
+        let tx = build_transaction(10);
+        assert_eq!(Signer::next_vote_nonce(&[tx], 3), Some(11));
+    }
+
+    #[test]
+    fn next_vote_nonce_should_not_overflow() {
+        let tx = build_transaction(u64::MAX);
+        assert_eq!(Signer::next_vote_nonce(&[tx], 0), None);
+    }
+
+    #[test]
+    fn get_account_nonces_should_reuse_cached_nonces_within_ttl() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.nonce_cache_ttl = Duration::from_secs(60);
+        let address = *mock.client.get_signer_address();
+
+        let server = mock.server.try_clone().unwrap();
+        let h = spawn(move || write_response(server, build_account_nonce_response(7).as_bytes()));
+        let account_nonces = signer.get_account_nonces(&mock.client, &[address]);
+        h.join().unwrap();
+        assert_eq!(account_nonces.get(&address), Some(&7));
+
+        // a second lookup must not reach the stacks node, which would never answer it
+        let (nonces_send, nonces_recv) = channel();
+        let client = mock.client.clone();
+        spawn(move || {
+            let account_nonces = signer.get_account_nonces(&client, &[address]);
+            nonces_send.send(account_nonces).unwrap();
+        });
+        let account_nonces = nonces_recv
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Expected the cached nonce to be reused");
+        assert_eq!(account_nonces.get(&address), Some(&7));
+    }
+
+    #[test]
+    fn only_burn_block_events_should_clear_cached_nonces() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let address = *mock.client.get_signer_address();
+        let cache_nonce = |signer: &Signer| {
+            signer
+                .account_nonce_cache
+                .borrow_mut()
+                .insert(address, (7, Instant::now()));
+        };
+        // Use another reward cycle so the events are not otherwise acted upon
+        let other_reward_cycle = signer.reward_cycle.wrapping_add(1);
+
+        cache_nonce(&signer);
+        let (res_send, _res_recv) = channel();
+        signer
+            .process_event(
+                &mock.client,
+                Some(&SignerEvent::NewBurnBlock(100)),
+                res_send,
+                other_reward_cycle,
+            )
+            .unwrap();
+        assert!(signer.account_nonce_cache.borrow().is_empty());
+
+        cache_nonce(&signer);
+        let (res_send, _res_recv) = channel();
+        signer
+            .process_event(
+                &mock.client,
+                Some(&SignerEvent::MinerMessages(vec![], vec![], None)),
+                res_send,
+                other_reward_cycle,
+            )
+            .unwrap();
+        assert_eq!(signer.account_nonce_cache.borrow().len(), 1);
+
+        // A block proposal has not been processed, so it cannot have changed any nonce
+        let block_proposal = BlockProposalSigners {
+            block: build_block(1),
+            burn_height: 100,
+            reward_cycle: other_reward_cycle,
+        };
+        let (res_send, _res_recv) = channel();
+        signer
+            .process_event(
+                &mock.client,
+                Some(&SignerEvent::MinerMessages(
+                    vec![block_proposal],
+                    vec![],
+                    None,
+                )),
+                res_send,
+                other_reward_cycle,
+            )
+            .unwrap();
+        assert_eq!(signer.account_nonce_cache.borrow().len(), 1);
+    }
+
+    #[test]
+    fn thresholds_should_follow_configured_ratios() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let signer = Signer::from(signer_config.clone());
+        let config = signer.coordinator.get_config();
+        assert_eq!(config.threshold, 14);
+        assert_eq!(config.dkg_threshold, 18);
+
+        let signer = Signer::from(SignerConfig {
+            signing_threshold_ratio: 0.5,
+            dkg_threshold_ratio: 0.76,
+            ..signer_config
+        });
+        let config = signer.coordinator.get_config();
+        assert_eq!(config.threshold, 10);
+        assert_eq!(config.dkg_threshold, 16);
+    }
+
+    #[test]
+    fn sign_command_should_be_skipped_if_no_longer_coordinator() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let stacks_client = StacksClient::from(&config);
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.approved_aggregate_public_key = Some(Point::new());
+        let current_reward_cycle = signer.reward_cycle;
+
+        let block = build_block(2);
+        signer.commands.push_back(Command::Sign {
+            block: block.clone(),
+            is_taproot: false,
+            merkle_root: None,
+        });
+
+        // Rotate the coordinator away from this signer: in the active reward cycle, the miner coordinates
+        signer.miner_key = Some(signer.coordinator_selector.get_coordinator().1);
+        assert!(!signer.is_coordinator(current_reward_cycle));
+
+        let command = signer.commands.pop_front().unwrap();
+        signer.execute_command(&stacks_client, &command, current_reward_cycle);
+
+        assert_eq!(signer.state, State::Idle);
+        assert_eq!(signer.coordinator.state, CoordinatorState::Idle);
+        assert!(signer
+            .signer_db
+            .block_lookup(signer.reward_cycle, &block.header.signer_signature_hash())
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn sign_command_should_be_skipped_for_block_conflicting_with_signed_block() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let stacks_client = StacksClient::from(&config);
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.approved_aggregate_public_key = Some(Point::new());
+        // The coordinator outside of the active reward cycle
+        signer.signer_id = signer.coordinator_selector.get_coordinator().0;
+        let current_reward_cycle = signer.reward_cycle.wrapping_add(1);
+        assert!(signer.is_coordinator(current_reward_cycle));
+
+        let signed_block = build_block(2);
+        let mut signed_block_info = BlockInfo::new(signed_block);
+        signed_block_info.signed_over = true;
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &signed_block_info)
+            .unwrap();
+
+        let mut conflicting_block = build_block(2);
+        conflicting_block.header.burn_spent = 4;
+        let command = Command::Sign {
+            block: conflicting_block.clone(),
+            is_taproot: false,
+            merkle_root: None,
+        };
+        signer.execute_command(&stacks_client, &command, current_reward_cycle);
+
+        assert_eq!(signer.coordinator.state, CoordinatorState::Idle);
+        assert!(signer
+            .signer_db
+            .block_lookup(
+                signer.reward_cycle,
+                &conflicting_block.header.signer_signature_hash()
+            )
+            .unwrap()
+            .is_none());
+    }
+
+    fn build_nonce_request(block: &NakamotoBlock) -> NonceRequest {
+        NonceRequest {
+            dkg_id: 1,
+            sign_id: 2,
+            sign_iter_id: 3,
+            message: block.serialize_to_vec(),
+            is_taproot: false,
+            merkle_root: None,
+        }
+    }
+
+    #[test]
+    fn signed_message_bytes_should_match_signing_round_message() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let mut block_info = BlockInfo::new(block.clone());
+
+        // The sign command starts the round over the serialized block
+        assert_eq!(block_info.signed_message_bytes(), block.serialize_to_vec());
+
+        // Voting replaces the nonce request message with the vote
+        block_info.valid = Some(true);
+        let mut nonce_request = build_nonce_request(&block);
+        signer.determine_vote(&mut block_info, &mut nonce_request);
+        assert_eq!(block_info.signed_message_bytes(), nonce_request.message);
+        assert_ne!(block_info.signed_message_bytes(), block.serialize_to_vec());
+    }
+
+    #[test]
+    fn determine_vote_should_record_vote_time() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let mut block_info = BlockInfo::new(block.clone());
+        assert_eq!(block_info.voted_at, None);
+
+        block_info.set_validated(true);
+        let validated_at = block_info.validated_at.expect("Expected a validation time");
+        let mut nonce_request = build_nonce_request(&block);
+        signer.determine_vote(&mut block_info, &mut nonce_request);
+        let voted_at = block_info.voted_at.expect("Expected a vote time");
+        assert!(voted_at >= validated_at);
+    }
+
+    #[test]
+    fn validate_nonce_request_should_vote_on_validated_block() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let mut block_info = BlockInfo::new(block.clone());
+        block_info.valid = Some(true);
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &block_info)
+            .unwrap();
+
+        let mut nonce_request = build_nonce_request(&block);
+        let block_info = signer
+            .validate_nonce_request(&mock.client, &mut nonce_request)
+            .expect("Expected block info for a known block");
+
+        let expected_vote = NakamotoBlockVote {
+            signer_signature_hash: block.header.signer_signature_hash(),
+            rejected: false,
+        };
+        assert_eq!(nonce_request.message, expected_vote.serialize_to_vec());
+        assert_eq!(block_info.vote, Some(expected_vote));
+        assert!(block_info.nonce_request.is_none());
+    }
+
+    #[test]
+    fn validate_nonce_request_should_cache_request_for_unvalidated_block() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &BlockInfo::new(block.clone()))
+            .unwrap();
+
+        let mut nonce_request = build_nonce_request(&block);
+        let block_info = signer
+            .validate_nonce_request(&mock.client, &mut nonce_request)
+            .expect("Expected block info for a known block");
+
+        // The message is left untouched until the block is validated
+        assert_eq!(nonce_request.message, block.serialize_to_vec());
+        assert!(block_info.vote.is_none());
+        assert!(block_info.valid.is_none());
+        assert_eq!(block_info.nonce_request, Some(nonce_request));
+    }
+
+    #[test]
+    fn validate_nonce_request_should_reject_oversized_messages_before_parsing() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &BlockInfo::new(block.clone()))
+            .unwrap();
+        let block_len = block.serialize_to_vec().len();
+
+        // The block is known, so it would be cached if it were parsed
+        signer.max_block_size = block_len - 1;
+        let mut nonce_request = build_nonce_request(&block);
+        assert!(signer
+            .validate_nonce_request(&mock.client, &mut nonce_request)
+            .is_none());
+        assert_eq!(nonce_request.message, block.serialize_to_vec());
+
+        signer.max_block_size = block_len;
+        let block_info = signer
+            .validate_nonce_request(&mock.client, &mut nonce_request)
+            .expect("Expected block info for a block within the size limit");
+        assert_eq!(block_info.nonce_request, Some(nonce_request));
+    }
+
+    #[test]
+    fn validate_nonce_request_should_submit_unknown_block_for_validation() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.approved_aggregate_public_key = Some(Point::new());
+        let block = build_block(2);
+
+        let mut nonce_request = build_nonce_request(&block);
+        let h = spawn(move || write_response(mock.server, b"HTTP/1.1 200 OK\n\n"));
+        let block_info = signer
+            .validate_nonce_request(&mock.client, &mut nonce_request)
+            .expect("Expected block info for an unknown block");
+        let request_bytes = h.join().unwrap();
+
+        assert!(String::from_utf8_lossy(&request_bytes).starts_with("POST /v2/block_proposal"));
+        assert_eq!(nonce_request.message, block.serialize_to_vec());
+        assert!(block_info.vote.is_none());
+        assert!(block_info.valid.is_none());
+        assert_eq!(block_info.nonce_request, Some(nonce_request));
+    }
+
+    #[test]
+    fn transient_validation_reject_should_resubmit_block() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let signer_signature_hash = block.header.signer_signature_hash();
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &BlockInfo::new(block))
+            .unwrap();
+
+        let reject = BlockValidateResponse::Reject(BlockValidateReject {
+            signer_signature_hash,
+            reason: "Chainstate Error: not ready".to_string(),
+            reason_code: ValidateRejectCode::ChainstateError,
+        });
+        let (res_send, _res_recv) = channel();
+        let h = spawn(move || write_response(mock.server, b"HTTP/1.1 200 OK\n\n"));
+        let reward_cycle = signer.reward_cycle;
+        signer.handle_block_validate_response(&mock.client, &reject, res_send, reward_cycle);
+        let request_bytes = h.join().unwrap();
+
+        // the block is resubmitted rather than rejected in stackerdb
+        assert!(String::from_utf8_lossy(&request_bytes).starts_with("POST /v2/block_proposal"));
+        let block_info = signer
+            .signer_db
+            .block_lookup(signer.reward_cycle, &signer_signature_hash)
+            .unwrap()
+            .expect("Expected the block to still be stored");
+        assert!(block_info.valid.is_none());
+        assert_eq!(
+            signer.transient_resubmissions.get(&signer_signature_hash),
+            Some(&(1, None))
+        );
+
+        // a further transient failure backs off before resubmitting the block
+        let (res_send, _res_recv) = channel();
+        signer.handle_block_validate_response(&mock.client, &reject, res_send, reward_cycle);
+        let (resubmissions, resubmit_at) = signer.transient_resubmissions[&signer_signature_hash];
+        assert_eq!(resubmissions, 2);
+        assert!(resubmit_at.unwrap() > Instant::now());
+    }
+
+    #[test]
+    fn transient_validation_reject_should_be_rejected_after_max_resubmissions() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let signer_signature_hash = block.header.signer_signature_hash();
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &BlockInfo::new(block))
+            .unwrap();
+        signer
+            .transient_resubmissions
+            .insert(signer_signature_hash, (MAX_TRANSIENT_RESUBMISSIONS, None));
+
+        let block_validate_reject = BlockValidateReject {
+            signer_signature_hash,
+            reason: "Chainstate Error: not ready".to_string(),
+            reason_code: ValidateRejectCode::ChainstateError,
+        };
+        let expected_message: SignerMessage = block_validate_reject.clone().into();
+        let reject = BlockValidateResponse::Reject(block_validate_reject);
+        let ack = StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        };
+        let (res_send, _res_recv) = channel();
+        let h = spawn(move || write_ack_response(mock.server, &ack));
+        let reward_cycle = signer.reward_cycle;
+        signer.handle_block_validate_response(&mock.client, &reject, res_send, reward_cycle);
+        let request_bytes = h.join().unwrap();
+
+        // the block is rejected in stackerdb instead of resubmitted
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.starts_with("POST /v2/stackerdb/"));
+        assert!(request.contains(&to_hex(&expected_message.serialize_to_vec())));
+        let block_info = signer
+            .signer_db
+            .block_lookup(signer.reward_cycle, &signer_signature_hash)
+            .unwrap()
+            .expect("Expected the block to still be stored");
+        assert_eq!(block_info.valid, Some(false));
+        assert!(signer.transient_resubmissions.is_empty());
+    }
+
+    #[test]
+    fn permanent_validation_reject_should_broadcast_rejection() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let signer_signature_hash = block.header.signer_signature_hash();
+        signer
+            .signer_db
+            .insert_block(signer.reward_cycle, &BlockInfo::new(block))
+            .unwrap();
+
+        let block_validate_reject = BlockValidateReject {
+            signer_signature_hash,
+            reason: "Invalid transaction".to_string(),
+            reason_code: ValidateRejectCode::BadTransaction,
+        };
+        let expected_message: SignerMessage = block_validate_reject.clone().into();
+        let reject = BlockValidateResponse::Reject(block_validate_reject);
+        let ack = StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        };
+        let (res_send, _res_recv) = channel();
+        let h = spawn(move || write_ack_response(mock.server, &ack));
+        let reward_cycle = signer.reward_cycle;
+        signer.handle_block_validate_response(&mock.client, &reject, res_send, reward_cycle);
+        let request_bytes = h.join().unwrap();
+
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.starts_with("POST /v2/stackerdb/"));
+        assert!(request.contains(&to_hex(&expected_message.serialize_to_vec())));
+        let block_info = signer
+            .signer_db
+            .block_lookup(signer.reward_cycle, &signer_signature_hash)
+            .unwrap()
+            .expect("Expected the block to still be stored");
+        assert_eq!(block_info.valid, Some(false));
+    }
+
+    #[test]
+    fn taproot_signature_should_be_broadcast_as_block_response() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let vote = NakamotoBlockVote {
+            signer_signature_hash: block.header.signer_signature_hash(),
+            rejected: false,
+        };
+        signer
+            .coordinator
+            .set_aggregate_public_key(Some(Point::new()));
+        signer
+            .coordinator
+            .start_signing_round(&vote.serialize_to_vec(), true, None)
+            .expect("Failed to start taproot signing round");
+
+        let proof = SchnorrProof::from([1u8; 64]);
+        let expected_message: SignerMessage =
+            BlockResponse::signed_taproot(vote, proof.clone()).into();
+        let ack = StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        };
+        let h = spawn(move || write_ack_response(mock.server, &ack));
+        signer.process_operation_results(&mock.client, &[OperationResult::SignTaproot(proof)]);
+        let request_bytes = h.join().unwrap();
+
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.starts_with("POST /v2/stackerdb/"));
+        assert!(request.contains(&to_hex(&expected_message.serialize_to_vec())));
+    }
+
+    #[test]
+    fn failed_block_response_should_be_resent_on_flush() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block = build_block(2);
+        let vote = NakamotoBlockVote {
+            signer_signature_hash: block.header.signer_signature_hash(),
+            rejected: false,
+        };
+        signer
+            .coordinator
+            .set_aggregate_public_key(Some(Point::new()));
+        signer
+            .coordinator
+            .start_signing_round(&vote.serialize_to_vec(), false, None)
+            .expect("Failed to start signing round");
+
+        let signature = Signature {
+            R: Point::new(),
+            z: Scalar::new(),
+        };
+        let expected_response =
+            BlockResponse::accepted(vote.signer_signature_hash, signature.clone());
+        let expected_message: SignerMessage = expected_response.clone().into();
+
+        // stackerdb rejects the response outright
+        let rejected_ack = StackerDBChunkAckData {
+            accepted: false,
+            reason: Some("No such slot".to_string()),
+            metadata: None,
+            code: Some(1),
+        };
+        let server = mock.server.try_clone().unwrap();
+        let h = spawn(move || write_ack_response(server, &rejected_ack));
+        signer.process_operation_results(&mock.client, &[OperationResult::Sign(signature)]);
+        h.join().unwrap();
+
+        assert_eq!(
+            signer
+                .signer_db
+                .pending_response(signer.reward_cycle)
+                .unwrap(),
+            Some(expected_response)
+        );
+
+        // the resend is not due yet
+        signer.flush_pending_responses();
+        assert!(signer.next_pending_response_flush.unwrap() > Instant::now());
+        signer.next_pending_response_flush = Some(Instant::now());
+
+        // the response is resent once stackerdb accepts chunks again
+        let accepted_ack = StackerDBChunkAckData {
+            accepted: true,
+            reason: None,
+            metadata: None,
+            code: None,
+        };
+        let h = spawn(move || write_ack_response(mock.server, &accepted_ack));
+        signer.flush_pending_responses();
+        let request_bytes = h.join().unwrap();
+
+        let request = String::from_utf8_lossy(&request_bytes);
+        assert!(request.starts_with("POST /v2/stackerdb/"));
+        assert!(request.contains(&to_hex(&expected_message.serialize_to_vec())));
+        assert!(signer
+            .signer_db
+            .pending_response(signer.reward_cycle)
+            .unwrap()
+            .is_none());
+        assert!(signer.next_pending_response_flush.is_none());
+    }
+
+    #[test]
+    fn pending_block_response_should_be_dropped_after_max_attempts() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let block_response = BlockResponse::Rejected(BlockRejection::new(
+            build_block(2).header.signer_signature_hash(),
+            RejectCode::ConnectivityIssues,
+        ));
+        signer
+            .signer_db
+            .set_pending_response(signer.reward_cycle, &block_response)
+            .unwrap();
+        signer.pending_response_attempts = MAX_PENDING_RESPONSE_ATTEMPTS - 1;
+        signer.next_pending_response_flush = Some(Instant::now());
+
+        let rejected_ack = StackerDBChunkAckData {
+            accepted: false,
+            reason: Some("No such slot".to_string()),
+            metadata: None,
+            code: Some(1),
+        };
+        let h = spawn(move || write_ack_response(mock.server, &rejected_ack));
+        signer.flush_pending_responses();
+        h.join().unwrap();
+
+        assert!(signer
+            .signer_db
+            .pending_response(signer.reward_cycle)
+            .unwrap()
+            .is_none());
+        assert_eq!(signer.pending_response_attempts, 0);
+        assert!(signer.next_pending_response_flush.is_none());
+    }
+
+    #[test]
+    fn persisted_state_should_only_load_if_it_matches_the_signer_set() {
+        let mock = MockServerClient::new();
+        let mut signer_config = generate_signer_config(&mock.config, 5, 20);
+        signer_config.db_path = std::env::temp_dir().join(format!(
+            "stacks-signer-test-{}.sqlite",
+            rand::random::<u64>()
+        ));
+        let reward_cycle = signer_config.reward_cycle;
+        let mut state = Signer::from(signer_config.clone())
+            .state_machine
+            .signer
+            .save();
+        let fresh_group_key = state.group_key;
+        let persisted_group_key = Point::from(Scalar::from(42));
+        assert_ne!(fresh_group_key, persisted_group_key);
+
+        // a state persisted for the same signer set is loaded
+        state.group_key = persisted_group_key;
+        let signer_db = SignerDb::new(&signer_config.db_path).unwrap();
+        signer_db.insert_signer_state(reward_cycle, &state).unwrap();
+        let signer = Signer::from(signer_config.clone());
+        assert_eq!(
+            signer.state_machine.signer.save().group_key,
+            persisted_group_key
+        );
+
+        // a state persisted for a different signer set is discarded
+        state.num_keys += 1;
+        signer_db.insert_signer_state(reward_cycle, &state).unwrap();
+        let signer = Signer::from(signer_config.clone());
+        assert_eq!(
+            signer.state_machine.signer.save().group_key,
+            fresh_group_key
+        );
+
+        std::fs::remove_file(signer_config.db_path).unwrap();
+    }
+
+    #[test]
+    fn dkg_should_be_retriggered_after_retry_timeout() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.dkg_retry_timeout = Duration::from_millis(10);
+        // Nothing to retry until a DKG command was queued
+        assert!(!signer.dkg_retry_timed_out());
+
+        signer.queue_dkg_command();
+        assert_eq!(signer.commands.front(), Some(&Command::Dkg));
+        assert!(!signer.dkg_retry_timed_out());
+        // The DKG round ran, but its vote never reached the threshold
+        signer.commands.clear();
+        std::thread::sleep(Duration::from_millis(20));
+
+        signer.state = State::OperationInProgress;
+        assert!(!signer.dkg_retry_timed_out());
+        signer.state = State::Idle;
+        assert!(signer.dkg_retry_timed_out());
+
+        let dkg_id = signer.coordinator.current_dkg_id;
+        signer.retry_dkg();
+        assert_eq!(signer.coordinator.current_dkg_id, dkg_id.wrapping_add(1));
+        assert_eq!(signer.commands.front(), Some(&Command::Dkg));
+        assert!(!signer.dkg_retry_timed_out());
+
+        // An approved aggregate key ends the retries
+        std::thread::sleep(Duration::from_millis(20));
+        signer.approved_aggregate_public_key = Some(Point::new());
+        assert!(!signer.dkg_retry_timed_out());
+    }
+
+    #[test]
+    fn queue_command_should_drop_oldest_sign_commands_but_keep_dkg() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let mut signer_config = generate_signer_config(&config, 5, 20);
+        signer_config.max_pending_commands = 3;
+        let mut signer = Signer::from(signer_config);
+        let sign_command = |chain_length| Command::Sign {
+            block: build_block(chain_length),
+            is_taproot: false,
+            merkle_root: None,
+        };
+
+        signer.queue_command(Command::Dkg);
+        for chain_length in 1..=4 {
+            signer.queue_command(sign_command(chain_length));
+        }
+        assert_eq!(
+            signer.commands,
+            VecDeque::from([Command::Dkg, sign_command(3), sign_command(4)])
+        );
+
+        // a DKG command queued at the front of a full queue still pushes out a sign command
+        signer.commands.pop_front();
+        signer.queue_command(sign_command(5));
+        signer.queue_dkg_command();
+        assert_eq!(
+            signer.commands,
+            VecDeque::from([Command::Dkg, sign_command(4), sign_command(5)])
+        );
+
+        // DKG commands are never dropped, even if the queue is over its limit
+        signer.max_pending_commands = 0;
+        signer.queue_command(sign_command(6));
+        assert_eq!(signer.commands, VecDeque::from([Command::Dkg]));
+    }
+
+    #[test]
+    fn aggregate_key_age_should_count_from_first_adoption() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        assert_eq!(signer.aggregate_key_age(1_000), None);
+
+        signer.approved_aggregate_public_key = Some(Point::from(Scalar::from(42)));
+        // Not adopted yet
+        assert_eq!(signer.aggregate_key_age(1_000), None);
+        signer.record_aggregate_key_adoption(1_000);
+        assert_eq!(signer.aggregate_key_age(1_000), Some(0));
+        assert_eq!(signer.aggregate_key_age(61_000), Some(60_000));
+
+        // Seeing the same key again does not reset its age
+        signer.record_aggregate_key_adoption(5_000);
+        assert_eq!(signer.aggregate_key_age(61_000), Some(60_000));
+
+        // A new key does
+        signer.approved_aggregate_public_key = Some(Point::from(Scalar::from(43)));
+        assert_eq!(signer.aggregate_key_age(61_000), None);
+        signer.record_aggregate_key_adoption(70_000);
+        assert_eq!(signer.aggregate_key_age(71_000), Some(1_000));
+    }
+
+    #[test]
+    fn handle_signer_messages_should_log_packets_in_order_when_enabled() {
+        let mock = MockServerClient::new();
+        let mut signer_config = generate_signer_config(&mock.config, 5, 20);
+        signer_config.log_packets = true;
+        let mut signer = Signer::from(signer_config);
+        let reward_cycle = signer.reward_cycle;
+        let before = get_epoch_time_ms() as u64;
+
+        let dkg_ids = vec![3, 1, 2];
+        for dkg_id in dkg_ids.iter() {
+            let packet = Packet {
+                msg: Message::DkgEnd(DkgEnd {
+                    dkg_id: *dkg_id,
+                    signer_id: 1,
+                    status: DkgStatus::Success,
+                }),
+                sig: vec![],
+            };
+            let (res, _) = channel();
+            signer.handle_signer_messages(
+                &mock.client,
+                res,
+                &[SignerMessage::Packet(packet)],
+                reward_cycle,
+            );
+        }
+
+        // Packets the signer dispatches to itself are not inbound and must not be logged
+        let packet = Packet {
+            msg: Message::DkgEnd(DkgEnd {
+                dkg_id: 4,
+                signer_id: 1,
+                status: DkgStatus::Success,
+            }),
+            sig: vec![],
+        };
+        let (res, _) = channel();
+        signer.handle_packets(&mock.client, res, &[packet], reward_cycle);
+
+        let logged_dkg_ids: Vec<_> = signer
+            .signer_db
+            .packet_trace(before)
+            .expect("Failed to get packet trace")
+            .into_iter()
+            .map(|packet| match packet.msg {
+                Message::DkgEnd(dkg_end) => dkg_end.dkg_id,
+                _ => panic!("Unexpected packet in the packet log"),
+            })
+            .collect();
+        assert_eq!(logged_dkg_ids, dkg_ids);
+
+        let after = get_epoch_time_ms() as u64 + 1;
+        assert!(signer.signer_db.packet_trace(after).unwrap().is_empty());
+    }
+
+    #[test]
+    fn verify_key_consistency_should_catch_mismatched_coordinator_key() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        // Nothing to check until a key is approved
+        signer.verify_key_consistency().unwrap();
+
+        let aggregate_key = Point::from(Scalar::from(42));
+        let mut state = signer.state_machine.signer.save();
+        state.group_key = aggregate_key;
+        signer.state_machine.signer = v2::Signer::load(&state);
+        signer.approved_aggregate_public_key = Some(aggregate_key);
+        signer
+            .coordinator
+            .set_aggregate_public_key(Some(aggregate_key));
+        signer.verify_key_consistency().unwrap();
+
+        let mismatched_key = Point::from(Scalar::from(43));
+        signer
+            .coordinator
+            .set_aggregate_public_key(Some(mismatched_key));
+        match signer.verify_key_consistency() {
+            Err(SignerError::InconsistentAggregateKey {
+                approved,
+                coordinator,
+                signer,
+            }) => {
+                assert_eq!(approved, aggregate_key);
+                assert_eq!(coordinator, Some(mismatched_key));
+                assert_eq!(signer, aggregate_key);
+            }
+            res => panic!("Expected an inconsistent aggregate key, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn blocks_in_flight_should_only_include_unrejected_signed_over_blocks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let reward_cycle = signer.reward_cycle;
+        assert!(signer.blocks_in_flight().unwrap().is_empty());
+
+        let mut insert_block = |chain_length, signed_over: bool, rejected: Option<bool>| {
+            let mut block_info = BlockInfo::new(build_block(chain_length));
+            block_info.signed_over = signed_over;
+            block_info.vote = rejected.map(|rejected| NakamotoBlockVote {
+                signer_signature_hash: block_info.signer_signature_hash(),
+                rejected,
+            });
+            signer
+                .signer_db
+                .insert_block(reward_cycle, &block_info)
+                .unwrap();
+            block_info.signer_signature_hash()
+        };
+        let undecided = insert_block(1, true, None);
+        let accepted = insert_block(2, true, Some(false));
+        insert_block(3, true, Some(true));
+        insert_block(4, false, None);
+
+        let mut in_flight = vec![undecided, accepted];
+        in_flight.sort();
+        let mut blocks_in_flight = signer.blocks_in_flight().unwrap();
+        blocks_in_flight.sort();
+        assert_eq!(blocks_in_flight, in_flight);
+
+        // An invalid block is not in flight, even before the signer votes on it
+        let mut invalid = BlockInfo::new(build_block(5));
+        invalid.signed_over = true;
+        invalid.valid = Some(false);
+        signer
+            .signer_db
+            .insert_block(reward_cycle, &invalid)
+            .unwrap();
+        let mut blocks_in_flight = signer.blocks_in_flight().unwrap();
+        blocks_in_flight.sort();
+        assert_eq!(blocks_in_flight, in_flight);
+    }
+
+    #[test]
+    fn max_offline_signers_should_tolerate_losing_the_largest_signers() {
+        // 5 signers with 4 keys each: the DKG threshold of 18 keys needs every signer
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let signer = Signer::from(signer_config);
+        assert_eq!(signer.coordinator.get_config().dkg_threshold, 18);
+        assert_eq!(signer.max_offline_signers(), 0);
+
+        let max_offline = |key_counts: &[usize], threshold| {
+            Signer::max_offline_signers_for(key_counts.iter().copied(), threshold)
+        };
+        assert_eq!(max_offline(&[4; 5], 18), 0);
+        assert_eq!(max_offline(&[1; 10], 9), 1);
+        assert_eq!(max_offline(&[10; 10], 90), 1);
+        assert_eq!(max_offline(&[1; 20], 14), 6);
+        // The signers holding the most keys are assumed offline first
+        assert_eq!(max_offline(&[1, 10, 1, 1, 1, 1], 14), 0);
+        assert_eq!(max_offline(&[1, 10, 1, 1, 1, 1], 5), 1);
+        assert_eq!(max_offline(&[], 0), 0);
+    }
+
+    #[test]
+    fn effective_online_threshold_should_require_enough_online_keys() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        // 5 signers with 4 keys each, so 4 signers are needed to meet the threshold of 14 keys
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let signer = Signer::from(signer_config);
+        assert_eq!(signer.coordinator.get_config().threshold, 14);
+
+        assert!(!signer.effective_online_threshold(&HashSet::new()));
+        assert!(!signer.effective_online_threshold(&HashSet::from_iter([0, 1, 2])));
+        assert!(signer.effective_online_threshold(&HashSet::from_iter([0, 1, 2, 3])));
+        assert!(signer.effective_online_threshold(&HashSet::from_iter([1, 2, 3, 4])));
+        assert!(signer.effective_online_threshold(&HashSet::from_iter(0..5)));
+        // Unknown signers carry no weight
+        assert!(!signer.effective_online_threshold(&HashSet::from_iter([0, 1, 2, 5, 6])));
+    }
+
+    #[test]
+    fn is_signer_address_should_report_signer_set_membership() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        assert_eq!(signer.signer_addresses.len(), 5);
+
+        let current_only = signer.signer_addresses[0];
+        let both = signer.signer_addresses[1];
+        let next_only = StacksAddress::burn_address(false);
+        let neither = StacksAddress::burn_address(true);
+        signer.next_signer_addresses = vec![both, next_only];
+
+        assert_eq!(
+            signer.is_signer_address(&current_only),
+            SignerSetMembership::Current
+        );
+        assert_eq!(signer.is_signer_address(&both), SignerSetMembership::Both);
+        assert_eq!(
+            signer.is_signer_address(&next_only),
+            SignerSetMembership::Next
+        );
+        assert_eq!(
+            signer.is_signer_address(&neither),
+            SignerSetMembership::None
+        );
+    }
+
+    #[test]
+    fn gc_expired_blocks_should_only_remove_old_decided_blocks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let reward_cycle = signer.reward_cycle;
+        let now = 10_000;
+        let max_age_secs = 100;
+
+        let mut insert_block = |chain_length, age_secs: u64, rejected: Option<bool>| {
+            let block = build_block(chain_length);
+            let mut block_info = BlockInfo::new(block.clone());
+            block_info.proposed_at = (now - age_secs) * 1000;
+            block_info.vote = rejected.map(|rejected| NakamotoBlockVote {
+                signer_signature_hash: block_info.signer_signature_hash(),
+                rejected,
+            });
+            signer
+                .signer_db
+                .insert_block(reward_cycle, &block_info)
+                .unwrap();
+            block.header.signer_signature_hash()
+        };
+        let old_accepted = insert_block(1, 500, Some(false));
+        let old_rejected = insert_block(2, 500, Some(true));
+        let old_pending = insert_block(3, 500, None);
+        let recent_accepted = insert_block(4, 10, Some(false));
+        let recent_pending = insert_block(5, 10, None);
+
+        signer.gc_expired_blocks(max_age_secs, now);
+
+        let lookup = |hash| signer.signer_db.block_lookup(reward_cycle, &hash).unwrap();
+        assert!(lookup(old_accepted).is_none());
+        assert!(lookup(old_rejected).is_none());
+        assert!(lookup(old_pending).is_some());
+        assert!(lookup(recent_accepted).is_some());
+        assert!(lookup(recent_pending).is_some());
+    }
+
+    #[test]
+    fn gc_stale_blocks_should_only_keep_recent_blocks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        let reward_cycle = signer.reward_cycle;
+        signer.gc_stale_blocks(10);
+
+        let mut insert_block = |chain_length| {
+            let block_info = BlockInfo::new(build_block(chain_length));
+            signer
+                .signer_db
+                .insert_block(reward_cycle, &block_info)
+                .unwrap();
+            block_info.signer_signature_hash()
+        };
+        let stale = insert_block(1);
+        let oldest_kept = insert_block(20);
+        let recent = insert_block(25);
+        let highest = insert_block(30);
+
+        signer.gc_stale_blocks(10);
+
+        let lookup = |hash| signer.signer_db.block_lookup(reward_cycle, &hash).unwrap();
+        assert!(lookup(stale).is_none());
+        assert!(lookup(oldest_kept).is_some());
+        assert!(lookup(recent).is_some());
+        assert!(lookup(highest).is_some());
+    }
+
+    #[test]
+    fn status_check_should_expire_blocks_from_old_reward_cycles() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let stacks_client = StacksClient::from(&config);
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.reward_cycle = 10;
+
+        let mut hashes = vec![];
+        for reward_cycle in 7..=10 {
+            let block = build_block(reward_cycle);
+            signer
+                .signer_db
+                .insert_block(reward_cycle, &BlockInfo::new(block.clone()))
+                .unwrap();
+            hashes.push((reward_cycle, block.header.signer_signature_hash()));
+        }
+
+        let (res_send, _res_recv) = channel();
+        signer
+            .process_event(
+                &stacks_client,
+                Some(&SignerEvent::StatusCheck),
+                res_send,
+                10,
+            )
+            .unwrap();
+
+        for (reward_cycle, hash) in hashes {
+            let block_info = signer.signer_db.block_lookup(reward_cycle, &hash).unwrap();
+            assert_eq!(block_info.is_some(), reward_cycle >= 9);
+        }
+    }
+
+    #[test]
+    fn recover_commands_should_queue_validated_unsigned_blocks() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let stacks_client = StacksClient::from(&config);
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        // Have an approved key so no DKG needs to be recovered
+        signer.approved_aggregate_public_key = Some(Point::new());
+        let reward_cycle = signer.reward_cycle;
+
+        let mut insert_block = |chain_length, valid: Option<bool>, signed_over: bool| {
+            let block = build_block(chain_length);
+            let mut block_info = BlockInfo::new(block.clone());
+            block_info.valid = valid;
+            block_info.signed_over = signed_over;
+            signer
+                .signer_db
+                .insert_block(reward_cycle, &block_info)
+                .unwrap();
+            block
+        };
+        let validated_unsigned = insert_block(1, Some(true), false);
+        insert_block(2, Some(true), true);
+        insert_block(3, Some(false), false);
+        insert_block(4, None, false);
+
+        // Not the coordinator: in the active reward cycle, the miner coordinates
+        signer.miner_key = Some(signer.coordinator_selector.get_coordinator().1);
+        signer.recover_commands(&stacks_client, reward_cycle);
+        assert!(signer.commands.is_empty());
+
+        // The coordinator outside of the active reward cycle
+        signer.signer_id = signer.coordinator_selector.get_coordinator().0;
+        signer.recover_commands(&stacks_client, reward_cycle.wrapping_add(1));
+        let expected_command = Command::Sign {
+            block: validated_unsigned,
+            is_taproot: false,
+            merkle_root: None,
+        };
+        assert_eq!(signer.commands, VecDeque::from([expected_command.clone()]));
+
+        // Recovering again does not duplicate commands
+        signer.recover_commands(&stacks_client, reward_cycle.wrapping_add(1));
+        assert_eq!(signer.commands, VecDeque::from([expected_command]));
+    }
+
+    #[test]
+    fn verify_tx_merkle_root_should_detect_tampered_roots() {
+        let block = build_block(2);
+        assert!(Signer::verify_tx_merkle_root(&block));
+
+        let mut block = build_block(3);
+        block.txs = vec![build_transaction(0), build_transaction(1)];
+        let txid_vecs = block
+            .txs
+            .iter()
+            .map(|tx| tx.txid().as_bytes().to_vec())
+            .collect();
+        block.header.tx_merkle_root = MerkleTree::<Sha512Trunc256Sum>::new(&txid_vecs).root();
+        assert!(Signer::verify_tx_merkle_root(&block));
+
+        // A tampered root
+        let mut tampered_block = block.clone();
+        tampered_block.header.tx_merkle_root = Sha512Trunc256Sum([0x06; 32]);
+        assert!(!Signer::verify_tx_merkle_root(&tampered_block));
+
+        // Transactions that no longer match the root
+        let mut tampered_block = block.clone();
+        tampered_block.txs.pop();
+        assert!(!Signer::verify_tx_merkle_root(&tampered_block));
+    }
+
+    #[test]
+    fn check_proposed_block_should_reject_mismatched_merkle_root() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        signer.approved_aggregate_public_key = Some(Point::new());
+
+        let mut block = build_block(2);
+        assert_eq!(signer.check_proposed_block(&block), None);
+
+        block.header.tx_merkle_root = Sha512Trunc256Sum([0x06; 32]);
+        assert_eq!(
+            signer.check_proposed_block(&block),
+            Some(RejectCode::InvalidMerkleRoot)
+        );
+    }
+
+    #[test]
+    fn check_aggregate_key_should_reject_normal_blocks_without_key() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let stacks_client = StacksClient::from(&config);
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        assert!(signer.approved_aggregate_public_key.is_none());
+
+        let mut block = build_block(2);
+        block.txs.push(build_transaction(0));
+        assert_eq!(
+            signer.check_aggregate_key(&block),
+            Some(RejectCode::NoAggregateKey)
+        );
+
+        // Prepare phase blocks carrying aggregate key votes are still considered
+        let mut vote_block = build_block(3);
+        vote_block.txs.push(
+            stacks_client
+                .build_vote_for_aggregate_public_key(
+                    0,
+                    0,
+                    Point::from(Scalar::from(42)),
+                    signer.reward_cycle,
+                    None,
+                    0,
+                )
+                .unwrap(),
+        );
+        assert_eq!(signer.check_aggregate_key(&vote_block), None);
+
+        // Any block is considered once a key is approved
+        signer.approved_aggregate_public_key = Some(Point::new());
+        assert_eq!(signer.check_aggregate_key(&block), None);
+    }
+
+    #[test]
+    fn coordinator_change_mid_round_should_be_counted() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+        assert_eq!(signer.metrics(), SignerMetrics::default());
+
+        // A coordinator change while idle is not counted
+        signer.reset_for_new_coordinator(0, 1);
+        assert_eq!(signer.metrics().coordinator_changes_mid_round, 0);
+
+        signer.update_operation();
+        assert_eq!(signer.state, State::OperationInProgress);
+        signer.reset_for_new_coordinator(1, 2);
+        assert_eq!(signer.state, State::Idle);
+        assert_eq!(signer.coordinator.state, CoordinatorState::Idle);
+        assert_eq!(signer.metrics().coordinator_changes_mid_round, 1);
+    }
+
+    #[test]
+    fn debug_snapshot_should_reflect_signer_state() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+
+        let snapshot = signer.debug_snapshot();
+        assert_eq!(snapshot.state, State::Idle);
+        assert_eq!(snapshot.reward_cycle, signer.reward_cycle);
+        assert_eq!(snapshot.signer_id, signer.signer_id);
+        assert_eq!(snapshot.approved_aggregate_public_key, None);
+        assert!(snapshot.commands.is_empty());
+        assert!(snapshot.queued_sign_blocks.is_empty());
+
+        let block = build_block(2);
+        signer.commands.push_back(Command::Dkg);
+        signer.commands.push_back(Command::Sign {
+            block: block.clone(),
+            is_taproot: false,
+            merkle_root: None,
+        });
+        let aggregate_key = Point::from(Scalar::from(7));
+        signer.approved_aggregate_public_key = Some(aggregate_key);
+        signer.update_operation();
+
+        let snapshot = signer.debug_snapshot();
+        assert_eq!(snapshot.state, State::OperationInProgress);
+        assert_eq!(snapshot.approved_aggregate_public_key, Some(aggregate_key));
+        assert_eq!(snapshot.commands, vec![CommandKind::Dkg, CommandKind::Sign]);
+        assert_eq!(
+            snapshot.coordinator_id,
+            signer.coordinator_selector.get_coordinator().0
+        );
+        assert_eq!(
+            snapshot.queued_sign_blocks,
+            vec![block.header.signer_signature_hash()]
+        );
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let decoded: SignerSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, snapshot);
+    }
+
+    #[test]
+    fn status_should_reflect_signer_state() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+
+        let status = signer.status();
+        assert_eq!(status.state, State::Idle);
+        assert_eq!(status.reward_cycle, signer.reward_cycle);
+        assert!(!status.has_approved_aggregate_key);
+        assert_eq!(
+            status.coordinator_id,
+            signer.coordinator_selector.get_coordinator().0
+        );
+        assert_eq!(status.pending_commands, 0);
+        assert_eq!(status.operation_elapsed_ms, None);
+
+        signer.commands.push_back(Command::Dkg);
+        signer.approved_aggregate_public_key = Some(Point::from(Scalar::from(7)));
+        signer.update_operation();
+
+        let status = signer.status();
+        assert_eq!(status.state, State::OperationInProgress);
+        assert!(status.has_approved_aggregate_key);
+        assert_eq!(status.pending_commands, 1);
+        assert!(status.operation_elapsed_ms.is_some());
+
+        let json = serde_json::to_string(&status).unwrap();
+        let decoded: SignerStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, status);
+    }
+
+    #[test]
+    fn poll_interval_should_back_off_when_idle() {
+        let config = GlobalConfig::load_from_file("./src/tests/conf/signer-0.toml").unwrap();
+        let signer_config = generate_signer_config(&config, 5, 20);
+        let mut signer = Signer::from(SignerConfig {
+            idle_poll_interval: Duration::from_secs(5),
+            active_poll_interval: Duration::from_millis(100),
+            ..signer_config
+        });
+
+        assert_eq!(signer.poll_interval(), Duration::from_secs(5));
+
+        // a queued command keeps the signer active
+        signer.commands.push_back(Command::Dkg);
+        assert_eq!(signer.poll_interval(), Duration::from_millis(100));
+
+        signer.commands.clear();
+        signer.update_operation();
+        assert_eq!(signer.poll_interval(), Duration::from_millis(100));
+
+        signer.finish_operation();
+        assert_eq!(signer.poll_interval(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn dkg_update_should_be_throttled() {
+        let mock = MockServerClient::new();
+        let signer_config = generate_signer_config(&mock.config, 5, 20);
+        let mut signer = Signer::from(signer_config);
+
+        assert!(signer.dkg_update_due());
+        assert!(!signer.dkg_update_due());
+
+        signer.next_dkg_update = Instant::now();
+        assert!(signer.dkg_update_due());
+        assert!(!signer.dkg_update_due());
+    }
+}
@@ -14,15 +14,25 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::io::{Read, Write};
 use std::path::Path;
+use std::time::Duration;
 
+use blockstack_lib::chainstate::nakamoto::NakamotoBlockVote;
 use blockstack_lib::util_lib::db::{
-    query_row, sqlite_open, table_exists, u64_to_sql, Error as DBError,
+    query_row, query_rows, sqlite_open, table_exists, tx_begin_immediate, u64_to_sql, DBTx,
+    Error as DBError, FromRow,
 };
-use rusqlite::{params, Connection, Error as SqliteError, OpenFlags, NO_PARAMS};
-use slog::slog_debug;
-use stacks_common::debug;
+use libflate::gzip;
+use libsigner::BlockResponse;
+use rusqlite::{params, Connection, Error as SqliteError, OpenFlags, Row, NO_PARAMS};
+use slog::{slog_debug, slog_warn};
+use stacks_common::types::chainstate::ConsensusHash;
 use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
+use stacks_common::{debug, warn};
+use wsts::curve::point::Point;
+use wsts::net::Packet;
 use wsts::traits::SignerState;
 
 use crate::signer::BlockInfo;
@@ -33,6 +43,32 @@ use crate::signer::BlockInfo;
 pub struct SignerDb {
     /// Connection to the SQLite database
     db: Connection,
+    /// Whether to gzip the block info of newly inserted blocks
+    compress_block_info: bool,
+}
+
+/// The number of rows in a table and the approximate number of bytes they take up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TableStats {
+    /// The number of rows in the table
+    pub rows: u64,
+    /// The total size of the table's serialized data, in bytes
+    pub bytes: u64,
+}
+
+/// Per-table storage statistics of the signer database, for capacity monitoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageStats {
+    /// Stats of the `blocks` table
+    pub blocks: TableStats,
+    /// Stats of the `signer_states` table
+    pub signer_states: TableStats,
+    /// Stats of the `packet_log` table
+    pub packet_log: TableStats,
+    /// Stats of the `pending_responses` table
+    pub pending_responses: TableStats,
+    /// Stats of the `aggregate_keys` table
+    pub aggregate_keys: TableStats,
 }
 
 const CREATE_BLOCKS_TABLE: &'static str = "
@@ -40,41 +76,275 @@ CREATE TABLE IF NOT EXISTS blocks (
     reward_cycle INTEGER NOT NULL,
     signer_signature_hash TEXT NOT NULL,
     block_info TEXT NOT NULL,
+    compressed INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (reward_cycle, signer_signature_hash)
 )";
 
+const CREATE_BLOCKS_CHAIN_LENGTH_INDEX: &'static str = "
+CREATE INDEX IF NOT EXISTS blocks_chain_length ON blocks (chain_length)";
+
+const CREATE_BLOCKS_SIGNED_OVER_INDEX: &'static str = "
+CREATE INDEX IF NOT EXISTS blocks_signed_over ON blocks (consensus_hash, signed_over, chain_length)";
+
+/// Reward cycle under which legacy blocks are stored when their reward cycle cannot be derived
+pub const UNKNOWN_REWARD_CYCLE: u64 = i64::MAX as u64;
+
 const CREATE_SIGNER_STATE_TABLE: &'static str = "
 CREATE TABLE IF NOT EXISTS signer_states (
     reward_cycle INTEGER PRIMARY KEY,
     state TEXT NOT NULL
 )";
 
+const CREATE_PACKET_LOG_TABLE: &'static str = "
+CREATE TABLE IF NOT EXISTS packet_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    received_at INTEGER NOT NULL,
+    packet TEXT NOT NULL
+)";
+
+const CREATE_PENDING_RESPONSES_TABLE: &'static str = "
+CREATE TABLE IF NOT EXISTS pending_responses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    reward_cycle INTEGER NOT NULL,
+    response TEXT NOT NULL
+)";
+
+const CREATE_SCHEMA_VERSION_TABLE: &'static str = "
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER NOT NULL
+)";
+
+const CREATE_AGGREGATE_KEYS_TABLE: &'static str = "
+CREATE TABLE IF NOT EXISTS aggregate_keys (
+    reward_cycle INTEGER PRIMARY KEY,
+    aggregate_key TEXT NOT NULL,
+    adopted_at INTEGER NOT NULL
+)";
+
 impl SignerDb {
+    /// The schema version that `migrate` brings the database to
+    pub const SCHEMA_VERSION: u32 = 5;
+
     /// Create a new `SignerState` instance.
     /// This will create a new SQLite database at the given path
     /// or an in-memory database if the path is ":memory:"
     pub fn new(db_path: impl AsRef<Path>) -> Result<Self, DBError> {
         let connection = Self::connect(db_path)?;
 
-        let signer_db = Self { db: connection };
+        let mut signer_db = Self {
+            db: connection,
+            compress_block_info: false,
+        };
 
-        signer_db.instantiate_db()?;
+        signer_db.migrate()?;
 
         Ok(signer_db)
     }
 
-    fn instantiate_db(&self) -> Result<(), DBError> {
-        if !table_exists(&self.db, "blocks")? {
-            self.db.execute(CREATE_BLOCKS_TABLE, NO_PARAMS)?;
+    /// Get the schema version of the database. Databases that predate the `schema_version`
+    /// table are at version 0.
+    fn get_schema_version(conn: &Connection) -> Result<u32, DBError> {
+        if !table_exists(conn, "schema_version")? {
+            return Ok(0);
+        }
+        let version: Option<i64> =
+            query_row(conn, "SELECT version FROM schema_version", NO_PARAMS)?;
+        u32::try_from(version.unwrap_or(0)).map_err(|_| DBError::ParseError)
+    }
+
+    /// Bring the database up to `SCHEMA_VERSION` by applying each migration step in order,
+    /// all within a single transaction
+    fn migrate(&mut self) -> Result<(), DBError> {
+        let tx = tx_begin_immediate(&mut self.db)?;
+        loop {
+            let version = Self::get_schema_version(&tx)?;
+            match version {
+                0 => Self::schema_1_migration(&tx)?,
+                1 => Self::schema_2_migration(&tx)?,
+                2 => Self::schema_3_migration(&tx)?,
+                3 => Self::schema_4_migration(&tx)?,
+                4 => Self::schema_5_migration(&tx)?,
+                Self::SCHEMA_VERSION => break,
+                x => {
+                    return Err(DBError::Other(format!(
+                        "Signer database schema version {x} is newer than supported by this binary ({})",
+                        Self::SCHEMA_VERSION
+                    )))
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write the schema version of the database
+    fn set_schema_version(tx: &DBTx, version: u32) -> Result<(), DBError> {
+        tx.execute("DELETE FROM schema_version", NO_PARAMS)?;
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            &[version],
+        )?;
+        Ok(())
+    }
+
+    /// Create the version 1 schema. Databases created before schema versioning may already
+    /// hold some of its tables, and may lack the `compressed` column of the `blocks` table.
+    fn schema_1_migration(tx: &DBTx) -> Result<(), DBError> {
+        for statement in [
+            CREATE_SCHEMA_VERSION_TABLE,
+            CREATE_BLOCKS_TABLE,
+            CREATE_SIGNER_STATE_TABLE,
+            CREATE_PACKET_LOG_TABLE,
+            CREATE_PENDING_RESPONSES_TABLE,
+        ] {
+            tx.execute(statement, NO_PARAMS)?;
+        }
+
+        let has_compressed: Option<i64> = query_row(
+            tx,
+            "SELECT COUNT(*) FROM pragma_table_info('blocks') WHERE name = 'compressed'",
+            NO_PARAMS,
+        )?;
+        if has_compressed.unwrap_or(0) == 0 {
+            tx.execute(
+                "ALTER TABLE blocks ADD COLUMN compressed INTEGER NOT NULL DEFAULT 0",
+                NO_PARAMS,
+            )?;
+        }
+
+        Self::set_schema_version(tx, 1)
+    }
+
+    /// Add the `chain_length` column to the `blocks` table, so that stale blocks can be removed
+    /// by height through an index
+    fn schema_2_migration(tx: &DBTx) -> Result<(), DBError> {
+        Self::add_chain_length_column(tx)?;
+        Self::set_schema_version(tx, 2)
+    }
+
+    /// Add the indexed `chain_length` column to the `blocks` table if it is missing, and fill it
+    /// in from the block info of every block that lacks it
+    fn add_chain_length_column(conn: &Connection) -> Result<(), DBError> {
+        let has_chain_length: Option<i64> = query_row(
+            conn,
+            "SELECT COUNT(*) FROM pragma_table_info('blocks') WHERE name = 'chain_length'",
+            NO_PARAMS,
+        )?;
+        if has_chain_length.unwrap_or(0) == 0 {
+            conn.execute(
+                "ALTER TABLE blocks ADD COLUMN chain_length INTEGER",
+                NO_PARAMS,
+            )?;
+        }
+        conn.execute(
+            "UPDATE blocks SET chain_length = json_extract(block_info, '$.block.header.chain_length') WHERE chain_length IS NULL AND compressed = 0",
+            NO_PARAMS,
+        )?;
+
+        // Compressed block info cannot be inspected with SQLite's JSON functions
+        let compressed_blocks: Vec<(u64, BlockInfo)> = {
+            let mut stmt = conn.prepare(
+                "SELECT block_info, compressed, reward_cycle FROM blocks WHERE chain_length IS NULL AND compressed = 1",
+            )?;
+            let rows =
+                stmt.query_and_then(NO_PARAMS, |row| -> Result<(u64, BlockInfo), DBError> {
+                    let reward_cycle: i64 = row.get(2)?;
+                    let reward_cycle =
+                        u64::try_from(reward_cycle).map_err(|_| DBError::ParseError)?;
+                    Ok((reward_cycle, BlockInfo::from_row(row)?))
+                })?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (reward_cycle, block_info) in compressed_blocks {
+            conn.execute(
+                "UPDATE blocks SET chain_length = ?1 WHERE reward_cycle = ?2 AND signer_signature_hash = ?3",
+                params![
+                    &u64_to_sql(block_info.block.header.chain_length)?,
+                    &u64_to_sql(reward_cycle)?,
+                    block_info.signer_signature_hash().to_string()
+                ],
+            )?;
+        }
+
+        conn.execute(CREATE_BLOCKS_CHAIN_LENGTH_INDEX, NO_PARAMS)?;
+        Ok(())
+    }
+
+    /// Add the `aggregate_keys` table, recording when each reward cycle's aggregate key was adopted
+    fn schema_3_migration(tx: &DBTx) -> Result<(), DBError> {
+        tx.execute(CREATE_AGGREGATE_KEYS_TABLE, NO_PARAMS)?;
+        Self::set_schema_version(tx, 3)
+    }
+
+    /// Move the blocks of a legacy `blocks` table, which predates the `reward_cycle` column, under
+    /// the reward cycle derived by `legacy_reward_cycle()`
+    fn schema_4_migration(tx: &DBTx) -> Result<(), DBError> {
+        let legacy_reward_cycle = Self::legacy_reward_cycle(tx)?;
+        Self::backfill_reward_cycles(tx, |_| legacy_reward_cycle)?;
+        Self::set_schema_version(tx, 4)
+    }
+
+    /// Add the `consensus_hash` and `signed_over` columns to the `blocks` table. Like
+    /// `chain_length`, they mirror the corresponding fields of each block's info, so that blocks
+    /// can be looked up by them through an index, without parsing or decompressing their info.
+    fn schema_5_migration(tx: &DBTx) -> Result<(), DBError> {
+        for statement in [
+            "ALTER TABLE blocks ADD COLUMN consensus_hash TEXT",
+            "ALTER TABLE blocks ADD COLUMN signed_over INTEGER NOT NULL DEFAULT 0",
+            "UPDATE blocks SET consensus_hash = json_extract(block_info, '$.block.header.consensus_hash'), signed_over = COALESCE(json_extract(block_info, '$.signed_over'), 0) WHERE compressed = 0",
+            CREATE_BLOCKS_SIGNED_OVER_INDEX,
+        ] {
+            tx.execute(statement, NO_PARAMS)?;
+        }
+
+        let compressed_blocks: Vec<(u64, BlockInfo)> = {
+            let mut stmt = tx.prepare(
+                "SELECT block_info, compressed, reward_cycle FROM blocks WHERE compressed = 1",
+            )?;
+            let rows =
+                stmt.query_and_then(NO_PARAMS, |row| -> Result<(u64, BlockInfo), DBError> {
+                    let reward_cycle: i64 = row.get(2)?;
+                    let reward_cycle =
+                        u64::try_from(reward_cycle).map_err(|_| DBError::ParseError)?;
+                    Ok((reward_cycle, BlockInfo::from_row(row)?))
+                })?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (reward_cycle, block_info) in compressed_blocks {
+            Self::store_block(tx, true, reward_cycle, &block_info)?;
         }
 
-        if !table_exists(&self.db, "signer_states")? {
-            self.db.execute(CREATE_SIGNER_STATE_TABLE, NO_PARAMS)?;
+        Self::set_schema_version(tx, 5)
+    }
+
+    /// Derive the reward cycle of the blocks of a legacy `blocks` table from the signer states
+    /// stored alongside them. A signer only stores the blocks proposed in the reward cycles it
+    /// saved its state for, so if it only ever saved its state for one reward cycle, every legacy
+    /// block belongs to it. Otherwise, the blocks' reward cycle cannot be derived.
+    fn legacy_reward_cycle(conn: &Connection) -> Result<Option<u64>, DBError> {
+        let reward_cycles: Vec<u64> =
+            query_rows(conn, "SELECT reward_cycle FROM signer_states", NO_PARAMS)?;
+        match reward_cycles.as_slice() {
+            [reward_cycle] => Ok(Some(*reward_cycle)),
+            _ => Ok(None),
         }
+    }
+
+    /// Set whether the block info of blocks inserted from now on is gzipped before being stored.
+    /// Blocks already in the database are read back regardless of how they were stored.
+    pub fn set_compression(&mut self, enabled: bool) {
+        self.compress_block_info = enabled;
+    }
 
+    /// Set how long queries wait for the database to be unlocked before failing with
+    /// `SQLITE_BUSY`.  This replaces the default busy handler, which retries indefinitely.
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<(), DBError> {
+        self.db.busy_timeout(timeout)?;
         Ok(())
     }
 
+    /// Open the database.  File-backed databases are put in WAL mode, so that block lookups
+    /// are not blocked by concurrent writes.
     fn connect(db_path: impl AsRef<Path>) -> Result<Connection, SqliteError> {
         sqlite_open(
             db_path,
@@ -108,6 +378,168 @@ impl SignerDb {
         Ok(())
     }
 
+    /// Get every stored signer state, along with its signer ID and reward cycle,
+    /// in ascending reward cycle order
+    pub fn get_all_signer_states(&self) -> Result<Vec<(u32, u64, SignerState)>, DBError> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT reward_cycle, state FROM signer_states ORDER BY reward_cycle")?;
+        let rows = stmt.query_and_then(
+            NO_PARAMS,
+            |row| -> Result<(u32, u64, SignerState), DBError> {
+                let reward_cycle: i64 = row.get(0)?;
+                let state: String = row.get(1)?;
+                let reward_cycle = u64::try_from(reward_cycle).map_err(|_| DBError::ParseError)?;
+                let state: SignerState =
+                    serde_json::from_str(&state).map_err(DBError::SerializationError)?;
+                Ok((state.id, reward_cycle, state))
+            },
+        )?;
+        rows.collect()
+    }
+
+    /// Remove the signer states of every reward cycle before `reward_cycle`
+    pub fn delete_signer_states_before(&self, reward_cycle: u64) -> Result<(), DBError> {
+        debug!("Removing signer states before reward cycle {reward_cycle}");
+        self.db.execute(
+            "DELETE FROM signer_states WHERE reward_cycle < ?",
+            &[u64_to_sql(reward_cycle)?],
+        )?;
+        Ok(())
+    }
+
+    /// Get the aggregate key adopted for the given reward cycle and when it was adopted,
+    /// in milliseconds, if any
+    pub fn get_aggregate_key_adoption(
+        &self,
+        reward_cycle: u64,
+    ) -> Result<Option<(Point, u64)>, DBError> {
+        let mut stmt = self.db.prepare(
+            "SELECT aggregate_key, adopted_at FROM aggregate_keys WHERE reward_cycle = ?",
+        )?;
+        let mut rows = stmt.query_and_then(
+            &[u64_to_sql(reward_cycle)?],
+            |row| -> Result<(Point, u64), DBError> {
+                let aggregate_key: String = row.get(0)?;
+                let adopted_at: i64 = row.get(1)?;
+                let aggregate_key =
+                    serde_json::from_str(&aggregate_key).map_err(DBError::SerializationError)?;
+                let adopted_at = u64::try_from(adopted_at).map_err(|_| DBError::ParseError)?;
+                Ok((aggregate_key, adopted_at))
+            },
+        )?;
+        rows.next().transpose()
+    }
+
+    /// Record that the given aggregate key was adopted for the given reward cycle at `adopted_at`
+    /// (in milliseconds), replacing any previously adopted key
+    pub fn insert_aggregate_key_adoption(
+        &self,
+        reward_cycle: u64,
+        aggregate_key: &Point,
+        adopted_at: u64,
+    ) -> Result<(), DBError> {
+        let serialized_key = serde_json::to_string(aggregate_key)?;
+        self.db.execute(
+            "INSERT OR REPLACE INTO aggregate_keys (reward_cycle, aggregate_key, adopted_at) VALUES (?1, ?2, ?3)",
+            params![&u64_to_sql(reward_cycle)?, &serialized_key, &u64_to_sql(adopted_at)?],
+        )?;
+        Ok(())
+    }
+
+    /// Get every reward cycle for which the database holds a signer state, a block, a pending
+    /// block response or an aggregate key, in ascending order.
+    /// Blocks stored under `UNKNOWN_REWARD_CYCLE` are not attributed to any reward cycle.
+    pub fn all_reward_cycles(&self) -> Result<Vec<u64>, DBError> {
+        query_rows(
+            &self.db,
+            "SELECT reward_cycle FROM (
+                SELECT reward_cycle FROM signer_states
+                UNION SELECT reward_cycle FROM blocks
+                UNION SELECT reward_cycle FROM pending_responses
+                UNION SELECT reward_cycle FROM aggregate_keys
+            ) WHERE reward_cycle != ?1 ORDER BY reward_cycle",
+            params![&u64_to_sql(UNKNOWN_REWARD_CYCLE)?],
+        )
+    }
+
+    /// Record an inbound packet in the `packet_log` table, timestamped with the current time in milliseconds
+    pub fn insert_packet(&self, packet: &Packet) -> Result<(), DBError> {
+        let serialized_packet = serde_json::to_string(packet)?;
+        self.db.execute(
+            "INSERT INTO packet_log (received_at, packet) VALUES (?1, ?2)",
+            params![&u64_to_sql(get_epoch_time_ms() as u64)?, &serialized_packet],
+        )?;
+        Ok(())
+    }
+
+    /// Get every logged packet received at or after `since` (in milliseconds), in the order they were received
+    pub fn packet_trace(&self, since: u64) -> Result<Vec<Packet>, DBError> {
+        let rows: Vec<String> = query_rows(
+            &self.db,
+            "SELECT packet FROM packet_log WHERE received_at >= ? ORDER BY id",
+            &[u64_to_sql(since)?],
+        )?;
+        rows.iter()
+            .map(|row| serde_json::from_str(row))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DBError::SerializationError)
+    }
+
+    /// Remove every logged packet received before `received_before` (in milliseconds).
+    /// Returns the number of packets removed.
+    pub fn remove_packets_before(&self, received_before: u64) -> Result<usize, DBError> {
+        let removed = self.db.execute(
+            "DELETE FROM packet_log WHERE received_at < ?",
+            &[u64_to_sql(received_before)?],
+        )?;
+        Ok(removed)
+    }
+
+    /// Persist a block response that could not be sent to stackerdb, so that it can be resent later.
+    /// Every block response is written to the same stackerdb slot, so it replaces any response
+    /// already pending for the reward cycle.
+    pub fn set_pending_response(
+        &mut self,
+        reward_cycle: u64,
+        response: &BlockResponse,
+    ) -> Result<(), DBError> {
+        let serialized_response = serde_json::to_string(response)?;
+        let tx = tx_begin_immediate(&mut self.db)?;
+        tx.execute(
+            "DELETE FROM pending_responses WHERE reward_cycle = ?",
+            &[u64_to_sql(reward_cycle)?],
+        )?;
+        tx.execute(
+            "INSERT INTO pending_responses (reward_cycle, response) VALUES (?1, ?2)",
+            params![&u64_to_sql(reward_cycle)?, &serialized_response],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get the block response pending for the given reward cycle, if any.
+    /// If several were persisted, only the latest one is returned.
+    pub fn pending_response(&self, reward_cycle: u64) -> Result<Option<BlockResponse>, DBError> {
+        let result: Option<String> = query_row(
+            &self.db,
+            "SELECT response FROM pending_responses WHERE reward_cycle = ? ORDER BY id DESC LIMIT 1",
+            &[u64_to_sql(reward_cycle)?],
+        )?;
+        result
+            .map(|response| serde_json::from_str(&response).map_err(DBError::SerializationError))
+            .transpose()
+    }
+
+    /// Remove the block responses pending for the given reward cycle, once sent or superseded
+    pub fn remove_pending_responses(&self, reward_cycle: u64) -> Result<(), DBError> {
+        self.db.execute(
+            "DELETE FROM pending_responses WHERE reward_cycle = ?",
+            &[u64_to_sql(reward_cycle)?],
+        )?;
+        Ok(())
+    }
+
     /// Fetch a block from the database using the block's
     /// `signer_signature_hash`
     pub fn block_lookup(
@@ -115,13 +547,47 @@ impl SignerDb {
         reward_cycle: u64,
         hash: &Sha512Trunc256Sum,
     ) -> Result<Option<BlockInfo>, DBError> {
+        Self::lookup_block(&self.db, reward_cycle, hash)
+    }
+
+    /// Fetch a block through the given connection, e.g. within an open transaction
+    fn lookup_block(
+        conn: &Connection,
+        reward_cycle: u64,
+        hash: &Sha512Trunc256Sum,
+    ) -> Result<Option<BlockInfo>, DBError> {
+        query_row(
+            conn,
+            "SELECT block_info, compressed FROM blocks WHERE reward_cycle = ? AND signer_signature_hash = ?",
+            params![&u64_to_sql(reward_cycle)?, hash.to_string()],
+        )
+    }
+
+    /// Fetch the `signer_signature_hash` of the block this signer already signed over
+    /// at the given `(consensus_hash, chain_length)`, if any
+    pub fn signed_block_at(
+        &self,
+        consensus_hash: &ConsensusHash,
+        chain_length: u64,
+    ) -> Result<Option<Sha512Trunc256Sum>, DBError> {
         let result: Option<String> = query_row(
             &self.db,
-            "SELECT block_info FROM blocks WHERE reward_cycle = ? AND signer_signature_hash = ?",
-            params![&u64_to_sql(reward_cycle)?, hash.to_string()],
+            "SELECT signer_signature_hash FROM blocks WHERE consensus_hash = ?1 AND signed_over = 1 AND chain_length = ?2",
+            params![consensus_hash.to_hex(), &u64_to_sql(chain_length)?],
         )?;
+        result
+            .map(|hash| Sha512Trunc256Sum::from_hex(&hash).map_err(|_| DBError::ParseError))
+            .transpose()
+    }
 
-        try_deserialize(result)
+    /// Count the blocks this signer has signed over in the tenure identified by `consensus_hash`
+    pub fn signed_count_for_tenure(&self, consensus_hash: &ConsensusHash) -> Result<u64, DBError> {
+        let result: Option<i64> = query_row(
+            &self.db,
+            "SELECT COUNT(*) FROM blocks WHERE consensus_hash = ?1 AND signed_over = 1",
+            &[consensus_hash.to_hex()],
+        )?;
+        u64::try_from(result.unwrap_or(0)).map_err(|_| DBError::ParseError)
     }
 
     /// Insert a block into the database.
@@ -130,6 +596,32 @@ impl SignerDb {
         &mut self,
         reward_cycle: u64,
         block_info: &BlockInfo,
+    ) -> Result<(), DBError> {
+        Self::store_block(&self.db, self.compress_block_info, reward_cycle, block_info)
+    }
+
+    /// Insert several blocks of the same reward cycle into the database in a single transaction
+    pub fn insert_blocks(
+        &mut self,
+        reward_cycle: u64,
+        block_infos: &[BlockInfo],
+    ) -> Result<(), DBError> {
+        let tx = tx_begin_immediate(&mut self.db)?;
+        for block_info in block_infos {
+            Self::store_block(&tx, self.compress_block_info, reward_cycle, block_info)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Write a block to the `blocks` table, gzipping its info if `compress_block_info` is set.
+    /// The block's consensus hash, chain length and whether it was signed over are also stored in
+    /// their own columns.
+    fn store_block(
+        conn: &Connection,
+        compress_block_info: bool,
+        reward_cycle: u64,
+        block_info: &BlockInfo,
     ) -> Result<(), DBError> {
         let block_json =
             serde_json::to_string(&block_info).expect("Unable to serialize block info");
@@ -146,14 +638,282 @@ impl SignerDb {
                 }
             })
         );
+        let consensus_hash = block_info.block.header.consensus_hash.to_hex();
+        let chain_length = u64_to_sql(block_info.block.header.chain_length)?;
+        if compress_block_info {
+            let compressed_json = compress(block_json.as_bytes()).map_err(DBError::IOError)?;
+            conn
+                .execute(
+                    "INSERT OR REPLACE INTO blocks (reward_cycle, signer_signature_hash, block_info, compressed, consensus_hash, chain_length, signed_over) VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6)",
+                    params![&u64_to_sql(reward_cycle)?, hash.to_string(), &compressed_json, &consensus_hash, &chain_length, block_info.signed_over],
+                )?;
+        } else {
+            conn
+                .execute(
+                    "INSERT OR REPLACE INTO blocks (reward_cycle, signer_signature_hash, block_info, compressed, consensus_hash, chain_length, signed_over) VALUES (?1, ?2, ?3, 0, ?4, ?5, ?6)",
+                    params![&u64_to_sql(reward_cycle)?, hash.to_string(), &block_json, &consensus_hash, &chain_length, block_info.signed_over],
+                )?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch all the blocks stored for the given reward cycle
+    pub fn get_blocks_for_reward_cycle(
+        &self,
+        reward_cycle: u64,
+    ) -> Result<Vec<BlockInfo>, DBError> {
+        query_rows(
+            &self.db,
+            "SELECT block_info, compressed FROM blocks WHERE reward_cycle = ?",
+            &[u64_to_sql(reward_cycle)?],
+        )
+    }
+
+    /// Remove a block from the database.
+    /// `hash` is the `signer_signature_hash` of the block.
+    pub fn remove_block(&self, reward_cycle: u64, hash: &Sha512Trunc256Sum) -> Result<(), DBError> {
+        debug!("Removing block_info: reward_cycle = {reward_cycle}, sighash = {hash}");
+        self.db.execute(
+            "DELETE FROM blocks WHERE reward_cycle = ?1 AND signer_signature_hash = ?2",
+            params![&u64_to_sql(reward_cycle)?, hash.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Get the highest chain length of the blocks stored for any reward cycle, if any
+    pub fn highest_chain_length(&self) -> Result<Option<u64>, DBError> {
+        let result: Option<i64> =
+            self.db
+                .query_row("SELECT MAX(chain_length) FROM blocks", NO_PARAMS, |row| {
+                    row.get(0)
+                })?;
+        result
+            .map(|chain_length| u64::try_from(chain_length).map_err(|_| DBError::ParseError))
+            .transpose()
+    }
+
+    /// Remove every block, of any reward cycle, whose chain length is below `chain_length`.
+    /// Returns the number of blocks removed.
+    pub fn remove_blocks_older_than(&self, chain_length: u64) -> Result<usize, DBError> {
+        debug!("Removing blocks below chain length {chain_length}");
+        let removed = self.db.execute(
+            "DELETE FROM blocks WHERE chain_length < ?",
+            &[u64_to_sql(chain_length)?],
+        )?;
+        Ok(removed)
+    }
+
+    /// Remove every block stored under a reward cycle before `reward_cycle`.
+    /// Legacy blocks stored under `UNKNOWN_REWARD_CYCLE` are kept.
+    /// Returns the number of blocks removed.
+    pub fn remove_blocks_before_reward_cycle(&self, reward_cycle: u64) -> Result<usize, DBError> {
+        debug!("Removing blocks before reward cycle {reward_cycle}");
+        let removed = self.db.execute(
+            "DELETE FROM blocks WHERE reward_cycle < ?",
+            &[u64_to_sql(reward_cycle)?],
+        )?;
+        Ok(removed)
+    }
+
+    /// Checkpoint the database's write-ahead log so that space freed by removed rows is released
+    pub fn checkpoint(&self) -> Result<(), DBError> {
         self.db
-            .execute(
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", NO_PARAMS, |_row| Ok(()))?;
+        Ok(())
+    }
+
+    /// Count the rows in each table and sum the sizes of the data they store.
+    /// Sizes are approximate: they cover each table's serialized data column, not keys,
+    /// indexes or free pages.
+    pub fn storage_stats(&self) -> Result<StorageStats, DBError> {
+        Ok(StorageStats {
+            blocks: self.table_stats("blocks", "block_info")?,
+            signer_states: self.table_stats("signer_states", "state")?,
+            packet_log: self.table_stats("packet_log", "packet")?,
+            pending_responses: self.table_stats("pending_responses", "response")?,
+            aggregate_keys: self.table_stats("aggregate_keys", "aggregate_key")?,
+        })
+    }
+
+    /// Count the rows in `table` and sum the byte lengths of their `column` values
+    fn table_stats(&self, table: &str, column: &str) -> Result<TableStats, DBError> {
+        let sql = format!(
+            "SELECT COUNT(*), COALESCE(SUM(length(CAST({column} AS BLOB))), 0) FROM {table}"
+        );
+        let (rows, bytes): (i64, i64) = self
+            .db
+            .query_row(&sql, NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(TableStats {
+            rows: u64::try_from(rows).map_err(|_| DBError::ParseError)?,
+            bytes: u64::try_from(bytes).map_err(|_| DBError::ParseError)?,
+        })
+    }
+
+    /// Update the validity of a block in a single statement, leaving the rest of its info untouched.
+    /// Also records when the validity was determined and the time elapsed since the block was
+    /// proposed as its validation latency.
+    /// Compressed blocks cannot be updated in place, and are instead read back, updated and rewritten
+    /// within the same transaction.
+    /// Returns whether the block existed in the database.
+    pub fn set_block_validity(
+        &mut self,
+        reward_cycle: u64,
+        hash: &Sha512Trunc256Sum,
+        valid: bool,
+    ) -> Result<bool, DBError> {
+        debug!("Setting block validity: reward_cycle = {reward_cycle}, sighash = {hash}, valid = {valid}");
+        let now_ms = u64_to_sql(get_epoch_time_ms() as u64)?;
+        let tx = tx_begin_immediate(&mut self.db)?;
+        let updated = tx.execute(
+            "UPDATE blocks SET block_info = json_set(block_info, '$.valid', json(?1), '$.validation_latency_ms', MAX(?2 - COALESCE(json_extract(block_info, '$.proposed_at'), ?2), 0), '$.validated_at', ?2 / 1000) WHERE reward_cycle = ?3 AND signer_signature_hash = ?4 AND compressed = 0",
+            params![valid.to_string(), now_ms, &u64_to_sql(reward_cycle)?, hash.to_string()],
+        )?;
+        if updated == 0 {
+            let Some(mut block_info) = Self::lookup_block(&tx, reward_cycle, hash)? else {
+                return Ok(false);
+            };
+            block_info.set_validated(valid);
+            Self::store_block(&tx, self.compress_block_info, reward_cycle, &block_info)?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Record this signer's vote on a block, leaving the rest of its info untouched.
+    /// Also records when the vote was cast, and drops the block's cached nonce request,
+    /// which the vote answers.
+    /// Compressed blocks cannot be updated in place, and are instead read back, updated and rewritten
+    /// within the same transaction.
+    /// Returns whether the block existed in the database.
+    pub fn set_block_vote(
+        &mut self,
+        reward_cycle: u64,
+        hash: &Sha512Trunc256Sum,
+        vote: &NakamotoBlockVote,
+    ) -> Result<bool, DBError> {
+        debug!(
+            "Setting block vote: reward_cycle = {reward_cycle}, sighash = {hash}, rejected = {}",
+            vote.rejected
+        );
+        let serialized_vote = serde_json::to_string(vote)?;
+        let now_secs = u64_to_sql(get_epoch_time_secs())?;
+        let tx = tx_begin_immediate(&mut self.db)?;
+        let updated = tx.execute(
+            "UPDATE blocks SET block_info = json_set(block_info, '$.vote', json(?1), '$.voted_at', ?2, '$.nonce_request', json('null')) WHERE reward_cycle = ?3 AND signer_signature_hash = ?4 AND compressed = 0",
+            params![&serialized_vote, now_secs, &u64_to_sql(reward_cycle)?, hash.to_string()],
+        )?;
+        if updated == 0 {
+            let Some(mut block_info) = Self::lookup_block(&tx, reward_cycle, hash)? else {
+                return Ok(false);
+            };
+            block_info.set_vote(vote.clone());
+            Self::store_block(&tx, self.compress_block_info, reward_cycle, &block_info)?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Migrate a legacy `blocks` table, which predates the `reward_cycle` column, to the current schema.
+    /// `derive_reward_cycle` determines each stored block's reward cycle from its burn context. Blocks
+    /// for which it returns `None` are stored under `UNKNOWN_REWARD_CYCLE`.
+    /// Does nothing if the table already has a `reward_cycle` column.
+    fn backfill_reward_cycles<F>(tx: &DBTx, derive_reward_cycle: F) -> Result<(), DBError>
+    where
+        F: Fn(&BlockInfo) -> Option<u64>,
+    {
+        let has_reward_cycle: Option<i64> = query_row(
+            tx,
+            "SELECT COUNT(*) FROM pragma_table_info('blocks') WHERE name = 'reward_cycle'",
+            NO_PARAMS,
+        )?;
+        if has_reward_cycle.unwrap_or(0) > 0 {
+            return Ok(());
+        }
+
+        tx.execute("ALTER TABLE blocks RENAME TO legacy_blocks", NO_PARAMS)?;
+        tx.execute(CREATE_BLOCKS_TABLE, NO_PARAMS)?;
+        let rows: Vec<String> = query_rows(tx, "SELECT block_info FROM legacy_blocks", NO_PARAMS)?;
+        for row in rows {
+            let block_info: BlockInfo =
+                serde_json::from_str(&row).map_err(DBError::SerializationError)?;
+            let hash = block_info.signer_signature_hash();
+            let reward_cycle = derive_reward_cycle(&block_info).unwrap_or_else(|| {
+                warn!("Unable to derive the reward cycle of legacy block {hash}. Storing it under reward cycle {UNKNOWN_REWARD_CYCLE}.");
+                UNKNOWN_REWARD_CYCLE
+            });
+            debug!("Backfilling block_info: reward_cycle = {reward_cycle}, sighash = {hash}");
+            tx.execute(
                 "INSERT OR REPLACE INTO blocks (reward_cycle, signer_signature_hash, block_info) VALUES (?1, ?2, ?3)",
-                params![&u64_to_sql(reward_cycle)?, hash.to_string(), &block_json],
+                params![&u64_to_sql(reward_cycle)?, hash.to_string(), &row],
             )?;
-
+        }
+        tx.execute("DROP TABLE legacy_blocks", NO_PARAMS)?;
+        Self::add_chain_length_column(tx)?;
         Ok(())
     }
+
+    /// Export the decisions made on every block of the given reward cycle as CSV.
+    /// Rows are ordered by chain length, and empty fields denote missing values.
+    pub fn export_cycle_csv(&self, reward_cycle: u64) -> Result<String, DBError> {
+        let mut blocks = self.get_blocks_for_reward_cycle(reward_cycle)?;
+        blocks.sort_by_key(|block_info| {
+            (
+                block_info.block.header.chain_length,
+                block_info.signer_signature_hash(),
+            )
+        });
+
+        let mut csv = String::from(
+            "signer_signature_hash,chain_length,voted,rejected,proposed_at,validation_latency_ms\n",
+        );
+        for block_info in blocks {
+            let rejected = block_info
+                .vote
+                .as_ref()
+                .map(|vote| vote.rejected.to_string())
+                .unwrap_or_default();
+            let validation_latency_ms = block_info
+                .validation_latency_ms
+                .map(|latency| latency.to_string())
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{rejected},{},{validation_latency_ms}\n",
+                block_info.signer_signature_hash(),
+                block_info.block.header.chain_length,
+                block_info.vote.is_some(),
+                block_info.proposed_at,
+            ));
+        }
+        Ok(csv)
+    }
+}
+
+impl FromRow<BlockInfo> for BlockInfo {
+    fn from_row<'a>(row: &'a Row) -> Result<BlockInfo, DBError> {
+        let compressed: bool = row.get(1)?;
+        let block_json = if compressed {
+            let compressed_json: Vec<u8> = row.get(0)?;
+            decompress(&compressed_json).map_err(DBError::IOError)?
+        } else {
+            row.get(0)?
+        };
+        serde_json::from_str(&block_json).map_err(DBError::SerializationError)
+    }
+}
+
+/// Gzip the given bytes
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    let mut encoder = gzip::Encoder::new(Vec::new())?;
+    encoder.write_all(bytes)?;
+    encoder.finish().into_result()
+}
+
+/// Gunzip the given bytes into a string
+fn decompress(bytes: &[u8]) -> Result<String, std::io::Error> {
+    let mut decoded = String::new();
+    gzip::Decoder::new(bytes)?.read_to_string(&mut decoded)?;
+    Ok(decoded)
 }
 
 fn try_deserialize<T>(s: Option<String>) -> Result<Option<T>, DBError>
@@ -185,14 +945,15 @@ mod tests {
         NakamotoBlock, NakamotoBlockHeader, NakamotoBlockVote,
     };
     use blockstack_lib::chainstate::stacks::ThresholdSignature;
+    use libsigner::{BlockRejection, RejectCode};
     use num_traits::identities::Zero;
     use polynomial::Polynomial;
     use stacks_common::bitvec::BitVec;
     use stacks_common::types::chainstate::{ConsensusHash, StacksBlockId, TrieHash};
     use stacks_common::util::secp256k1::MessageSignature;
     use wsts::common::Nonce;
-    use wsts::curve::point::Point;
     use wsts::curve::scalar::Scalar;
+    use wsts::net::{Message, NonceRequest};
     use wsts::traits::PartyState;
 
     use super::*;
@@ -264,8 +1025,8 @@ mod tests {
     fn test_basic_signer_db_with_path(db_path: impl AsRef<Path>) {
         let mut db = SignerDb::new(db_path).expect("Failed to create signer db");
         let reward_cycle = 1;
-        let (block_info, block) = create_block();
-        db.insert_block(reward_cycle, &block_info)
+        let (expected_block_info, block) = create_block();
+        db.insert_block(reward_cycle, &expected_block_info)
             .expect("Unable to insert block into db");
 
         let block_info = db
@@ -273,7 +1034,7 @@ mod tests {
             .unwrap()
             .expect("Unable to get block from db");
 
-        assert_eq!(BlockInfo::new(block.clone()), block_info);
+        assert_eq!(expected_block_info, block_info);
 
         // Test looking up a block from a different reward cycle
         let block_info = db
@@ -285,7 +1046,8 @@ mod tests {
     #[test]
     fn test_basic_signer_db() {
         let db_path = tmp_db_path();
-        test_basic_signer_db_with_path(db_path)
+        test_basic_signer_db_with_path(&db_path);
+        fs::remove_file(db_path).unwrap();
     }
 
     #[test]
@@ -293,13 +1055,39 @@ mod tests {
         test_basic_signer_db_with_path(":memory:")
     }
 
+    #[test]
+    fn test_signer_db_pragmas() {
+        let db_path = tmp_db_path();
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let journal_mode: String = db
+            .db
+            .query_row("PRAGMA journal_mode", NO_PARAMS, |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        db.set_busy_timeout(Duration::from_millis(1234)).unwrap();
+        let busy_timeout: i64 = db
+            .db
+            .query_row("PRAGMA busy_timeout", NO_PARAMS, |row| row.get(0))
+            .unwrap();
+        assert_eq!(busy_timeout, 1234);
+
+        // in-memory databases cannot use WAL, but still open
+        let db = SignerDb::new(":memory:").expect("Failed to create signer db");
+        let journal_mode: String = db
+            .db
+            .query_row("PRAGMA journal_mode", NO_PARAMS, |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "memory");
+    }
+
     #[test]
     fn test_update_block() {
         let db_path = tmp_db_path();
-        let mut db = SignerDb::new(db_path).expect("Failed to create signer db");
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
         let reward_cycle = 42;
-        let (block_info, block) = create_block();
-        db.insert_block(reward_cycle, &block_info)
+        let (expected_block_info, block) = create_block();
+        db.insert_block(reward_cycle, &expected_block_info)
             .expect("Unable to insert block into db");
 
         let block_info = db
@@ -307,7 +1095,7 @@ mod tests {
             .unwrap()
             .expect("Unable to get block from db");
 
-        assert_eq!(BlockInfo::new(block.clone()), block_info);
+        assert_eq!(expected_block_info, block_info);
 
         let old_block_info = block_info;
         let old_block = block;
@@ -334,20 +1122,216 @@ mod tests {
 
         assert_ne!(old_block_info, block_info);
         assert_eq!(block_info.vote, Some(vote));
+
+        fs::remove_file(db_path).unwrap();
     }
 
     #[test]
-    fn test_write_signer_state() {
+    fn test_set_block_validity() {
         let db_path = tmp_db_path();
-        let db = SignerDb::new(db_path).expect("Failed to create signer db");
-        let state_0 = create_signer_state(0);
-        let state_1 = create_signer_state(1);
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let mut other_db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let reward_cycle = 3;
+        let (mut block_info, block) = create_block();
+        let hash = block.header.signer_signature_hash();
+        db.insert_block(reward_cycle, &block_info)
+            .expect("Unable to insert block into db");
 
-        db.insert_signer_state(10, &state_0)
-            .expect("Failed to insert signer state");
+        // Another writer records a vote on the block
+        let vote = NakamotoBlockVote {
+            signer_signature_hash: hash,
+            rejected: false,
+        };
+        block_info.vote = Some(vote.clone());
+        other_db
+            .insert_block(reward_cycle, &block_info)
+            .expect("Unable to insert block into db");
 
-        db.insert_signer_state(11, &state_1)
-            .expect("Failed to insert signer state");
+        // Updating the validity must not clobber the vote
+        assert!(db
+            .set_block_validity(reward_cycle, &hash, true)
+            .expect("Failed to set block validity"));
+        let stored = db
+            .block_lookup(reward_cycle, &hash)
+            .unwrap()
+            .expect("Unable to get block from db");
+        assert_eq!(stored.valid, Some(true));
+        assert_eq!(stored.vote, Some(vote.clone()));
+        assert_eq!(stored.proposed_at, block_info.proposed_at);
+        assert!(stored.validation_latency_ms.is_some());
+        assert!(stored.validated_at.is_some());
+
+        assert!(other_db
+            .set_block_validity(reward_cycle, &hash, false)
+            .expect("Failed to set block validity"));
+        let stored = other_db
+            .block_lookup(reward_cycle, &hash)
+            .unwrap()
+            .expect("Unable to get block from db");
+        assert_eq!(stored.valid, Some(false));
+        assert_eq!(stored.vote, Some(vote));
+
+        // Unknown blocks are not inserted
+        assert!(!db
+            .set_block_validity(reward_cycle + 1, &hash, true)
+            .expect("Failed to set block validity"));
+        assert!(db.block_lookup(reward_cycle + 1, &hash).unwrap().is_none());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_set_block_vote() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let reward_cycle = 3;
+        for compressed in [false, true] {
+            db.set_compression(compressed);
+            let (_, block) = create_block_override(|b| {
+                b.header.chain_length = if compressed { 2 } else { 1 };
+            });
+            let hash = block.header.signer_signature_hash();
+            let nonce_request = NonceRequest {
+                dkg_id: 1,
+                sign_id: 2,
+                sign_iter_id: 3,
+                message: vec![1, 2, 3],
+                is_taproot: false,
+                merkle_root: None,
+            };
+            let mut block_info = BlockInfo::new_with_request(block, nonce_request);
+            block_info.valid = Some(true);
+            db.insert_block(reward_cycle, &block_info)
+                .expect("Unable to insert block into db");
+
+            let vote = NakamotoBlockVote {
+                signer_signature_hash: hash,
+                rejected: false,
+            };
+            assert!(db
+                .set_block_vote(reward_cycle, &hash, &vote)
+                .expect("Failed to set block vote"));
+            let stored = db
+                .block_lookup(reward_cycle, &hash)
+                .unwrap()
+                .expect("Unable to get block from db");
+            assert!(stored.voted_at.is_some());
+
+            // Only the vote, its time and the answered nonce request change
+            block_info.set_vote(vote.clone());
+            block_info.voted_at = stored.voted_at;
+            assert_eq!(stored, block_info);
+
+            // Unknown blocks are not inserted
+            assert!(!db
+                .set_block_vote(reward_cycle + 1, &hash, &vote)
+                .expect("Failed to set block vote"));
+            assert!(db.block_lookup(reward_cycle + 1, &hash).unwrap().is_none());
+        }
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_block_info_without_timestamps_deserializes() {
+        let (mut block_info, _block) = create_block();
+        block_info.set_validated(true);
+        let mut json = serde_json::to_value(&block_info).unwrap();
+        let fields = json.as_object_mut().unwrap();
+        assert!(fields.remove("validated_at").is_some());
+        assert!(fields.remove("voted_at").is_some());
+
+        let legacy: BlockInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.valid, Some(true));
+        assert_eq!(legacy.validated_at, None);
+        assert_eq!(legacy.voted_at, None);
+    }
+
+    #[test]
+    fn test_export_cycle_csv() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let reward_cycle = 7;
+
+        let (mut accepted, accepted_block) = create_block_override(|b| {
+            b.header.chain_length = 5;
+        });
+        accepted.proposed_at = 1_000;
+        accepted.validation_latency_ms = Some(250);
+        accepted.vote = Some(NakamotoBlockVote {
+            signer_signature_hash: accepted_block.header.signer_signature_hash(),
+            rejected: false,
+        });
+        let (mut rejected, rejected_block) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        rejected.proposed_at = 2_000;
+        rejected.validation_latency_ms = Some(40);
+        rejected.vote = Some(NakamotoBlockVote {
+            signer_signature_hash: rejected_block.header.signer_signature_hash(),
+            rejected: true,
+        });
+        let (mut pending, pending_block) = create_block_override(|b| {
+            b.header.chain_length = 8;
+        });
+        pending.proposed_at = 3_000;
+        // A block from another reward cycle must not be exported
+        let (other_cycle, _) = create_block_override(|b| {
+            b.header.chain_length = 1;
+        });
+
+        db.insert_block(reward_cycle, &accepted)
+            .expect("Unable to insert block into db");
+        db.insert_block(reward_cycle, &rejected)
+            .expect("Unable to insert block into db");
+        db.insert_block(reward_cycle, &pending)
+            .expect("Unable to insert block into db");
+        db.insert_block(reward_cycle + 1, &other_cycle)
+            .expect("Unable to insert block into db");
+
+        let csv = db
+            .export_cycle_csv(reward_cycle)
+            .expect("Failed to export reward cycle");
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "signer_signature_hash,chain_length,voted,rejected,proposed_at,validation_latency_ms"
+                    .to_string(),
+                format!(
+                    "{},3,true,true,2000,40",
+                    rejected_block.header.signer_signature_hash()
+                ),
+                format!(
+                    "{},5,true,false,1000,250",
+                    accepted_block.header.signer_signature_hash()
+                ),
+                format!(
+                    "{},8,false,,3000,",
+                    pending_block.header.signer_signature_hash()
+                ),
+            ]
+        );
+
+        let csv = db
+            .export_cycle_csv(reward_cycle + 2)
+            .expect("Failed to export reward cycle");
+        assert_eq!(csv.lines().count(), 1);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_write_signer_state() {
+        let db_path = tmp_db_path();
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let state_0 = create_signer_state(0);
+        let state_1 = create_signer_state(1);
+
+        db.insert_signer_state(10, &state_0)
+            .expect("Failed to insert signer state");
+
+        db.insert_signer_state(11, &state_1)
+            .expect("Failed to insert signer state");
 
         assert_eq!(
             db.get_signer_state(10)
@@ -371,5 +1355,871 @@ mod tests {
             .get_signer_state(9)
             .expect("Failed to get signer state")
             .is_none());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_get_and_delete_all_signer_states() {
+        let db_path = tmp_db_path();
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert!(db.get_all_signer_states().unwrap().is_empty());
+
+        db.insert_signer_state(12, &create_signer_state(2)).unwrap();
+        db.insert_signer_state(10, &create_signer_state(0)).unwrap();
+        db.insert_signer_state(11, &create_signer_state(1)).unwrap();
+
+        let states = db.get_all_signer_states().unwrap();
+        assert_eq!(
+            states
+                .iter()
+                .map(|(signer_id, reward_cycle, state)| {
+                    assert_eq!(*signer_id, state.id);
+                    (*signer_id, *reward_cycle)
+                })
+                .collect::<Vec<_>>(),
+            vec![(0, 10), (1, 11), (2, 12)]
+        );
+
+        db.delete_signer_states_before(12).unwrap();
+        let states = db.get_all_signer_states().unwrap();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].0, 2);
+        assert_eq!(states[0].1, 12);
+        assert!(db.get_signer_state(10).unwrap().is_none());
+        assert!(db.get_signer_state(11).unwrap().is_none());
+        assert!(db.get_signer_state(12).unwrap().is_some());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    /// Create a legacy database, whose `blocks` table predates the `reward_cycle` column, holding
+    /// the given blocks and signer states for the given reward cycles
+    fn create_legacy_db(db_path: &Path, block_infos: &[&BlockInfo], reward_cycles: &[u64]) {
+        let legacy_db = SignerDb::connect(db_path).unwrap();
+        legacy_db
+            .execute(
+                "CREATE TABLE blocks (signer_signature_hash TEXT PRIMARY KEY, block_info TEXT NOT NULL)",
+                NO_PARAMS,
+            )
+            .unwrap();
+        for block_info in block_infos {
+            legacy_db
+                .execute(
+                    "INSERT INTO blocks (signer_signature_hash, block_info) VALUES (?1, ?2)",
+                    params![
+                        block_info.signer_signature_hash().to_string(),
+                        serde_json::to_string(block_info).unwrap()
+                    ],
+                )
+                .unwrap();
+        }
+        legacy_db
+            .execute(CREATE_SIGNER_STATE_TABLE, NO_PARAMS)
+            .unwrap();
+        for reward_cycle in reward_cycles {
+            legacy_db
+                .execute(
+                    "INSERT INTO signer_states (reward_cycle, state) VALUES (?1, ?2)",
+                    params![u64_to_sql(*reward_cycle).unwrap(), "{}"],
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_backfill_reward_cycles() {
+        let db_path = tmp_db_path();
+        let (block_info_1, block_1) = create_block_override(|b| {
+            b.header.chain_length = 11;
+        });
+        let (block_info_2, block_2) = create_block_override(|b| {
+            b.header.chain_length = 22;
+        });
+        let (block_info_3, block_3) = create_block_override(|b| {
+            b.header.chain_length = 33;
+        });
+        create_legacy_db(
+            &db_path,
+            &[&block_info_1, &block_info_2, &block_info_3],
+            &[],
+        );
+
+        {
+            let mut legacy_db = SignerDb::connect(&db_path).unwrap();
+            let tx = tx_begin_immediate(&mut legacy_db).unwrap();
+            SignerDb::backfill_reward_cycles(&tx, |block_info| {
+                match block_info.block.header.chain_length {
+                    11 => Some(1),
+                    22 => Some(2),
+                    _ => None,
+                }
+            })
+            .expect("Failed to backfill reward cycles");
+            tx.commit().unwrap();
+        }
+
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert_eq!(
+            db.block_lookup(1, &block_1.header.signer_signature_hash())
+                .unwrap()
+                .expect("Unable to get block from db"),
+            block_info_1
+        );
+        assert_eq!(
+            db.block_lookup(2, &block_2.header.signer_signature_hash())
+                .unwrap()
+                .expect("Unable to get block from db"),
+            block_info_2
+        );
+        assert!(db
+            .block_lookup(1, &block_3.header.signer_signature_hash())
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            db.block_lookup(
+                UNKNOWN_REWARD_CYCLE,
+                &block_3.header.signer_signature_hash()
+            )
+            .unwrap()
+            .expect("Unable to get block from db"),
+            block_info_3
+        );
+
+        // running the backfill again is a no-op
+        let tx = tx_begin_immediate(&mut db.db).unwrap();
+        SignerDb::backfill_reward_cycles(&tx, |_| Some(5))
+            .expect("Failed to backfill reward cycles");
+        tx.commit().unwrap();
+        assert!(db.get_blocks_for_reward_cycle(5).unwrap().is_empty());
+        assert_eq!(
+            db.get_blocks_for_reward_cycle(1).unwrap(),
+            vec![block_info_1]
+        );
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_legacy_blocks() {
+        let (block_info_1, block_1) = create_block_override(|b| {
+            b.header.chain_length = 1;
+        });
+        let (block_info_2, block_2) = create_block_override(|b| {
+            b.header.chain_length = 2;
+        });
+
+        // The signer only saved its state for one reward cycle, so every legacy block belongs to it
+        let db_path = tmp_db_path();
+        create_legacy_db(&db_path, &[&block_info_1, &block_info_2], &[7]);
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert_eq!(
+            db.block_lookup(7, &block_1.header.signer_signature_hash())
+                .unwrap()
+                .expect("Unable to get block from db"),
+            block_info_1
+        );
+        assert_eq!(
+            db.block_lookup(7, &block_2.header.signer_signature_hash())
+                .unwrap()
+                .expect("Unable to get block from db"),
+            block_info_2
+        );
+        fs::remove_file(db_path).unwrap();
+
+        // The signer saved its state for several reward cycles, so the blocks' is unknown
+        let db_path = tmp_db_path();
+        create_legacy_db(&db_path, &[&block_info_1, &block_info_2], &[7, 8]);
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert!(db.get_blocks_for_reward_cycle(7).unwrap().is_empty());
+        assert!(db.get_blocks_for_reward_cycle(8).unwrap().is_empty());
+        assert_eq!(
+            db.get_blocks_for_reward_cycle(UNKNOWN_REWARD_CYCLE)
+                .unwrap()
+                .len(),
+            2
+        );
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_signed_block_at() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let consensus_hash = ConsensusHash([0x04; 20]);
+
+        let (mut signed_block_info, signed_block) = create_block();
+        signed_block_info.signed_over = true;
+        db.insert_block(1, &signed_block_info).unwrap();
+
+        // a different block at the same slot, which has not been signed over
+        let (unsigned_block_info, unsigned_block) = create_block_override(|b| {
+            b.header.burn_spent = 10;
+        });
+        db.insert_block(1, &unsigned_block_info).unwrap();
+        assert_ne!(
+            signed_block.header.signer_signature_hash(),
+            unsigned_block.header.signer_signature_hash()
+        );
+
+        // a block that has not been signed over, at its own slot
+        let (other_block_info, _) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        db.insert_block(1, &other_block_info).unwrap();
+
+        assert_eq!(
+            db.signed_block_at(&consensus_hash, 2).unwrap(),
+            Some(signed_block.header.signer_signature_hash())
+        );
+        assert!(db.signed_block_at(&consensus_hash, 3).unwrap().is_none());
+        assert!(db
+            .signed_block_at(&ConsensusHash([0x05; 20]), 2)
+            .unwrap()
+            .is_none());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_signed_count_for_tenure() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let tenure_1 = ConsensusHash([0x04; 20]);
+        let tenure_2 = ConsensusHash([0x05; 20]);
+        assert_eq!(db.signed_count_for_tenure(&tenure_1).unwrap(), 0);
+
+        for chain_length in 1..=3 {
+            let (mut block_info, _) = create_block_override(|b| {
+                b.header.chain_length = chain_length;
+            });
+            block_info.signed_over = true;
+            db.insert_block(1, &block_info).unwrap();
+        }
+        // an unsigned block in the same tenure is not counted
+        let (unsigned_block_info, _) = create_block_override(|b| {
+            b.header.chain_length = 4;
+        });
+        db.insert_block(1, &unsigned_block_info).unwrap();
+        // signed blocks in the second tenure, across reward cycles
+        for (reward_cycle, chain_length) in [(1, 5), (2, 6)] {
+            let (mut block_info, _) = create_block_override(|b| {
+                b.header.consensus_hash = tenure_2;
+                b.header.chain_length = chain_length;
+            });
+            block_info.signed_over = true;
+            db.insert_block(reward_cycle, &block_info).unwrap();
+        }
+
+        assert_eq!(db.signed_count_for_tenure(&tenure_1).unwrap(), 3);
+        assert_eq!(db.signed_count_for_tenure(&tenure_2).unwrap(), 2);
+        assert_eq!(
+            db.signed_count_for_tenure(&ConsensusHash([0x06; 20]))
+                .unwrap(),
+            0
+        );
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_version_0_db() {
+        let db_path = tmp_db_path();
+        let (block_info, block) = create_block();
+        let signer_state = create_signer_state(1);
+
+        // a database that predates schema versioning
+        {
+            let legacy_db = SignerDb::connect(&db_path).unwrap();
+            legacy_db
+                .execute(
+                    "CREATE TABLE blocks (reward_cycle INTEGER NOT NULL, signer_signature_hash TEXT NOT NULL, block_info TEXT NOT NULL, PRIMARY KEY (reward_cycle, signer_signature_hash))",
+                    NO_PARAMS,
+                )
+                .unwrap();
+            legacy_db
+                .execute(CREATE_SIGNER_STATE_TABLE, NO_PARAMS)
+                .unwrap();
+            legacy_db
+                .execute(
+                    "INSERT INTO blocks (reward_cycle, signer_signature_hash, block_info) VALUES (?1, ?2, ?3)",
+                    params![
+                        1,
+                        block.header.signer_signature_hash().to_string(),
+                        serde_json::to_string(&block_info).unwrap()
+                    ],
+                )
+                .unwrap();
+            legacy_db
+                .execute(
+                    "INSERT INTO signer_states (reward_cycle, state) VALUES (?1, ?2)",
+                    params![1, serde_json::to_string(&signer_state).unwrap()],
+                )
+                .unwrap();
+            assert_eq!(SignerDb::get_schema_version(&legacy_db).unwrap(), 0);
+        }
+
+        let db = SignerDb::new(&db_path).expect("Failed to migrate signer db");
+        assert_eq!(
+            SignerDb::get_schema_version(&db.db).unwrap(),
+            SignerDb::SCHEMA_VERSION
+        );
+        for table in ["packet_log", "pending_responses", "aggregate_keys"] {
+            assert!(table_exists(&db.db, table).unwrap());
+        }
+        assert_eq!(
+            db.block_lookup(1, &block.header.signer_signature_hash())
+                .unwrap()
+                .unwrap(),
+            block_info
+        );
+        assert_eq!(db.get_signer_state(1).unwrap().unwrap().id, signer_state.id);
+        assert_eq!(db.highest_chain_length().unwrap(), Some(2));
+        drop(db);
+
+        // reopening a migrated database leaves it untouched
+        let db = SignerDb::new(&db_path).expect("Failed to reopen signer db");
+        let versions: Vec<i64> =
+            query_rows(&db.db, "SELECT version FROM schema_version", NO_PARAMS).unwrap();
+        assert_eq!(versions, vec![i64::from(SignerDb::SCHEMA_VERSION)]);
+        drop(db);
+
+        // a database from a newer binary is refused
+        {
+            let newer_db = SignerDb::connect(&db_path).unwrap();
+            newer_db
+                .execute("UPDATE schema_version SET version = 100", NO_PARAMS)
+                .unwrap();
+        }
+        assert!(SignerDb::new(&db_path).is_err());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_all_reward_cycles() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert!(db.all_reward_cycles().unwrap().is_empty());
+
+        db.insert_signer_state(5, &create_signer_state(1)).unwrap();
+        db.insert_signer_state(6, &create_signer_state(1)).unwrap();
+        let (block_info_1, _) = create_block();
+        let (block_info_2, _) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        db.insert_block(6, &block_info_1).unwrap();
+        db.insert_block(7, &block_info_1).unwrap();
+        db.insert_block(7, &block_info_2).unwrap();
+
+        assert_eq!(db.all_reward_cycles().unwrap(), vec![5, 6, 7]);
+
+        let response = BlockResponse::Rejected(BlockRejection::new(
+            block_info_1.signer_signature_hash(),
+            RejectCode::ConnectivityIssues,
+        ));
+        db.set_pending_response(8, &response).unwrap();
+        db.insert_aggregate_key_adoption(9, &Point::from(Scalar::from(42)), 1)
+            .unwrap();
+        db.insert_block(UNKNOWN_REWARD_CYCLE, &block_info_2)
+            .unwrap();
+
+        assert_eq!(db.all_reward_cycles().unwrap(), vec![5, 6, 7, 8, 9]);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_pending_response() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert!(db.pending_response(1).unwrap().is_none());
+
+        let response = |byte| {
+            BlockResponse::Rejected(BlockRejection::new(
+                Sha512Trunc256Sum([byte; 32]),
+                RejectCode::ConnectivityIssues,
+            ))
+        };
+        db.set_pending_response(1, &response(1)).unwrap();
+        db.set_pending_response(2, &response(2)).unwrap();
+        assert_eq!(db.pending_response(1).unwrap(), Some(response(1)));
+
+        // a newer response supersedes the pending one
+        db.set_pending_response(1, &response(3)).unwrap();
+        assert_eq!(db.pending_response(1).unwrap(), Some(response(3)));
+        assert_eq!(db.storage_stats().unwrap().pending_responses.rows, 2);
+
+        db.remove_pending_responses(1).unwrap();
+        assert!(db.pending_response(1).unwrap().is_none());
+        assert_eq!(db.pending_response(2).unwrap(), Some(response(2)));
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_packets_before() {
+        let db_path = tmp_db_path();
+        let db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let packet = Packet {
+            msg: Message::NonceRequest(NonceRequest {
+                dkg_id: 1,
+                sign_id: 2,
+                sign_iter_id: 3,
+                message: vec![1, 2, 3],
+                is_taproot: false,
+                merkle_root: None,
+            }),
+            sig: vec![],
+        };
+        let before = get_epoch_time_ms() as u64;
+        db.insert_packet(&packet).unwrap();
+        db.insert_packet(&packet).unwrap();
+        let after = get_epoch_time_ms() as u64 + 1;
+
+        assert_eq!(db.remove_packets_before(before).unwrap(), 0);
+        assert_eq!(db.packet_trace(0).unwrap().len(), 2);
+        assert_eq!(db.remove_packets_before(after).unwrap(), 2);
+        assert!(db.packet_trace(0).unwrap().is_empty());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_blocks_older_than() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert_eq!(db.highest_chain_length().unwrap(), None);
+
+        let block_infos: Vec<_> = [1, 5, 10, 20]
+            .into_iter()
+            .map(|chain_length| {
+                create_block_override(|b| {
+                    b.header.chain_length = chain_length;
+                })
+                .0
+            })
+            .collect();
+        for block_info in &block_infos[..2] {
+            db.insert_block(1, block_info).unwrap();
+        }
+        db.set_compression(true);
+        for block_info in &block_infos[2..] {
+            db.insert_block(2, block_info).unwrap();
+        }
+        assert_eq!(db.highest_chain_length().unwrap(), Some(20));
+
+        assert_eq!(db.remove_blocks_older_than(10).unwrap(), 2);
+        assert!(db.get_blocks_for_reward_cycle(1).unwrap().is_empty());
+        let mut blocks = db.get_blocks_for_reward_cycle(2).unwrap();
+        blocks.sort_by_key(|block_info| block_info.block.header.chain_length);
+        assert_eq!(blocks[..], block_infos[2..]);
+        assert_eq!(db.remove_blocks_older_than(10).unwrap(), 0);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_compressed_chain_length() {
+        let db_path = tmp_db_path();
+        let (block_info, block) = create_block_override(|b| {
+            b.header.chain_length = 7;
+        });
+
+        // a version 1 blocks table, without the chain_length column
+        {
+            let mut legacy_db = SignerDb::connect(&db_path).unwrap();
+            let tx = tx_begin_immediate(&mut legacy_db).unwrap();
+            SignerDb::schema_1_migration(&tx).unwrap();
+            tx.execute(
+                "INSERT INTO blocks (reward_cycle, signer_signature_hash, block_info, compressed) VALUES (?1, ?2, ?3, 1)",
+                params![
+                    1,
+                    block.header.signer_signature_hash().to_string(),
+                    compress(serde_json::to_string(&block_info).unwrap().as_bytes()).unwrap()
+                ],
+            )
+            .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = SignerDb::new(&db_path).expect("Failed to migrate signer db");
+        assert_eq!(db.highest_chain_length().unwrap(), Some(7));
+        assert_eq!(db.remove_blocks_older_than(8).unwrap(), 1);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_remove_blocks_before_reward_cycle() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let (block_info, _) = create_block();
+        for reward_cycle in [5, 6, 7, UNKNOWN_REWARD_CYCLE] {
+            db.insert_block(reward_cycle, &block_info).unwrap();
+        }
+
+        assert_eq!(db.remove_blocks_before_reward_cycle(7).unwrap(), 2);
+        assert_eq!(db.all_reward_cycles().unwrap(), vec![7]);
+        assert_eq!(
+            db.get_blocks_for_reward_cycle(UNKNOWN_REWARD_CYCLE)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(db.remove_blocks_before_reward_cycle(7).unwrap(), 0);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_get_blocks_for_reward_cycle() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let (block_info_1, _) = create_block();
+        let (block_info_2, _) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        let (block_info_3, _) = create_block_override(|b| {
+            b.header.chain_length = 4;
+        });
+        db.insert_block(1, &block_info_1).unwrap();
+        db.insert_block(1, &block_info_2).unwrap();
+        db.insert_block(2, &block_info_3).unwrap();
+
+        let mut blocks = db.get_blocks_for_reward_cycle(1).unwrap();
+        blocks.sort_by_key(|block_info| block_info.block.header.chain_length);
+        assert_eq!(blocks, vec![block_info_1, block_info_2]);
+        assert_eq!(
+            db.get_blocks_for_reward_cycle(2).unwrap(),
+            vec![block_info_3]
+        );
+        assert!(db.get_blocks_for_reward_cycle(3).unwrap().is_empty());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_insert_blocks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COMMITS: AtomicUsize = AtomicUsize::new(0);
+        fn count_commits(sql: &str) {
+            if sql.trim().to_uppercase().starts_with("COMMIT") {
+                COMMITS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        let block_infos: Vec<_> = (2..12)
+            .map(|chain_length| {
+                create_block_override(|b| {
+                    b.header.chain_length = chain_length;
+                })
+                .0
+            })
+            .collect();
+
+        db.db.trace(Some(count_commits));
+        db.insert_blocks(1, &block_infos).unwrap();
+        db.db.trace(None);
+        assert_eq!(COMMITS.load(Ordering::SeqCst), 1);
+
+        for block_info in block_infos.iter() {
+            assert_eq!(
+                db.block_lookup(1, &block_info.signer_signature_hash())
+                    .unwrap()
+                    .as_ref(),
+                Some(block_info)
+            );
+        }
+        assert_eq!(
+            db.get_blocks_for_reward_cycle(1).unwrap().len(),
+            block_infos.len()
+        );
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_reward_cycle_queries_use_index() {
+        use rusqlite::ToSql;
+
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        for reward_cycle in 0..3 {
+            let block_infos: Vec<_> = (0..1000)
+                .map(|chain_length| {
+                    create_block_override(|b| {
+                        b.header.chain_length = chain_length;
+                    })
+                    .0
+                })
+                .collect();
+            db.insert_blocks(reward_cycle, &block_infos).unwrap();
+        }
+
+        for reward_cycle in 0..3 {
+            assert_eq!(
+                db.get_blocks_for_reward_cycle(reward_cycle).unwrap().len(),
+                1000
+            );
+        }
+        assert_eq!(db.get_blocks_for_reward_cycle(3).unwrap().len(), 0);
+
+        // reward_cycle leads the primary key, so cycle-scoped queries search its index
+        // instead of scanning the table. Height-based removals search the chain_length index.
+        let hash = "00".repeat(32);
+        let consensus_hash = "00".repeat(20);
+        let queries: [(&str, &[&dyn ToSql]); 6] = [
+            (
+                "SELECT block_info, compressed FROM blocks WHERE reward_cycle = ?",
+                params![1],
+            ),
+            ("DELETE FROM blocks WHERE reward_cycle < ?", params![1]),
+            (
+                "DELETE FROM blocks WHERE reward_cycle = ?1 AND signer_signature_hash = ?2",
+                params![1, hash],
+            ),
+            ("DELETE FROM blocks WHERE chain_length < ?", params![1]),
+            (
+                "SELECT signer_signature_hash FROM blocks WHERE consensus_hash = ?1 AND signed_over = 1 AND chain_length = ?2",
+                params![consensus_hash, 1],
+            ),
+            (
+                "SELECT COUNT(*) FROM blocks WHERE consensus_hash = ?1 AND signed_over = 1",
+                params![consensus_hash],
+            ),
+        ];
+        for (query, args) in queries {
+            let plan: Vec<String> = db
+                .db
+                .prepare(&format!("EXPLAIN QUERY PLAN {query}"))
+                .unwrap()
+                .query_map(args, |row| row.get(3))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert!(
+                plan.iter().any(|detail| detail.contains("USING INDEX")),
+                "expected an index search for {query}, got {plan:?}"
+            );
+        }
+
+        assert_eq!(db.remove_blocks_before_reward_cycle(2).unwrap(), 2000);
+        assert_eq!(db.get_blocks_for_reward_cycle(2).unwrap().len(), 1000);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_compressed_block_info() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        db.set_compression(true);
+        let consensus_hash = ConsensusHash([0x04; 20]);
+
+        let (mut block_info, block) = create_block();
+        block_info.signed_over = true;
+        db.insert_block(1, &block_info).unwrap();
+        let hash = block.header.signer_signature_hash();
+
+        let (compressed, stored): (bool, Vec<u8>) = db
+            .db
+            .query_row(
+                "SELECT compressed, block_info FROM blocks WHERE signer_signature_hash = ?",
+                &[hash.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert!(compressed);
+        assert_ne!(stored, serde_json::to_vec(&block_info).unwrap());
+
+        assert_eq!(db.block_lookup(1, &hash).unwrap().unwrap(), block_info);
+        assert_eq!(db.get_blocks_for_reward_cycle(1).unwrap(), vec![block_info]);
+        assert_eq!(db.signed_block_at(&consensus_hash, 2).unwrap(), Some(hash));
+        assert_eq!(db.signed_count_for_tenure(&consensus_hash).unwrap(), 1);
+
+        assert!(db.set_block_validity(1, &hash, true).unwrap());
+        let updated = db.block_lookup(1, &hash).unwrap().unwrap();
+        assert_eq!(updated.valid, Some(true));
+        assert!(updated.validation_latency_ms.is_some());
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_storage_stats() {
+        let db_path = tmp_db_path();
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        assert_eq!(db.storage_stats().unwrap(), StorageStats::default());
+
+        let (block_info_1, _) = create_block();
+        let (block_info_2, _) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        db.insert_block(1, &block_info_1).unwrap();
+        db.insert_block(1, &block_info_2).unwrap();
+        let state = create_signer_state(0);
+        db.insert_signer_state(1, &state).unwrap();
+
+        let stats = db.storage_stats().unwrap();
+        assert_eq!(
+            stats.blocks,
+            TableStats {
+                rows: 2,
+                bytes: (serde_json::to_string(&block_info_1).unwrap().len()
+                    + serde_json::to_string(&block_info_2).unwrap().len())
+                    as u64,
+            }
+        );
+        assert_eq!(
+            stats.signer_states,
+            TableStats {
+                rows: 1,
+                bytes: serde_json::to_string(&state).unwrap().len() as u64,
+            }
+        );
+        assert_eq!(stats.packet_log, TableStats::default());
+        assert_eq!(stats.pending_responses, TableStats::default());
+        assert_eq!(stats.aggregate_keys, TableStats::default());
+
+        // compressed blocks are counted at their compressed size
+        db.set_compression(true);
+        let (block_info_3, _) = create_block_override(|b| {
+            b.header.chain_length = 4;
+        });
+        db.insert_block(1, &block_info_3).unwrap();
+        let stats = db.storage_stats().unwrap();
+        assert_eq!(stats.blocks.rows, 3);
+        let compressed_len = stats.blocks.bytes
+            - (serde_json::to_string(&block_info_1).unwrap().len()
+                + serde_json::to_string(&block_info_2).unwrap().len()) as u64;
+        assert!(compressed_len > 0);
+        assert!(compressed_len < serde_json::to_string(&block_info_3).unwrap().len() as u64);
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_block_columns() {
+        let db_path = tmp_db_path();
+        let consensus_hash = ConsensusHash([0x04; 20]);
+        let (mut signed_block_info, signed_block) = create_block();
+        signed_block_info.signed_over = true;
+        let (mut compressed_block_info, compressed_block) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        compressed_block_info.signed_over = true;
+        let (unsigned_block_info, _) = create_block_override(|b| {
+            b.header.chain_length = 4;
+        });
+
+        // a version 1 blocks table, without the columns mirroring the block info
+        {
+            let legacy_db = SignerDb::connect(&db_path).unwrap();
+            legacy_db.execute(CREATE_BLOCKS_TABLE, NO_PARAMS).unwrap();
+            for block_info in [&signed_block_info, &unsigned_block_info] {
+                legacy_db
+                    .execute(
+                        "INSERT INTO blocks (reward_cycle, signer_signature_hash, block_info, compressed) VALUES (?1, ?2, ?3, 0)",
+                        params![
+                            1,
+                            block_info.signer_signature_hash().to_string(),
+                            serde_json::to_string(block_info).unwrap()
+                        ],
+                    )
+                    .unwrap();
+            }
+            let compressed_json = compress(
+                serde_json::to_string(&compressed_block_info)
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .unwrap();
+            legacy_db
+                .execute(
+                    "INSERT INTO blocks (reward_cycle, signer_signature_hash, block_info, compressed) VALUES (?1, ?2, ?3, 1)",
+                    params![
+                        1,
+                        compressed_block_info.signer_signature_hash().to_string(),
+                        compressed_json
+                    ],
+                )
+                .unwrap();
+        }
+
+        let db = SignerDb::new(&db_path).expect("Failed to migrate signer db");
+        assert_eq!(
+            db.signed_block_at(&consensus_hash, 2).unwrap(),
+            Some(signed_block.header.signer_signature_hash())
+        );
+        assert_eq!(
+            db.signed_block_at(&consensus_hash, 3).unwrap(),
+            Some(compressed_block.header.signer_signature_hash())
+        );
+        assert_eq!(db.signed_block_at(&consensus_hash, 4).unwrap(), None);
+        assert_eq!(db.signed_count_for_tenure(&consensus_hash).unwrap(), 2);
+
+        let mut blocks = db.get_blocks_for_reward_cycle(1).unwrap();
+        blocks.sort_by_key(|block_info| block_info.block.header.chain_length);
+        assert_eq!(
+            blocks,
+            vec![
+                signed_block_info,
+                compressed_block_info,
+                unsigned_block_info
+            ]
+        );
+
+        fs::remove_file(db_path).unwrap();
+    }
+
+    #[test]
+    fn test_uncompressed_legacy_block_info() {
+        let db_path = tmp_db_path();
+        let (block_info, block) = create_block();
+
+        // a blocks table that predates the compressed column
+        {
+            let legacy_db = SignerDb::connect(&db_path).unwrap();
+            legacy_db
+                .execute(
+                    "CREATE TABLE blocks (reward_cycle INTEGER NOT NULL, signer_signature_hash TEXT NOT NULL, block_info TEXT NOT NULL, PRIMARY KEY (reward_cycle, signer_signature_hash))",
+                    NO_PARAMS,
+                )
+                .unwrap();
+            legacy_db
+                .execute(
+                    "INSERT INTO blocks (reward_cycle, signer_signature_hash, block_info) VALUES (?1, ?2, ?3)",
+                    params![
+                        1,
+                        block.header.signer_signature_hash().to_string(),
+                        serde_json::to_string(&block_info).unwrap()
+                    ],
+                )
+                .unwrap();
+        }
+
+        let mut db = SignerDb::new(&db_path).expect("Failed to create signer db");
+        db.set_compression(true);
+        assert_eq!(
+            db.block_lookup(1, &block.header.signer_signature_hash())
+                .unwrap()
+                .unwrap(),
+            block_info
+        );
+
+        // compressed and uncompressed blocks are read back side by side
+        let (other_block_info, _) = create_block_override(|b| {
+            b.header.chain_length = 3;
+        });
+        db.insert_block(1, &other_block_info).unwrap();
+        let mut blocks = db.get_blocks_for_reward_cycle(1).unwrap();
+        blocks.sort_by_key(|block_info| block_info.block.header.chain_length);
+        assert_eq!(blocks, vec![block_info, other_block_info]);
+
+        fs::remove_file(db_path).unwrap();
     }
 }
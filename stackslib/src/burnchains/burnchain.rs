@@ -247,7 +247,7 @@ impl BurnchainStateTransition {
             windowed_block_commits,
             windowed_missed_commits,
             burn_blocks,
-        );
+        )?;
         BurnSamplePoint::prometheus_update_miner_commitments(&burn_dist);
 
         // find out which block commits we're going to take
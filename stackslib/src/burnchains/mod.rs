@@ -36,7 +36,7 @@ use self::bitcoin::{
     BitcoinBlock, BitcoinInputType, BitcoinTransaction, BitcoinTxInput, BitcoinTxOutput,
     Error as btc_error,
 };
-use crate::chainstate::burn::distribution::BurnSamplePoint;
+use crate::chainstate::burn::distribution::{BurnSamplePoint, DistributionError};
 use crate::chainstate::burn::operations::leader_block_commit::OUTPUTS_PER_COMMIT;
 use crate::chainstate::burn::operations::{
     BlockstackOperationType, Error as op_error, LeaderKeyRegisterOp,
@@ -682,6 +682,8 @@ pub enum Error {
     FSError(io::Error),
     /// Operation processing error
     OpError(op_error),
+    /// Burn distribution calculation error
+    DistributionError(DistributionError),
     /// Try again error
     TrySyncAgain,
     UnknownBlock(BurnchainHeaderHash),
@@ -705,6 +707,7 @@ impl fmt::Display for Error {
             Error::BurnchainPeerBroken => write!(f, "Remote burnchain peer has misbehaved"),
             Error::FSError(ref e) => fmt::Display::fmt(e, f),
             Error::OpError(ref e) => fmt::Display::fmt(e, f),
+            Error::DistributionError(ref e) => fmt::Display::fmt(e, f),
             Error::TrySyncAgain => write!(f, "Try synchronizing again"),
             Error::UnknownBlock(block) => write!(f, "Unknown burnchain block {}", block),
             Error::NonCanonicalPoxId(parent, child) => write!(
@@ -732,6 +735,7 @@ impl error::Error for Error {
             Error::BurnchainPeerBroken => None,
             Error::FSError(ref e) => Some(e),
             Error::OpError(ref e) => Some(e),
+            Error::DistributionError(ref e) => Some(e),
             Error::TrySyncAgain => None,
             Error::UnknownBlock(_) => None,
             Error::NonCanonicalPoxId(_, _) => None,
@@ -753,6 +757,12 @@ impl From<sqlite_error> for Error {
     }
 }
 
+impl From<DistributionError> for Error {
+    fn from(e: DistributionError) -> Error {
+        Error::DistributionError(e)
+    }
+}
+
 impl From<btc_error> for Error {
     fn from(e: btc_error) -> Error {
         Error::Bitcoin(e)
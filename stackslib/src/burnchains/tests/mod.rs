@@ -264,6 +264,7 @@ impl TestMiner {
             AddressHashMode::SerializeP2WSH => {
                 TransactionAuth::from_p2wsh(&self.privks, self.num_sigs)
             }
+            AddressHashMode::SerializeP2TR => TransactionAuth::from_p2tr(&self.privks[0]),
         }
     }
 
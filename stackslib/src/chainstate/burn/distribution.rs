@@ -14,11 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::cmp;
 use std::collections::{BTreeMap, HashMap};
+use std::{cmp, error, fmt};
 
+use sha2::{Digest, Sha512_256};
 use stacks_common::address::AddressHashMode;
-use stacks_common::util::hash::Hash160;
+use stacks_common::types::chainstate::{BurnchainHeaderHash, VRFSeed};
+use stacks_common::util::hash::{Hash160, Sha512Trunc256Sum};
 use stacks_common::util::log;
 use stacks_common::util::uint::{BitArray, Uint256, Uint512};
 use stacks_common::util::vrf::VRFPublicKey;
@@ -30,6 +32,7 @@ use crate::chainstate::burn::operations::leader_block_commit::MissedBlockCommit;
 use crate::chainstate::burn::operations::{
     BlockstackOperationType, LeaderBlockCommitOp, LeaderKeyRegisterOp,
 };
+use crate::chainstate::burn::SortitionHash;
 use crate::chainstate::stacks::StacksPublicKey;
 use crate::core::MINING_COMMITMENT_WINDOW;
 use crate::monitoring;
@@ -43,6 +46,92 @@ pub struct BurnSamplePoint {
     pub candidate: LeaderBlockCommitOp,
 }
 
+/// Reasons a burn distribution cannot be calculated from the given operations
+#[derive(Debug, Clone, PartialEq)]
+pub enum DistributionError {
+    /// No blocks' worth of block-commits were given
+    EmptyWindow,
+    /// More blocks' worth of block-commits were given than fit in a mining commitment window
+    WindowTooLarge { window_size: usize },
+    /// The block-commits, missed commits, and burn-block flags do not describe the same window
+    MismatchedLengths {
+        block_commits: usize,
+        missed_commits: usize,
+        burn_blocks: usize,
+    },
+    /// A block-commit is not at the block height implied by its position in the window
+    OutOfOrderCommit {
+        commit_txid: Txid,
+        block_height: u64,
+        expected_block_height: u64,
+    },
+    /// The weighted burns of the block-commits do not fit in a u64.
+    /// `accumulated` is the running total at the sample point that overflowed it.
+    BurnOverflow { accumulated: u128 },
+}
+
+impl fmt::Display for DistributionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DistributionError::EmptyWindow => write!(f, "No block-commits in the window"),
+            DistributionError::WindowTooLarge { window_size } => write!(
+                f,
+                "Window of {} blocks exceeds the mining commitment window of {}",
+                window_size, MINING_COMMITMENT_WINDOW
+            ),
+            DistributionError::MismatchedLengths {
+                block_commits,
+                missed_commits,
+                burn_blocks,
+            } => write!(
+                f,
+                "Mismatched window lengths: {} blocks of commits, {} blocks of missed commits, {} burn-block flags",
+                block_commits, missed_commits, burn_blocks
+            ),
+            DistributionError::OutOfOrderCommit {
+                commit_txid,
+                block_height,
+                expected_block_height,
+            } => write!(
+                f,
+                "Block-commit {} is at height {}, but expected height {}",
+                commit_txid, block_height, expected_block_height
+            ),
+            DistributionError::BurnOverflow { accumulated } => write!(
+                f,
+                "Total weighted burn of the block-commits overflows a u64 (accumulated {})",
+                accumulated
+            ),
+        }
+    }
+}
+
+impl error::Error for DistributionError {
+    fn cause(&self) -> Option<&dyn error::Error> {
+        None
+    }
+}
+
+/// A sortition test vector, for checking other implementations against this one: the
+/// block-commits a burn distribution was computed from, and the resulting sample points in the
+/// same order. Burns are encoded as decimal strings and ranges as big-endian hex, so that
+/// consumers do not need 128- or 256-bit JSON numbers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnDistributionTestVector {
+    pub inputs: Vec<LeaderBlockCommitOp>,
+    pub outputs: Vec<BurnSampleRange>,
+}
+
+/// The computed part of a `BurnSamplePoint` in a `BurnDistributionTestVector`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnSampleRange {
+    pub txid: Txid,
+    pub burns: String,
+    pub median_burn: String,
+    pub range_start: String,
+    pub range_end: String,
+}
+
 #[derive(Debug, Clone)]
 enum LinkedCommitIdentifier {
     Missed(MissedBlockCommit),
@@ -97,24 +186,45 @@ impl BurnSamplePoint {
     fn sanity_check_window(
         block_commits: &Vec<Vec<LeaderBlockCommitOp>>,
         missed_commits: &Vec<Vec<MissedBlockCommit>>,
-    ) {
-        assert!(block_commits.len() <= (MINING_COMMITMENT_WINDOW as usize));
-        assert_eq!(missed_commits.len() + 1, block_commits.len());
+        burn_blocks: &Vec<bool>,
+    ) -> Result<(), DistributionError> {
+        if block_commits.is_empty() {
+            return Err(DistributionError::EmptyWindow);
+        }
+        if block_commits.len() > (MINING_COMMITMENT_WINDOW as usize) {
+            return Err(DistributionError::WindowTooLarge {
+                window_size: block_commits.len(),
+            });
+        }
+        if missed_commits.len() + 1 != block_commits.len()
+            || burn_blocks.len() != block_commits.len()
+        {
+            return Err(DistributionError::MismatchedLengths {
+                block_commits: block_commits.len(),
+                missed_commits: missed_commits.len(),
+                burn_blocks: burn_blocks.len(),
+            });
+        }
         let mut block_height_at_index = None;
         for (index, commits) in block_commits.iter().enumerate() {
             let index = index as u64;
             for commit in commits.iter() {
                 if let Some((first_block_height, first_index)) = block_height_at_index {
-                    assert_eq!(
-                        commit.block_height,
-                        first_block_height + (index - first_index),
-                        "Commits and Burns should be in block height order"
-                    );
+                    // commits should be in block height order
+                    let expected_block_height = first_block_height + (index - first_index);
+                    if commit.block_height != expected_block_height {
+                        return Err(DistributionError::OutOfOrderCommit {
+                            commit_txid: commit.txid.clone(),
+                            block_height: commit.block_height,
+                            expected_block_height,
+                        });
+                    }
                 } else {
                     block_height_at_index = Some((commit.block_height, index));
                 }
             }
         }
+        Ok(())
     }
 
     /// Make a burn distribution -- a list of (burn total, block candidate) pairs -- from a block's
@@ -133,7 +243,8 @@ impl BurnSamplePoint {
     ///  Burns are evaluated over the mining commitment window, where the effective burn for
     ///   a commitment is := min(last_burn_amount, median over the window)
     ///
-    /// Returns the distribution, which consumes the given lists of operations.
+    /// Returns the distribution, which consumes the given lists of operations, or an error if the
+    /// given lists do not describe a well-formed window.
     ///
     /// * `block_commits`: this is a mapping from relative block_height to the block
     ///     commits that occurred at that height. These relative block heights start
@@ -154,12 +265,10 @@ impl BurnSamplePoint {
         mut block_commits: Vec<Vec<LeaderBlockCommitOp>>,
         mut missed_commits: Vec<Vec<MissedBlockCommit>>,
         burn_blocks: Vec<bool>,
-    ) -> Vec<BurnSamplePoint> {
+    ) -> Result<Vec<BurnSamplePoint>, DistributionError> {
         // sanity check
+        BurnSamplePoint::sanity_check_window(&block_commits, &missed_commits, &burn_blocks)?;
         let window_size = block_commits.len() as u8;
-        assert!(window_size > 0);
-        BurnSamplePoint::sanity_check_window(&block_commits, &missed_commits);
-        assert_eq!(burn_blocks.len(), block_commits.len());
 
         // first, let's link all of the current block commits to the priors
         let mut commits_with_priors: Vec<_> =
@@ -294,8 +403,8 @@ impl BurnSamplePoint {
             .collect();
 
         // calculate burn ranges
-        BurnSamplePoint::make_sortition_ranges(&mut burn_sample);
-        burn_sample
+        BurnSamplePoint::make_sortition_ranges(&mut burn_sample)?;
+        Ok(burn_sample)
     }
 
     /// Update prometheus metrics from burn samples.
@@ -324,31 +433,91 @@ impl BurnSamplePoint {
         }
     }
 
+    /// Calculate the burn distribution of a single block's worth of block-commits.
+    /// The commits are sorted by txid first, so the resulting sample points (and thus the
+    /// sortition ranges they are assigned) do not depend on the order they were given in.
     #[cfg(test)]
     pub fn make_distribution(
         all_block_candidates: Vec<LeaderBlockCommitOp>,
+        consumed_leader_keys: Vec<LeaderKeyRegisterOp>,
+    ) -> Result<Vec<BurnSamplePoint>, DistributionError> {
+        Self::make_distribution_with_weights(
+            all_block_candidates,
+            consumed_leader_keys,
+            |candidate| candidate.burn_fee as u128,
+        )
+    }
+
+    /// Calculate the burn distribution of a single block's worth of block-commits, like
+    /// `make_distribution()`, but weigh each commit by `weight_fn` instead of its burn fee.
+    /// This lets callers experiment with other weightings, such as the median of a miner's
+    /// last few commits.  The total weight must fit in a u64.
+    pub fn make_distribution_with_weights<F>(
+        mut all_block_candidates: Vec<LeaderBlockCommitOp>,
         _consumed_leader_keys: Vec<LeaderKeyRegisterOp>,
-    ) -> Vec<BurnSamplePoint> {
-        Self::make_min_median_distribution(vec![all_block_candidates], vec![], vec![true])
+        weight_fn: F,
+    ) -> Result<Vec<BurnSamplePoint>, DistributionError>
+    where
+        F: Fn(&LeaderBlockCommitOp) -> u128,
+    {
+        all_block_candidates.sort_by_key(|candidate| candidate.txid);
+        let block_commits = vec![all_block_candidates];
+        BurnSamplePoint::sanity_check_window(&block_commits, &vec![], &vec![true])?;
+
+        let mut burn_sample: Vec<_> = block_commits
+            .into_iter()
+            .flatten()
+            .map(|candidate| {
+                let burns = weight_fn(&candidate);
+                BurnSamplePoint {
+                    burns,
+                    median_burn: burns,
+                    range_start: Uint256::zero(), // To be filled in
+                    range_end: Uint256::zero(),   // To be filled in
+                    candidate,
+                }
+            })
+            .collect();
+        BurnSamplePoint::get_total_burns(&burn_sample)?;
+
+        BurnSamplePoint::make_sortition_ranges(&mut burn_sample)?;
+        Ok(burn_sample)
     }
 
     /// Calculate the ranges between 0 and 2**256 - 1 over which each point in the burn sample
     /// applies, so we can later select which block to use.
-    fn make_sortition_ranges(burn_sample: &mut Vec<BurnSamplePoint>) -> () {
+    /// Returns `DistributionError::BurnOverflow` if the total burns do not fit in a u64.
+    fn make_sortition_ranges(
+        burn_sample: &mut Vec<BurnSamplePoint>,
+    ) -> Result<(), DistributionError> {
         if burn_sample.len() == 0 {
             // empty sample
-            return;
+            return Ok(());
         }
         if burn_sample.len() == 1 {
             // sample that covers the whole range
             burn_sample[0].range_start = Uint256::zero();
             burn_sample[0].range_end = Uint256::max();
-            return;
+            return Ok(());
         }
 
         // total burns for valid blocks?
-        // NOTE: this can't overflow -- there's no way we get that many (u64) burns
-        let total_burns_u128 = BurnSamplePoint::get_total_burns(&burn_sample).unwrap() as u128;
+        let total_burns_u128 = u128::from(BurnSamplePoint::get_total_burns(&burn_sample)?);
+        if total_burns_u128 == 0 {
+            // no candidate burned anything, so there is nothing to weigh them by.
+            // Give each of them an equal share of the range instead of dividing by zero.
+            let num_candidates = Uint512::from_u128(burn_sample.len() as u128);
+            let mut range_start = Uint256::zero();
+            for i in 0..burn_sample.len() {
+                burn_sample[i].range_start = range_start;
+                burn_sample[i].range_end = ((Uint512::from_uint256(&Uint256::max())
+                    * Uint512::from_u128((i + 1) as u128))
+                    / num_candidates)
+                    .to_uint256();
+                range_start = burn_sample[i].range_end;
+            }
+            return Ok(());
+        }
         let total_burns = Uint512::from_u128(total_burns_u128);
 
         // determine range start/end for each sample.
@@ -379,17 +548,182 @@ impl BurnSamplePoint {
                 burn_sample[_i].range_end
             );
         }
+        Ok(())
     }
 
     /// Calculate the total amount of crypto destroyed in this burn distribution.
-    /// Returns None if there was an overflow.
-    pub fn get_total_burns(burn_dist: &[BurnSamplePoint]) -> Option<u64> {
+    /// Returns `DistributionError::BurnOverflow` if the total does not fit in a u64, and logs
+    /// the block-commits with the largest burns.
+    pub fn get_total_burns(burn_dist: &[BurnSamplePoint]) -> Result<u64, DistributionError> {
+        let mut total_burns: u64 = 0;
+        for sample_point in burn_dist.iter() {
+            let accumulated = u128::from(total_burns).saturating_add(sample_point.burns);
+            total_burns = match u64::try_from(accumulated) {
+                Ok(total_burns) => total_burns,
+                Err(_) => {
+                    BurnSamplePoint::log_largest_burns(burn_dist);
+                    return Err(DistributionError::BurnOverflow { accumulated });
+                }
+            };
+        }
+        Ok(total_burns)
+    }
+
+    /// Log the block-commits with the largest burns in this burn distribution
+    fn log_largest_burns(burn_dist: &[BurnSamplePoint]) {
+        let mut largest: Vec<_> = burn_dist.iter().collect();
+        largest.sort_by(|a, b| b.burns.cmp(&a.burns));
+        for sample_point in largest.into_iter().take(5) {
+            warn!("Burn distribution overflows a u64";
+                "txid" => %sample_point.candidate.txid,
+                "burns" => sample_point.burns,
+            );
+        }
+    }
+
+    /// Find the sample point whose sortition range contains `index`.
+    /// Returns None if no range contains it.
+    pub fn select(burn_dist: &[BurnSamplePoint], index: &Uint256) -> Option<usize> {
         burn_dist
             .iter()
-            .try_fold(0u64, |burns_so_far, sample_point| {
-                let n = u64::try_from(sample_point.burns).ok()?;
-                burns_so_far.checked_add(n)
+            .position(|point| point.range_start <= *index && *index < point.range_end)
+    }
+
+    /// Find the sample point that wins the sortition of the burnchain block `burn_header_hash`.
+    /// `parent_sortition_hash` is the sortition hash of the parent snapshot, and `seed` is the VRF
+    /// seed of the last winning block-commit.  The burn header hash and then the seed are mixed
+    /// into the parent sortition hash, just as sortition does, and the resulting index is
+    /// selected from the distribution.
+    /// Returns None if the distribution is empty.
+    pub fn winner_for_seed(
+        burn_dist: &[BurnSamplePoint],
+        parent_sortition_hash: &SortitionHash,
+        burn_header_hash: &BurnchainHeaderHash,
+        seed: &VRFSeed,
+    ) -> Option<usize> {
+        if burn_dist.len() == 1 {
+            // the only candidate wins, even if the index is at the very end of the range
+            return Some(0);
+        }
+        let index = parent_sortition_hash
+            .mix_burn_header(burn_header_hash)
+            .mix_VRF_seed(seed)
+            .to_uint256();
+        Self::select(burn_dist, &index)
+    }
+
+    /// Find the sample point whose sortition range contains each of the given indexes.
+    /// The range boundaries are collected once and binary-searched for each index, so this is
+    /// cheaper than calling `select` for each index when replaying many sortitions.
+    pub fn select_batch(burn_dist: &[BurnSamplePoint], hashes: &[Uint256]) -> Vec<Option<usize>> {
+        // the ranges are contiguous and ordered, so the range ends are sorted
+        let range_ends: Vec<_> = burn_dist.iter().map(|point| point.range_end).collect();
+        hashes
+            .iter()
+            .map(|index| {
+                let i = range_ends.partition_point(|range_end| range_end <= index);
+                burn_dist
+                    .get(i)
+                    .filter(|point| point.range_start <= *index)
+                    .map(|_| i)
+            })
+            .collect()
+    }
+
+    /// Calculate a digest of the given distribution, so that nodes can cheaply check that they
+    /// computed the same sortition. Each sample point's txid, burns, and range are hashed in order.
+    pub fn distribution_digest(burn_dist: &[BurnSamplePoint]) -> Sha512Trunc256Sum {
+        let mut hasher = Sha512_256::new();
+        for point in burn_dist.iter() {
+            hasher.update(point.candidate.txid.as_bytes());
+            hasher.update(point.burns.to_be_bytes());
+            hasher.update(point.range_start.to_u8_slice_be());
+            hasher.update(point.range_end.to_u8_slice_be());
+        }
+        Sha512Trunc256Sum::from_hasher(hasher)
+    }
+
+    /// Encode the given distribution as a `BurnDistributionTestVector` in JSON
+    pub fn to_test_vector_json(burn_dist: &[BurnSamplePoint]) -> String {
+        let test_vector = BurnDistributionTestVector {
+            inputs: burn_dist
+                .iter()
+                .map(|point| point.candidate.clone())
+                .collect(),
+            outputs: burn_dist
+                .iter()
+                .map(|point| BurnSampleRange {
+                    txid: point.candidate.txid.clone(),
+                    burns: point.burns.to_string(),
+                    median_burn: point.median_burn.to_string(),
+                    range_start: point.range_start.to_hex_be(),
+                    range_end: point.range_end.to_hex_be(),
+                })
+                .collect(),
+        };
+        serde_json::to_string(&test_vector)
+            .expect("FATAL: failed to serialize burn distribution test vector")
+    }
+
+    /// Decode a distribution from a `BurnDistributionTestVector` in JSON.
+    /// Returns None if the JSON is malformed, or if its inputs and outputs do not line up.
+    pub fn from_test_vector_json(json: &str) -> Option<Vec<BurnSamplePoint>> {
+        let test_vector: BurnDistributionTestVector = serde_json::from_str(json).ok()?;
+        if test_vector.inputs.len() != test_vector.outputs.len() {
+            return None;
+        }
+        test_vector
+            .inputs
+            .into_iter()
+            .zip(test_vector.outputs)
+            .map(|(candidate, output)| {
+                if candidate.txid != output.txid {
+                    return None;
+                }
+                Some(BurnSamplePoint {
+                    burns: output.burns.parse().ok()?,
+                    median_burn: output.median_burn.parse().ok()?,
+                    range_start: Uint256::from_hex_be(&output.range_start)?,
+                    range_end: Uint256::from_hex_be(&output.range_end)?,
+                    candidate,
+                })
             })
+            .collect()
+    }
+
+    /// Calculate the distribution that would result from adding `extra_burn` to the sample point
+    /// at `target_index`, with the sortition ranges recomputed. The given distribution is left
+    /// untouched, and is returned as-is if `target_index` is out of bounds.
+    /// Returns `DistributionError::BurnOverflow` if the simulated total burns do not fit in a u64.
+    pub fn simulate_user_burn(
+        burn_dist: &[BurnSamplePoint],
+        target_index: usize,
+        extra_burn: u128,
+    ) -> Result<Vec<BurnSamplePoint>, DistributionError> {
+        let mut simulated = burn_dist.to_vec();
+        let Some(target) = simulated.get_mut(target_index) else {
+            return Ok(simulated);
+        };
+        target.burns = target.burns.saturating_add(extra_burn);
+        BurnSamplePoint::get_total_burns(&simulated)?;
+        BurnSamplePoint::make_sortition_ranges(&mut simulated)?;
+        Ok(simulated)
+    }
+
+    /// Calculate the minimum burn a sample point needs in order to be assigned more than half of
+    /// the sortition range, given the total burns of all the other sample points.
+    pub fn min_burn_to_dominate(other_burns_total: u128) -> u128 {
+        other_burns_total.saturating_add(1)
+    }
+
+    /// Calculate the minimum burn a new block-commit needs in order to be assigned more than half
+    /// of the sortition range, given the existing burn distribution.
+    /// Returns None if there was an overflow.
+    pub fn get_min_burn_to_dominate(burn_dist: &[BurnSamplePoint]) -> Option<u128> {
+        let other_burns_total = BurnSamplePoint::get_total_burns(burn_dist).ok()?;
+        Some(BurnSamplePoint::min_burn_to_dominate(u128::from(
+            other_burns_total,
+        )))
     }
 }
 
@@ -406,7 +740,7 @@ mod tests {
     use stacks_common::util::uint::{BitArray, Uint256, Uint512};
     use stacks_common::util::vrf::*;
 
-    use super::BurnSamplePoint;
+    use super::{BurnSamplePoint, DistributionError};
     use crate::burnchains::bitcoin::address::BitcoinAddress;
     use crate::burnchains::bitcoin::keys::BitcoinPublicKey;
     use crate::burnchains::bitcoin::BitcoinNetworkType;
@@ -417,7 +751,7 @@ mod tests {
     use crate::chainstate::burn::operations::{
         BlockstackOperationType, LeaderBlockCommitOp, LeaderKeyRegisterOp,
     };
-    use crate::chainstate::burn::ConsensusHash;
+    use crate::chainstate::burn::{ConsensusHash, SortitionHash};
     use crate::chainstate::stacks::address::StacksAddressExtensions;
     use crate::chainstate::stacks::index::TrieHashExtension;
     use crate::chainstate::stacks::StacksPublicKey;
@@ -534,7 +868,8 @@ mod tests {
             commits.clone(),
             vec![vec![]; (MINING_COMMITMENT_WINDOW - 1) as usize],
             vec![false, false, false, true, true, true],
-        );
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 2, "Should be two miners");
 
@@ -567,7 +902,8 @@ mod tests {
             commits.clone(),
             vec![vec![]; (MINING_COMMITMENT_WINDOW - 1) as usize],
             vec![false, false, false, true, true, true],
-        );
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 2, "Should be two miners");
 
@@ -627,7 +963,8 @@ mod tests {
             commits.clone(),
             vec![vec![]; (MINING_COMMITMENT_WINDOW - 1) as usize],
             vec![false, false, false, false, false, false],
-        );
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 2, "Should be two miners");
 
@@ -680,7 +1017,8 @@ mod tests {
             commits.clone(),
             vec![vec![]; (MINING_COMMITMENT_WINDOW - 1) as usize],
             vec![false, false, false, false, false, false],
-        );
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 2, "Should be two miners");
 
@@ -736,7 +1074,8 @@ mod tests {
             commits.clone(),
             missed_commits.clone(),
             vec![false, false, false, false, false, false],
-        );
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 2, "Should be two miners");
 
@@ -750,6 +1089,447 @@ mod tests {
         assert_eq!(result[1].candidate.txid, commits[5][1].txid);
     }
 
+    #[test]
+    fn make_distribution_with_weights() {
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+        ];
+
+        // the identity weight yields the plain distribution
+        let burn_dist = BurnSamplePoint::make_distribution(commits.clone(), vec![]).unwrap();
+        let identity_dist =
+            BurnSamplePoint::make_distribution_with_weights(commits.clone(), vec![], |bc| {
+                bc.burn_fee as u128
+            })
+            .unwrap();
+        assert_eq!(identity_dist, burn_dist);
+
+        // a custom weight replaces each commit's burn fee
+        let weighted_dist =
+            BurnSamplePoint::make_distribution_with_weights(commits.clone(), vec![], |bc| {
+                100 - bc.burn_fee as u128
+            })
+            .unwrap();
+        assert_eq!(weighted_dist.len(), burn_dist.len());
+        for (weighted, plain) in weighted_dist.iter().zip(burn_dist.iter()) {
+            assert_eq!(weighted.candidate, plain.candidate);
+            assert_eq!(weighted.burns, 100 - plain.burns);
+            assert_eq!(weighted.median_burn, weighted.burns);
+        }
+        assert_eq!(BurnSamplePoint::get_total_burns(&weighted_dist), Ok(240));
+        assert_eq!(weighted_dist[0].range_start, Uint256::zero());
+        assert_eq!(weighted_dist[2].range_end, Uint256::max());
+
+        // the smallest commit now covers the largest range
+        let range_size = |point: &BurnSamplePoint| point.range_end - point.range_start;
+        let smallest = weighted_dist
+            .iter()
+            .find(|point| point.candidate.burn_fee == 10)
+            .unwrap();
+        let largest = weighted_dist
+            .iter()
+            .find(|point| point.candidate.burn_fee == 30)
+            .unwrap();
+        assert!(range_size(smallest) > range_size(largest));
+
+        // weights too large to total are rejected
+        assert_eq!(
+            BurnSamplePoint::make_distribution_with_weights(commits.clone(), vec![], |_| {
+                u64::MAX as u128
+            }),
+            Err(DistributionError::BurnOverflow {
+                accumulated: 2 * (u64::MAX as u128)
+            })
+        );
+
+        // a single weight too large for a u64 is rejected with it as the accumulated total
+        assert_eq!(
+            BurnSamplePoint::make_distribution_with_weights(commits, vec![], |bc| {
+                if bc.burn_fee == 20 {
+                    u128::MAX
+                } else {
+                    1
+                }
+            }),
+            Err(DistributionError::BurnOverflow {
+                accumulated: u128::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn make_distribution_with_zero_burns() {
+        let commits = vec![
+            make_block_commit(0, 1, 1, 1, None, 1),
+            make_block_commit(0, 2, 2, 2, None, 1),
+            make_block_commit(0, 3, 3, 3, None, 1),
+            make_block_commit(0, 4, 4, 4, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits, vec![]).unwrap();
+        assert_eq!(burn_dist.len(), 4);
+
+        // every candidate gets an equal share of the whole range
+        let quarter = Uint256::max() / Uint256::from_u64(4);
+        assert_eq!(burn_dist[0].range_start, Uint256::zero());
+        for i in 0..burn_dist.len() {
+            assert_eq!(burn_dist[i].burns, 0);
+            if i > 0 {
+                assert_eq!(burn_dist[i].range_start, burn_dist[i - 1].range_end);
+            }
+            let width = burn_dist[i].range_end - burn_dist[i].range_start;
+            assert!(width == quarter || width == quarter + Uint256::from_u64(1));
+        }
+        assert_eq!(burn_dist[3].range_end, Uint256::max());
+
+        for (i, point) in burn_dist.iter().enumerate() {
+            assert_eq!(
+                BurnSamplePoint::select(&burn_dist, &point.range_start),
+                Some(i)
+            );
+        }
+    }
+
+    #[test]
+    fn select_batch() {
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+            make_block_commit(40, 4, 4, 4, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits, vec![]).unwrap();
+
+        let mut hashes = vec![Uint256::zero(), Uint256::max()];
+        for point in burn_dist.iter() {
+            hashes.push(point.range_start);
+            hashes.push(point.range_end);
+            hashes.push(point.range_start + Uint256::from_u64(1));
+        }
+        let last_range_start = burn_dist[burn_dist.len() - 1].range_start;
+        hashes.push(last_range_start - Uint256::from_u64(1));
+
+        let batch = BurnSamplePoint::select_batch(&burn_dist, &hashes);
+        let individual: Vec<_> = hashes
+            .iter()
+            .map(|hash| BurnSamplePoint::select(&burn_dist, hash))
+            .collect();
+        assert_eq!(batch, individual);
+
+        assert_eq!(batch[0], Some(0));
+        // the end of the space is not part of any range
+        assert_eq!(batch[1], None);
+        for (i, point) in burn_dist.iter().enumerate() {
+            assert_eq!(
+                BurnSamplePoint::select(&burn_dist, &point.range_start),
+                Some(i)
+            );
+        }
+        assert_eq!(batch[batch.len() - 1], Some(burn_dist.len() - 2));
+
+        assert_eq!(
+            BurnSamplePoint::select_batch(&[], &hashes),
+            vec![None; hashes.len()]
+        );
+    }
+
+    #[test]
+    fn winner_for_seed() {
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits.clone(), vec![]).unwrap();
+        let parent_sortition_hash = SortitionHash([0x11; 32]);
+        let burn_header_hash = BurnchainHeaderHash([0x22; 32]);
+
+        for (seed, winner) in [(0x31, 0), (0x34, 1), (0x30, 2)] {
+            assert_eq!(
+                BurnSamplePoint::winner_for_seed(
+                    &burn_dist,
+                    &parent_sortition_hash,
+                    &burn_header_hash,
+                    &VRFSeed([seed; 32]),
+                ),
+                Some(winner)
+            );
+        }
+
+        // a lone candidate always wins, and there is no winner without candidates
+        let single_dist =
+            BurnSamplePoint::make_distribution(vec![commits[0].clone()], vec![]).unwrap();
+        assert_eq!(
+            BurnSamplePoint::winner_for_seed(
+                &single_dist,
+                &parent_sortition_hash,
+                &burn_header_hash,
+                &VRFSeed([0x31; 32]),
+            ),
+            Some(0)
+        );
+        assert_eq!(
+            BurnSamplePoint::winner_for_seed(
+                &[],
+                &parent_sortition_hash,
+                &burn_header_hash,
+                &VRFSeed([0x31; 32]),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn distribution_digest() {
+        let make_commits = || {
+            vec![
+                make_block_commit(10, 1, 1, 1, None, 1),
+                make_block_commit(20, 2, 2, 2, None, 1),
+                make_block_commit(30, 3, 3, 3, None, 1),
+            ]
+        };
+        let burn_dist = BurnSamplePoint::make_distribution(make_commits(), vec![]).unwrap();
+        let digest = BurnSamplePoint::distribution_digest(&burn_dist);
+
+        // independently computing the same distribution yields the same digest
+        let same_burn_dist = BurnSamplePoint::make_distribution(make_commits(), vec![]).unwrap();
+        assert_eq!(
+            BurnSamplePoint::distribution_digest(&same_burn_dist),
+            digest
+        );
+
+        // changing any burn changes the digest
+        for i in 0..burn_dist.len() {
+            let mut changed_burn_dist = burn_dist.clone();
+            changed_burn_dist[i].burns += 1;
+            assert_ne!(
+                BurnSamplePoint::distribution_digest(&changed_burn_dist),
+                digest
+            );
+        }
+
+        // as does a different distribution of the same total burn
+        let mut commits = make_commits();
+        commits[0].burn_fee = 20;
+        commits[1].burn_fee = 10;
+        let other_burn_dist = BurnSamplePoint::make_distribution(commits, vec![]).unwrap();
+        assert_ne!(
+            BurnSamplePoint::distribution_digest(&other_burn_dist),
+            digest
+        );
+        assert_ne!(BurnSamplePoint::distribution_digest(&[]), digest);
+    }
+
+    #[test]
+    fn burn_distribution_test_vector() {
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits, vec![]).unwrap();
+        let json = BurnSamplePoint::to_test_vector_json(&burn_dist);
+
+        // encoding is deterministic
+        assert_eq!(BurnSamplePoint::to_test_vector_json(&burn_dist), json);
+        assert!(json.contains(&format!(
+            "\"range_end\":\"{}\"",
+            burn_dist[2].range_end.to_hex_be()
+        )));
+
+        assert_eq!(
+            BurnSamplePoint::from_test_vector_json(&json).unwrap(),
+            burn_dist
+        );
+        assert_eq!(
+            BurnSamplePoint::from_test_vector_json(&BurnSamplePoint::to_test_vector_json(&[]))
+                .unwrap(),
+            vec![]
+        );
+
+        // inputs and outputs must line up
+        let mut test_vector: BurnDistributionTestVector = serde_json::from_str(&json).unwrap();
+        test_vector.outputs.swap(0, 1);
+        assert!(BurnSamplePoint::from_test_vector_json(
+            &serde_json::to_string(&test_vector).unwrap()
+        )
+        .is_none());
+        test_vector.outputs.pop();
+        assert!(BurnSamplePoint::from_test_vector_json(
+            &serde_json::to_string(&test_vector).unwrap()
+        )
+        .is_none());
+        assert!(BurnSamplePoint::from_test_vector_json("not json").is_none());
+    }
+
+    #[test]
+    fn simulate_user_burn() {
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits, vec![]).unwrap();
+        let simulated = BurnSamplePoint::simulate_user_burn(&burn_dist, 1, 40).unwrap();
+
+        assert_eq!(simulated.len(), burn_dist.len());
+        assert_eq!(simulated[1].burns, 60);
+        let share = |point: &BurnSamplePoint| point.range_end - point.range_start;
+        assert!(share(&simulated[1]) > share(&burn_dist[1]));
+        assert!(share(&simulated[0]) < share(&burn_dist[0]));
+        assert!(share(&simulated[2]) < share(&burn_dist[2]));
+
+        // the ranges still partition the space
+        assert_eq!(simulated[0].range_start, Uint256::zero());
+        for i in 1..simulated.len() {
+            assert_eq!(simulated[i].range_start, simulated[i - 1].range_end);
+        }
+        assert_eq!(simulated[simulated.len() - 1].range_end, Uint256::max());
+
+        // out-of-bounds targets leave the distribution unchanged
+        assert_eq!(
+            BurnSamplePoint::simulate_user_burn(&burn_dist, 3, 40),
+            Ok(burn_dist.clone())
+        );
+
+        // burns that overflow the total are reported instead of panicking
+        assert!(matches!(
+            BurnSamplePoint::simulate_user_burn(&burn_dist, 1, u64::MAX.into()),
+            Err(DistributionError::BurnOverflow { .. })
+        ));
+        assert_eq!(
+            BurnSamplePoint::simulate_user_burn(&burn_dist, 1, u128::MAX),
+            Err(DistributionError::BurnOverflow {
+                accumulated: u128::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn make_distribution_is_independent_of_commit_order() {
+        // two of the commits burn the same amount
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(20, 3, 3, 3, None, 1),
+        ];
+        let mut reversed_commits = commits.clone();
+        reversed_commits.reverse();
+
+        let burn_dist = BurnSamplePoint::make_distribution(commits.clone(), vec![]).unwrap();
+        let reversed_burn_dist =
+            BurnSamplePoint::make_distribution(reversed_commits, vec![]).unwrap();
+        assert_eq!(burn_dist, reversed_burn_dist);
+
+        let txids: Vec<_> = burn_dist.iter().map(|point| point.candidate.txid).collect();
+        let expected_txids: Vec<_> = commits.iter().map(|commit| commit.txid).collect();
+        assert_eq!(txids, expected_txids);
+    }
+
+    #[test]
+    fn make_min_median_distribution_rejects_malformed_window() {
+        assert_eq!(
+            BurnSamplePoint::make_min_median_distribution(vec![], vec![], vec![]),
+            Err(DistributionError::EmptyWindow)
+        );
+
+        let window_size = (MINING_COMMITMENT_WINDOW + 1) as usize;
+        assert_eq!(
+            BurnSamplePoint::make_min_median_distribution(
+                vec![vec![]; window_size],
+                vec![vec![]; window_size - 1],
+                vec![false; window_size],
+            ),
+            Err(DistributionError::WindowTooLarge { window_size })
+        );
+
+        let commits = vec![
+            vec![make_block_commit(10, 1, 1, 1, None, 1)],
+            vec![make_block_commit(10, 2, 2, 2, Some(1), 2)],
+        ];
+        assert_eq!(
+            BurnSamplePoint::make_min_median_distribution(
+                commits.clone(),
+                vec![],
+                vec![false, false],
+            ),
+            Err(DistributionError::MismatchedLengths {
+                block_commits: 2,
+                missed_commits: 0,
+                burn_blocks: 2,
+            })
+        );
+        assert_eq!(
+            BurnSamplePoint::make_min_median_distribution(
+                commits.clone(),
+                vec![vec![]],
+                vec![false],
+            ),
+            Err(DistributionError::MismatchedLengths {
+                block_commits: 2,
+                missed_commits: 1,
+                burn_blocks: 1,
+            })
+        );
+
+        let mut out_of_order_commits = commits.clone();
+        out_of_order_commits[1][0].block_height = 3;
+        let out_of_order_txid = out_of_order_commits[1][0].txid.clone();
+        assert_eq!(
+            BurnSamplePoint::make_min_median_distribution(
+                out_of_order_commits,
+                vec![vec![]],
+                vec![false, false],
+            ),
+            Err(DistributionError::OutOfOrderCommit {
+                commit_txid: out_of_order_txid,
+                block_height: 3,
+                expected_block_height: 2,
+            })
+        );
+
+        assert!(BurnSamplePoint::make_min_median_distribution(
+            commits,
+            vec![vec![]],
+            vec![false, false]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn min_burn_to_dominate() {
+        assert_eq!(BurnSamplePoint::min_burn_to_dominate(0), 1);
+        assert_eq!(BurnSamplePoint::min_burn_to_dominate(100), 101);
+        assert_eq!(BurnSamplePoint::min_burn_to_dominate(u128::MAX), u128::MAX);
+
+        let commits = vec![
+            make_block_commit(10, 1, 1, 1, None, 1),
+            make_block_commit(20, 2, 2, 2, None, 1),
+            make_block_commit(30, 3, 3, 3, None, 1),
+        ];
+        let burn_dist = BurnSamplePoint::make_distribution(commits.clone(), vec![]).unwrap();
+        let min_burn = BurnSamplePoint::get_min_burn_to_dominate(&burn_dist).unwrap();
+        assert_eq!(min_burn, 61);
+        assert_eq!(BurnSamplePoint::get_min_burn_to_dominate(&[]).unwrap(), 1);
+
+        // a new commit burning the minimum gets more than half of the range...
+        let half_range = Uint256::max() >> 1;
+        let mut dominating_commits = commits.clone();
+        dominating_commits.insert(0, make_block_commit(min_burn as u64, 4, 4, 4, None, 1));
+        let burn_dist = BurnSamplePoint::make_distribution(dominating_commits, vec![]).unwrap();
+        let new_point = &burn_dist[3];
+        assert_eq!(new_point.burns, min_burn);
+        assert!(new_point.range_end - new_point.range_start > half_range);
+
+        // ...but burning any less does not
+        let mut tied_commits = commits;
+        tied_commits.insert(0, make_block_commit(min_burn as u64 - 1, 4, 4, 4, None, 1));
+        let burn_dist = BurnSamplePoint::make_distribution(tied_commits, vec![]).unwrap();
+        let new_point = &burn_dist[3];
+        assert!(new_point.range_end - new_point.range_start <= half_range);
+    }
+
     #[test]
     fn make_burn_distribution() {
         let first_burn_hash = BurnchainHeaderHash::from_hex(
@@ -1256,7 +2036,8 @@ mod tests {
             let dist = BurnSamplePoint::make_distribution(
                 f.block_commits.iter().cloned().collect(),
                 f.consumed_leader_keys.iter().cloned().collect(),
-            );
+            )
+            .unwrap();
             assert_eq!(dist, f.res);
         }
     }
@@ -476,7 +476,7 @@ mod test {
         burn_dist: &[BurnSamplePoint],
         txids: &Vec<Txid>,
     ) -> Result<BlockSnapshot, db_error> {
-        let total_burn = BurnSamplePoint::get_total_burns(burn_dist);
+        let total_burn = BurnSamplePoint::get_total_burns(burn_dist).ok();
         BlockSnapshot::make_snapshot(
             sort_tx,
             burnchain,
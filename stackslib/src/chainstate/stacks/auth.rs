@@ -14,13 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
+use std::collections::HashSet;
 use std::io;
 use std::io::prelude::*;
 use std::io::{Read, Write};
 
+use rayon::prelude::*;
 use stacks_common::address::{public_keys_to_address_hash, AddressHashMode};
 use stacks_common::codec::{
-    read_next, write_next, Error as codec_error, StacksMessageCodec, MAX_MESSAGE_LEN,
+    read_next, read_next_at_most, write_next, Error as codec_error, StacksMessageCodec,
+    MAX_MESSAGE_LEN,
 };
 use stacks_common::types::chainstate::StacksAddress;
 use stacks_common::types::StacksPublicKeyBuffer;
@@ -39,6 +43,51 @@ use crate::chainstate::stacks::{
 };
 use crate::net::{Error as net_error, STACKS_PUBLIC_KEY_ENCODED_SIZE};
 
+/// Maximum number of auth fields `MultisigSpendingCondition::canonicalize_fields` will reorder.
+/// It may have to try every ordering of the fields, so this bounds its running time.
+pub const MAX_CANONICALIZE_FIELDS: usize = 8;
+
+/// Maximum number of auth fields a multisig spending condition may declare.  This matches the
+/// largest signature count a condition can require, and is checked before the fields are read.
+pub const MAX_AUTH_FIELDS: u32 = u16::MAX as u32;
+
+/// Recovers the public key that produced a signature over a message hash.  Spending condition
+/// verification goes through this, so that alternative secp256k1 libraries can be benchmarked.
+pub trait RecoveryBackend {
+    fn recover_to_pubkey(
+        &self,
+        msg_hash: &[u8],
+        sig: &MessageSignature,
+    ) -> Result<StacksPublicKey, &'static str>;
+}
+
+/// The default recovery backend, which uses `StacksPublicKey::recover_to_pubkey`
+pub struct Secp256k1RecoveryBackend;
+
+impl RecoveryBackend for Secp256k1RecoveryBackend {
+    fn recover_to_pubkey(
+        &self,
+        msg_hash: &[u8],
+        sig: &MessageSignature,
+    ) -> Result<StacksPublicKey, &'static str> {
+        StacksPublicKey::recover_to_pubkey(msg_hash, sig)
+    }
+}
+
+/// Context for verifying spending conditions
+pub struct VerifyContext<'a> {
+    /// The backend used to recover public keys from signatures
+    pub recovery: &'a dyn RecoveryBackend,
+}
+
+impl Default for VerifyContext<'static> {
+    fn default() -> Self {
+        VerifyContext {
+            recovery: &Secp256k1RecoveryBackend,
+        }
+    }
+}
+
 impl StacksMessageCodec for TransactionAuthField {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
         match *self {
@@ -136,7 +185,7 @@ impl StacksMessageCodec for MultisigSpendingCondition {
         let tx_fee: u64 = read_next(fd)?;
         let fields: Vec<TransactionAuthField> = {
             let mut bound_read = BoundReader::from_reader(fd, MAX_MESSAGE_LEN as u64);
-            read_next(&mut bound_read)
+            read_next_at_most(&mut bound_read, MAX_AUTH_FIELDS)
         }?;
 
         let signatures_required: u16 = read_next(fd)?;
@@ -216,10 +265,143 @@ impl MultisigSpendingCondition {
             .push(TransactionAuthField::PublicKey(public_key));
     }
 
+    /// Like `push_signature()`, but refuse an uncompressed key encoding if the hash mode only
+    /// allows compressed keys, instead of leaving it to be rejected at verification time.
+    pub fn try_push_signature(
+        &mut self,
+        key_encoding: TransactionPublicKeyEncoding,
+        signature: MessageSignature,
+    ) -> Result<(), net_error> {
+        if self.hash_mode.requires_compressed_keys()
+            && key_encoding == TransactionPublicKeyEncoding::Uncompressed
+        {
+            return Err(net_error::SigningError(format!(
+                "expected compressed keys only for hash mode {:?}",
+                self.hash_mode
+            )));
+        }
+        self.push_signature(key_encoding, signature);
+        Ok(())
+    }
+
+    /// Like `push_public_key()`, but refuse an uncompressed key if the hash mode only allows
+    /// compressed keys, instead of leaving it to be rejected at verification time.
+    pub fn try_push_public_key(&mut self, public_key: StacksPublicKey) -> Result<(), net_error> {
+        if self.hash_mode.requires_compressed_keys() && !public_key.compressed() {
+            return Err(net_error::SigningError(format!(
+                "expected compressed keys only for hash mode {:?}",
+                self.hash_mode
+            )));
+        }
+        self.push_public_key(public_key);
+        Ok(())
+    }
+
     pub fn pop_auth_field(&mut self) -> Option<TransactionAuthField> {
         self.fields.pop()
     }
 
+    /// Number of signature fields appended so far
+    pub fn signatures_present(&self) -> u16 {
+        let mut num_sigs: u16 = 0;
+        for field in self.fields.iter() {
+            if field.is_signature() {
+                num_sigs = num_sigs
+                    .checked_add(1)
+                    .expect("Unreasonable amount of signatures"); // something is seriously wrong if this fails
+            }
+        }
+        num_sigs
+    }
+
+    /// Number of signatures that still need to be appended before the condition can be
+    /// finalized
+    pub fn remaining_signatures(&self) -> u16 {
+        self.signatures_required
+            .saturating_sub(self.signatures_present())
+    }
+
+    /// Does this condition carry at least `signatures_required` signatures?
+    pub fn is_complete(&self) -> bool {
+        self.signatures_present() >= self.signatures_required
+    }
+
+    /// Check that `signatures_required` and the auth fields obey the hash mode's constraints.
+    /// P2WSH (sequential or not) conditions must use compressed keys only, and must require at
+    /// least one and at most as many signatures as there are distinct keys.
+    /// Apart from sequential P2WSH's compressed keys, which the codec has always required, this
+    /// is only enforced from Stacks 3.0 on, via `TransactionAuth::validate_multisig_constraints()`.
+    pub fn validate_consistency(&self) -> Result<(), net_error> {
+        if !self.hash_mode.requires_compressed_keys() {
+            return Ok(());
+        }
+
+        let mut public_keys = HashSet::new();
+        let mut num_sigs: u32 = 0;
+        for field in self.fields.iter() {
+            match field {
+                TransactionAuthField::Signature(key_encoding, _) => {
+                    if *key_encoding == TransactionPublicKeyEncoding::Uncompressed {
+                        return Err(net_error::VerifyingError(
+                            "expected compressed keys only".to_string(),
+                        ));
+                    }
+                    // the signing key is only known once recovered, so count it as distinct
+                    num_sigs += 1;
+                }
+                TransactionAuthField::PublicKey(pubk) => {
+                    if !pubk.compressed() {
+                        return Err(net_error::VerifyingError(
+                            "expected compressed keys only".to_string(),
+                        ));
+                    }
+                    public_keys.insert(pubk.to_bytes_compressed());
+                }
+            }
+        }
+
+        let distinct_key_count = num_sigs + public_keys.len() as u32;
+        if self.signatures_required == 0 || u32::from(self.signatures_required) > distinct_key_count
+        {
+            return Err(net_error::VerifyingError(format!(
+                "signatures required ({}) must be between 1 and the number of distinct keys ({})",
+                self.signatures_required, distinct_key_count
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that no two signatures come from the same public key, given the keys recovered
+    /// from them (e.g. by `verify_and_recover()`).
+    /// This is only enforced from Stacks 3.0 on, via
+    /// `TransactionAuth::validate_multisig_constraints()`.
+    pub fn validate_distinct_signing_keys(
+        signing_keys: &[StacksPublicKey],
+    ) -> Result<(), net_error> {
+        let mut seen = HashSet::new();
+        for signing_key in signing_keys.iter() {
+            if !seen.insert(signing_key.to_bytes_compressed()) {
+                return Err(net_error::VerifyingError(
+                    "Multiple signatures from the same public key".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of times the sighash advances while a complete condition is signed or verified.
+    /// Each signature field advances it once, and public key fields never do, so for sequential
+    /// hash modes this is `signatures_required` -- i.e. a coordinator hands a signer the sighash
+    /// after as many advances as there are signatures ahead of it. Order-independent hash modes
+    /// check every signature against the initial sighash, so it never advances.
+    pub fn expected_sighash_advances(&self) -> u16 {
+        if self.hash_mode.is_order_independent() {
+            0
+        } else {
+            self.signatures_required
+        }
+    }
+
     pub fn address_mainnet(&self) -> StacksAddress {
         StacksAddress {
             version: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
@@ -242,7 +424,201 @@ impl MultisigSpendingCondition {
         initial_sighash: &Txid,
         cond_code: &TransactionAuthFlags,
     ) -> Result<Txid, net_error> {
+        self.verify_and_recover(initial_sighash, cond_code)
+            .map(|(next_sighash, _)| next_sighash)
+    }
+
+    /// Authenticate a spending condition like `verify()`, and also return the public keys
+    /// recovered from its signatures, in field order.
+    pub fn verify_and_recover(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
+        self.verify_and_recover_with(&VerifyContext::default(), initial_sighash, cond_code)
+    }
+
+    /// Like `verify_and_recover()`, but recover public keys with the given context's backend
+    pub fn verify_and_recover_with(
+        &self,
+        ctx: &VerifyContext,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
+        let (addr_bytes, next_sighash, signing_keys) =
+            self.recover_signer(ctx, initial_sighash, cond_code)?;
+        if addr_bytes != self.signer {
+            return Err(net_error::VerifyingError(format!(
+                "Signer hash does not equal hash of public key(s): {} != {}",
+                addr_bytes, self.signer
+            )));
+        }
+
+        Ok((next_sighash, signing_keys))
+    }
+
+    /// Authenticate a spending condition against an initial sighash, accepting any of the
+    /// given signer hashes (e.g. both the old and new key sets of a wallet rotating its keys).
+    /// The recovered signer hash is checked against `allowed_signers` only; the condition's own
+    /// `signer` field is not consulted.
+    pub fn verify_against(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+        allowed_signers: &[Hash160],
+    ) -> Result<Txid, net_error> {
+        let (addr_bytes, next_sighash, _) =
+            self.recover_signer(&VerifyContext::default(), initial_sighash, cond_code)?;
+        if !allowed_signers.contains(&addr_bytes) {
+            return Err(net_error::VerifyingError(format!(
+                "Hash of public key(s) is not an allowed signer: {}",
+                addr_bytes
+            )));
+        }
+
+        Ok(next_sighash)
+    }
+
+    /// Reorder the auth fields into the order they were signed in: the one ordering whose
+    /// rolling sighash recovers public keys that hash to the signer. Coordinators that
+    /// collected the same fields in different orders thus assemble identical conditions.
+    /// Fails if there are more than `MAX_CANONICALIZE_FIELDS` fields, or if no ordering
+    /// verifies, in which case the fields are left untouched.
+    pub fn canonicalize_fields(
+        &mut self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(), net_error> {
+        if self.fields.len() > MAX_CANONICALIZE_FIELDS {
+            return Err(net_error::SigningError(format!(
+                "Cannot canonicalize more than {} auth fields",
+                MAX_CANONICALIZE_FIELDS
+            )));
+        }
+
+        let mut order = vec![];
+        let mut pubkeys = vec![];
+        if !self.find_signing_order(initial_sighash, cond_code, &mut order, &mut pubkeys) {
+            return Err(net_error::VerifyingError(
+                "No ordering of the auth fields verifies".to_string(),
+            ));
+        }
+
+        let mut canonical = self.clone();
+        canonical.fields = order.into_iter().map(|i| self.fields[i].clone()).collect();
+        canonical.verify(initial_sighash, cond_code)?;
+        *self = canonical;
+        Ok(())
+    }
+
+    /// Depth-first search for an ordering of the auth fields whose public keys hash to the signer.
+    /// `order` holds the indexes of the fields placed so far, `pubkeys` their public keys, and
+    /// `cur_sighash` the sighash the next signature must verify against.
+    /// Returns true once `order` holds a complete ordering.
+    fn find_signing_order(
+        &self,
+        cur_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+        order: &mut Vec<usize>,
+        pubkeys: &mut Vec<StacksPublicKey>,
+    ) -> bool {
+        if order.len() == self.fields.len() {
+            return StacksAddress::from_public_keys(
+                0,
+                &self.hash_mode.to_address_hash_mode(),
+                self.signatures_required as usize,
+                pubkeys,
+            )
+            .map_or(false, |addr| addr.bytes == self.signer);
+        }
+
+        for (i, field) in self.fields.iter().enumerate() {
+            if order.contains(&i) {
+                continue;
+            }
+            let (pubkey, next_sighash) = match field {
+                TransactionAuthField::PublicKey(ref pubkey) => {
+                    (pubkey.clone(), cur_sighash.clone())
+                }
+                TransactionAuthField::Signature(ref pubkey_encoding, ref sigbuf) => {
+                    let Ok((pubkey, next_sighash)) =
+                        TransactionSpendingCondition::next_verification(
+                            cur_sighash,
+                            cond_code,
+                            self.tx_fee,
+                            self.nonce,
+                            pubkey_encoding,
+                            sigbuf,
+                        )
+                    else {
+                        continue;
+                    };
+                    if self.hash_mode.is_order_independent() {
+                        (pubkey, cur_sighash.clone())
+                    } else {
+                        (pubkey, next_sighash)
+                    }
+                }
+            };
+
+            order.push(i);
+            pubkeys.push(pubkey);
+            if self.find_signing_order(&next_sighash, cond_code, order, pubkeys) {
+                return true;
+            }
+            order.pop();
+            pubkeys.pop();
+        }
+        false
+    }
+
+    /// Recover the public key of every field, in field order, without checking them against the
+    /// signer.  Public key fields are returned as-is, and signature fields yield the key that
+    /// produced them.  Recovery tooling can use this to reconstruct a condition's full field set
+    /// when its public keys have been lost.
+    pub fn try_recover_pubkeys(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<Vec<StacksPublicKey>, net_error> {
+        let mut pubkeys = Vec::with_capacity(self.fields.len());
+        let mut cur_sighash = initial_sighash.clone();
+        for field in self.fields.iter() {
+            let pubkey = match field {
+                TransactionAuthField::PublicKey(ref pubkey) => pubkey.clone(),
+                TransactionAuthField::Signature(ref pubkey_encoding, ref sigbuf) => {
+                    let (pubkey, next_sighash) = TransactionSpendingCondition::next_verification(
+                        &cur_sighash,
+                        cond_code,
+                        self.tx_fee,
+                        self.nonce,
+                        pubkey_encoding,
+                        sigbuf,
+                    )?;
+                    if !self.hash_mode.is_order_independent() {
+                        cur_sighash = next_sighash;
+                    }
+                    pubkey
+                }
+            };
+            pubkeys.push(pubkey);
+        }
+        Ok(pubkeys)
+    }
+
+    /// Recover all public keys from the condition's fields, and hash them to the signer via the
+    /// condition's hash mode. Returns the signer hash, the next sighash and the public keys
+    /// recovered from the signatures, in field order.
+    /// If the hash mode is order-independent, every signature is checked against the initial
+    /// sighash, which is also the returned next sighash.
+    fn recover_signer(
+        &self,
+        ctx: &VerifyContext,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Hash160, Txid, Vec<StacksPublicKey>), net_error> {
         let mut pubkeys = vec![];
+        let mut recovered_keys = vec![];
         let mut cur_sighash = initial_sighash.clone();
         let mut num_sigs: u16 = 0;
         let mut have_uncompressed = false;
@@ -259,18 +635,23 @@ impl MultisigSpendingCondition {
                         have_uncompressed = true;
                     }
 
-                    let (pubkey, next_sighash) = TransactionSpendingCondition::next_verification(
-                        &cur_sighash,
-                        cond_code,
-                        self.tx_fee,
-                        self.nonce,
-                        pubkey_encoding,
-                        sigbuf,
-                    )?;
-                    cur_sighash = next_sighash;
+                    let (pubkey, next_sighash) =
+                        TransactionSpendingCondition::next_verification_with(
+                            ctx,
+                            &cur_sighash,
+                            cond_code,
+                            self.tx_fee,
+                            self.nonce,
+                            pubkey_encoding,
+                            sigbuf,
+                        )?;
+                    if !self.hash_mode.is_order_independent() {
+                        cur_sighash = next_sighash;
+                    }
                     num_sigs = num_sigs
                         .checked_add(1)
                         .ok_or(net_error::VerifyingError("Too many signatures".to_string()))?;
+                    recovered_keys.push(pubkey.clone());
                     pubkey
                 }
             };
@@ -283,7 +664,7 @@ impl MultisigSpendingCondition {
             ));
         }
 
-        if have_uncompressed && self.hash_mode == MultisigHashMode::P2WSH {
+        if have_uncompressed && self.hash_mode.requires_compressed_keys() {
             return Err(net_error::VerifyingError(
                 "Uncompressed keys are not allowed in this hash mode".to_string(),
             ));
@@ -303,14 +684,7 @@ impl MultisigSpendingCondition {
             }
         };
 
-        if addr_bytes != self.signer {
-            return Err(net_error::VerifyingError(format!(
-                "Signer hash does not equal hash of public key(s): {} != {}",
-                addr_bytes, self.signer
-            )));
-        }
-
-        Ok(cur_sighash)
+        Ok((addr_bytes, cur_sighash, recovered_keys))
     }
 }
 
@@ -350,8 +724,8 @@ impl StacksMessageCodec for SinglesigSpendingCondition {
 
         let signature: MessageSignature = read_next(fd)?;
 
-        // sanity check -- must be compressed if we're using p2wpkh
-        if hash_mode == SinglesigHashMode::P2WPKH
+        // sanity check -- must be compressed if we're using p2wpkh or p2tr
+        if (hash_mode == SinglesigHashMode::P2WPKH || hash_mode == SinglesigHashMode::P2TR)
             && key_encoding != TransactionPublicKeyEncoding::Compressed
         {
             test_debug!("Incompatible hashing mode and key encoding");
@@ -390,7 +764,9 @@ impl SinglesigSpendingCondition {
 
     pub fn address_mainnet(&self) -> StacksAddress {
         let version = match self.hash_mode {
-            SinglesigHashMode::P2PKH => C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+            SinglesigHashMode::P2PKH | SinglesigHashMode::P2TR => {
+                C32_ADDRESS_VERSION_MAINNET_SINGLESIG
+            }
             SinglesigHashMode::P2WPKH => C32_ADDRESS_VERSION_MAINNET_MULTISIG,
         };
         StacksAddress {
@@ -401,7 +777,9 @@ impl SinglesigSpendingCondition {
 
     pub fn address_testnet(&self) -> StacksAddress {
         let version = match self.hash_mode {
-            SinglesigHashMode::P2PKH => C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            SinglesigHashMode::P2PKH | SinglesigHashMode::P2TR => {
+                C32_ADDRESS_VERSION_TESTNET_SINGLESIG
+            }
             SinglesigHashMode::P2WPKH => C32_ADDRESS_VERSION_TESTNET_MULTISIG,
         };
         StacksAddress {
@@ -419,7 +797,29 @@ impl SinglesigSpendingCondition {
         initial_sighash: &Txid,
         cond_code: &TransactionAuthFlags,
     ) -> Result<Txid, net_error> {
-        let (pubkey, next_sighash) = TransactionSpendingCondition::next_verification(
+        self.verify_and_recover(initial_sighash, cond_code)
+            .map(|(next_sighash, _)| next_sighash)
+    }
+
+    /// Authenticate a spending condition like `verify()`, and also return the public key
+    /// recovered from its signature.
+    pub fn verify_and_recover(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
+        self.verify_and_recover_with(&VerifyContext::default(), initial_sighash, cond_code)
+    }
+
+    /// Like `verify_and_recover()`, but recover the public key with the given context's backend
+    pub fn verify_and_recover_with(
+        &self,
+        ctx: &VerifyContext,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
+        let (pubkey, next_sighash) = TransactionSpendingCondition::next_verification_with(
+            ctx,
             initial_sighash,
             cond_code,
             self.tx_fee,
@@ -431,7 +831,7 @@ impl SinglesigSpendingCondition {
             0,
             &self.hash_mode.to_address_hash_mode(),
             1,
-            &vec![pubkey],
+            &vec![pubkey.clone()],
         ) {
             Some(a) => a.bytes,
             None => {
@@ -448,7 +848,7 @@ impl SinglesigSpendingCondition {
             )));
         }
 
-        Ok(next_sighash)
+        Ok((next_sighash, vec![pubkey]))
     }
 }
 
@@ -534,6 +934,24 @@ impl TransactionSpendingCondition {
         ))
     }
 
+    /// Make a taproot key-path spending condition for the given key, which must be compressed.
+    /// Only valid in Stacks 3.0 and later.
+    pub fn new_singlesig_p2tr(pubkey: StacksPublicKey) -> Option<TransactionSpendingCondition> {
+        let signer_addr =
+            StacksAddress::from_public_keys(0, &AddressHashMode::SerializeP2TR, 1, &vec![pubkey])?;
+
+        Some(TransactionSpendingCondition::Singlesig(
+            SinglesigSpendingCondition {
+                signer: signer_addr.bytes.clone(),
+                nonce: 0,
+                tx_fee: 0,
+                hash_mode: SinglesigHashMode::P2TR,
+                key_encoding: TransactionPublicKeyEncoding::Compressed,
+                signature: MessageSignature::empty(),
+            },
+        ))
+    }
+
     pub fn new_multisig_p2sh(
         num_sigs: u16,
         pubkeys: Vec<StacksPublicKey>,
@@ -580,6 +998,54 @@ impl TransactionSpendingCondition {
         ))
     }
 
+    /// Like `new_multisig_p2sh`, but the signatures can be appended in any order
+    pub fn new_multisig_order_independent_p2sh(
+        num_sigs: u16,
+        pubkeys: Vec<StacksPublicKey>,
+    ) -> Option<TransactionSpendingCondition> {
+        let signer_addr = StacksAddress::from_public_keys(
+            0,
+            &AddressHashMode::SerializeP2SH,
+            num_sigs as usize,
+            &pubkeys,
+        )?;
+
+        Some(TransactionSpendingCondition::Multisig(
+            MultisigSpendingCondition {
+                signer: signer_addr.bytes.clone(),
+                nonce: 0,
+                tx_fee: 0,
+                hash_mode: MultisigHashMode::P2SHNonSequential,
+                fields: vec![],
+                signatures_required: num_sigs,
+            },
+        ))
+    }
+
+    /// Like `new_multisig_p2wsh`, but the signatures can be appended in any order
+    pub fn new_multisig_order_independent_p2wsh(
+        num_sigs: u16,
+        pubkeys: Vec<StacksPublicKey>,
+    ) -> Option<TransactionSpendingCondition> {
+        let signer_addr = StacksAddress::from_public_keys(
+            0,
+            &AddressHashMode::SerializeP2WSH,
+            num_sigs as usize,
+            &pubkeys,
+        )?;
+
+        Some(TransactionSpendingCondition::Multisig(
+            MultisigSpendingCondition {
+                signer: signer_addr.bytes.clone(),
+                nonce: 0,
+                tx_fee: 0,
+                hash_mode: MultisigHashMode::P2WSHNonSequential,
+                fields: vec![],
+                signatures_required: num_sigs,
+            },
+        ))
+    }
+
     /// When committing to the fact that a transaction is sponsored, the origin doesn't know
     /// anything else.  Instead, it commits to this sentinel value as its sponsor.
     /// It is intractable to calculate a private key that could generate this.
@@ -603,17 +1069,7 @@ impl TransactionSpendingCondition {
                     0
                 }
             }
-            TransactionSpendingCondition::Multisig(ref data) => {
-                let mut num_sigs: u16 = 0;
-                for field in data.fields.iter() {
-                    if field.is_signature() {
-                        num_sigs = num_sigs
-                            .checked_add(1)
-                            .expect("Unreasonable amount of signatures"); // something is seriously wrong if this fails
-                    }
-                }
-                num_sigs
-            }
+            TransactionSpendingCondition::Multisig(ref data) => data.signatures_present(),
         }
     }
 
@@ -694,6 +1150,13 @@ impl TransactionSpendingCondition {
         }
     }
 
+    /// Deserialize a spending condition from exactly `buf`, and get its account address, given
+    /// the network flag
+    pub fn address_from_bytes(buf: &[u8], mainnet: bool) -> Result<StacksAddress, net_error> {
+        let condition = TransactionSpendingCondition::consensus_deserialize_exact(buf)?;
+        Ok(condition.get_address(mainnet))
+    }
+
     /// Clear fee rate, nonces, signatures, and public keys
     pub fn clear(&mut self) -> () {
         match *self {
@@ -806,6 +1269,27 @@ impl TransactionSpendingCondition {
         nonce: u64,
         key_encoding: &TransactionPublicKeyEncoding,
         sig: &MessageSignature,
+    ) -> Result<(StacksPublicKey, Txid), net_error> {
+        TransactionSpendingCondition::next_verification_with(
+            &VerifyContext::default(),
+            cur_sighash,
+            cond_code,
+            tx_fee,
+            nonce,
+            key_encoding,
+            sig,
+        )
+    }
+
+    /// Like `next_verification()`, but recover the public key with the given context's backend
+    pub fn next_verification_with(
+        ctx: &VerifyContext,
+        cur_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+        tx_fee: u64,
+        nonce: u64,
+        key_encoding: &TransactionPublicKeyEncoding,
+        sig: &MessageSignature,
     ) -> Result<(StacksPublicKey, Txid), net_error> {
         let sighash_presign = TransactionSpendingCondition::make_sighash_presign(
             cur_sighash,
@@ -815,7 +1299,9 @@ impl TransactionSpendingCondition {
         );
 
         // verify the current signature
-        let mut pubk = StacksPublicKey::recover_to_pubkey(sighash_presign.as_bytes(), sig)
+        let mut pubk = ctx
+            .recovery
+            .recover_to_pubkey(sighash_presign.as_bytes(), sig)
             .map_err(|ve| net_error::VerifyingError(ve.to_string()))?;
 
         match key_encoding {
@@ -829,23 +1315,134 @@ impl TransactionSpendingCondition {
         Ok((pubk, next_sighash))
     }
 
+    /// Calculate the presign sighash that the signer at `signer_index` must sign, by replaying
+    /// the rolling hash over every signature that comes before it.  For a multisig condition,
+    /// `signer_index` is a position in its auth fields, and may be equal to the number of fields
+    /// in order to get the sighash for the next signature to be appended.  A singlesig condition
+    /// only has the signer at index 0.
+    /// Lets offline signers (e.g. hardware wallets) sign without reconstructing the transaction.
+    pub fn sighash_for_signer(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+        signer_index: usize,
+    ) -> Result<Txid, net_error> {
+        let cur_sighash = match *self {
+            TransactionSpendingCondition::Singlesig(_) => {
+                if signer_index != 0 {
+                    return Err(net_error::SigningError(format!(
+                        "Signer index {} out of bounds for a singlesig spending condition",
+                        signer_index
+                    )));
+                }
+                initial_sighash.clone()
+            }
+            TransactionSpendingCondition::Multisig(ref data) => {
+                if signer_index > data.fields.len() {
+                    return Err(net_error::SigningError(format!(
+                        "Signer index {} out of bounds for a multisig spending condition with {} auth fields",
+                        signer_index,
+                        data.fields.len()
+                    )));
+                }
+                let mut cur_sighash = initial_sighash.clone();
+                if !data.hash_mode.is_order_independent() {
+                    for field in data.fields[..signer_index].iter() {
+                        if let TransactionAuthField::Signature(ref key_encoding, ref sig) = field {
+                            let (_, next_sighash) =
+                                TransactionSpendingCondition::next_verification(
+                                    &cur_sighash,
+                                    cond_code,
+                                    data.tx_fee,
+                                    data.nonce,
+                                    key_encoding,
+                                    sig,
+                                )?;
+                            cur_sighash = next_sighash;
+                        }
+                    }
+                }
+                cur_sighash
+            }
+        };
+        Ok(TransactionSpendingCondition::make_sighash_presign(
+            &cur_sighash,
+            cond_code,
+            self.tx_fee(),
+            self.nonce(),
+        ))
+    }
+
     /// Verify all signatures
     pub fn verify(
         &self,
         initial_sighash: &Txid,
         cond_code: &TransactionAuthFlags,
     ) -> Result<Txid, net_error> {
+        self.verify_and_recover(initial_sighash, cond_code)
+            .map(|(next_sighash, _)| next_sighash)
+    }
+
+    /// Verify all signatures, and return the public keys recovered from them in field order
+    /// along with the next sighash
+    pub fn verify_and_recover(
+        &self,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
+        self.verify_and_recover_with(&VerifyContext::default(), initial_sighash, cond_code)
+    }
+
+    /// Verify all signatures, recovering public keys with the given context's backend
+    pub fn verify_with(
+        &self,
+        ctx: &VerifyContext,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<Txid, net_error> {
+        self.verify_and_recover_with(ctx, initial_sighash, cond_code)
+            .map(|(next_sighash, _)| next_sighash)
+    }
+
+    /// Like `verify_and_recover()`, but recover public keys with the given context's backend
+    pub fn verify_and_recover_with(
+        &self,
+        ctx: &VerifyContext,
+        initial_sighash: &Txid,
+        cond_code: &TransactionAuthFlags,
+    ) -> Result<(Txid, Vec<StacksPublicKey>), net_error> {
         match *self {
             TransactionSpendingCondition::Singlesig(ref data) => {
-                data.verify(initial_sighash, cond_code)
+                data.verify_and_recover_with(ctx, initial_sighash, cond_code)
             }
             TransactionSpendingCondition::Multisig(ref data) => {
-                data.verify(initial_sighash, cond_code)
+                data.verify_and_recover_with(ctx, initial_sighash, cond_code)
             }
         }
     }
 }
 
+/// Calculate how many more bytes the `multisig` spending condition takes up than the `singlesig`
+/// one once serialized. Negative if it is smaller.
+pub fn auth_size_delta(
+    singlesig: &TransactionSpendingCondition,
+    multisig: &TransactionSpendingCondition,
+) -> isize {
+    let singlesig_size = singlesig.serialize_to_vec().len() as isize;
+    let multisig_size = multisig.serialize_to_vec().len() as isize;
+    multisig_size - singlesig_size
+}
+
+/// Verify a batch of auths, each against its own initial sighash, across all available cores.
+/// Like `TransactionAuth::verify_auths()`, every auth is checked independently, and the i-th
+/// result corresponds to the i-th auth.
+pub fn verify_auths_parallel(auths: &[(TransactionAuth, Txid)]) -> Vec<Result<bool, net_error>> {
+    auths
+        .par_iter()
+        .map(|(auth, initial_sighash)| auth.verify(initial_sighash).map(|_| true))
+        .collect()
+}
+
 impl StacksMessageCodec for TransactionAuth {
     fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
         match *self {
@@ -886,6 +1483,127 @@ impl StacksMessageCodec for TransactionAuth {
     }
 }
 
+/// Transaction authorization requirements a node may enforce before relaying a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayPolicy {
+    /// Minimum fee the paying account must offer
+    pub min_fee: u64,
+    /// Maximum fee a sponsor may offer, if capped
+    pub max_sponsor_fee: Option<u64>,
+    /// Whether the origin and sponsor must be different accounts, and multisig public keys unique
+    pub require_distinct_signers: bool,
+}
+
+impl TransactionSpendingCondition {
+    /// Get the hash of the signer's public key(s)
+    pub fn signer(&self) -> &Hash160 {
+        match *self {
+            TransactionSpendingCondition::Singlesig(ref data) => &data.signer,
+            TransactionSpendingCondition::Multisig(ref data) => &data.signer,
+        }
+    }
+
+    /// Is this a taproot spending condition?
+    pub fn is_p2tr(&self) -> bool {
+        match self {
+            TransactionSpendingCondition::Singlesig(data) => {
+                data.hash_mode == SinglesigHashMode::P2TR
+            }
+            TransactionSpendingCondition::Multisig(_) => false,
+        }
+    }
+
+    /// Get the wire-format hash mode byte of this spending condition
+    pub fn hash_mode_byte(&self) -> u8 {
+        match self {
+            TransactionSpendingCondition::Singlesig(data) => data.hash_mode.clone() as u8,
+            TransactionSpendingCondition::Multisig(data) => data.hash_mode.clone() as u8,
+        }
+    }
+
+    /// Can this spending condition's signatures be collected in any order?
+    pub fn is_order_independent(&self) -> bool {
+        match self {
+            TransactionSpendingCondition::Singlesig(_) => false,
+            TransactionSpendingCondition::Multisig(data) => data.hash_mode.is_order_independent(),
+        }
+    }
+
+    /// Check a multisig spending condition against the constraints enforced from Stacks 3.0 on,
+    /// given the public keys recovered from its signatures. Singlesig conditions have none.
+    fn validate_multisig_constraints(
+        &self,
+        signing_keys: &[StacksPublicKey],
+    ) -> Result<(), net_error> {
+        let TransactionSpendingCondition::Multisig(ref data) = self else {
+            return Ok(());
+        };
+        data.validate_consistency()?;
+        MultisigSpendingCondition::validate_distinct_signing_keys(signing_keys)
+    }
+
+    /// Check that no public key appears more than once in a multisig spending condition
+    pub fn validate_distinct_public_keys(&self) -> Result<(), Error> {
+        let TransactionSpendingCondition::Multisig(ref data) = self else {
+            return Ok(());
+        };
+        let mut seen = HashSet::new();
+        for field in data.fields.iter() {
+            if let TransactionAuthField::PublicKey(ref pubkey) = field {
+                if !seen.insert(pubkey.to_bytes_compressed()) {
+                    return Err(Error::InvalidStacksTransaction(
+                        format!(
+                            "Duplicate public key {} in multisig spending condition",
+                            pubkey.to_hex()
+                        ),
+                        false,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a spending condition out of `buf`, starting at index `start`.  The condition must
+    /// end at or before index `max_size`.
+    /// Returns the spending condition and the index just past its last byte.
+    pub fn parse_with_end(
+        buf: &[u8],
+        start: u32,
+        max_size: u32,
+    ) -> Result<(TransactionSpendingCondition, u32), net_error> {
+        let limit = cmp::min(max_size as usize, buf.len());
+        let start_idx = start as usize;
+        if start_idx > limit {
+            return Err(net_error::OverflowError(format!(
+                "Spending condition start index {} exceeds buffer bound {}",
+                start, limit
+            )));
+        }
+        let mut remaining = &buf[start_idx..limit];
+        let cond = TransactionSpendingCondition::consensus_deserialize(&mut remaining)?;
+        // the consumed bytes are bounded by max_size - start, so this fits in a u32
+        let consumed = (limit - start_idx - remaining.len()) as u32;
+        Ok((cond, start + consumed))
+    }
+
+    /// Deserialize a spending condition that spans all of `buf`.
+    /// Unlike `consensus_deserialize()`, this fails if any bytes are left unconsumed.
+    pub fn consensus_deserialize_exact(
+        buf: &[u8],
+    ) -> Result<TransactionSpendingCondition, net_error> {
+        let mut remaining = buf;
+        let cond = TransactionSpendingCondition::consensus_deserialize(&mut remaining)?;
+        if !remaining.is_empty() {
+            return Err(net_error::DeserializeError(format!(
+                "{} trailing bytes after spending condition",
+                remaining.len()
+            )));
+        }
+        Ok(cond)
+    }
+}
+
 impl TransactionAuth {
     pub fn from_p2pkh(privk: &StacksPrivateKey) -> Option<TransactionAuth> {
         match TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
@@ -917,6 +1635,11 @@ impl TransactionAuth {
         }
     }
 
+    pub fn from_p2tr(privk: &StacksPrivateKey) -> Option<TransactionAuth> {
+        TransactionSpendingCondition::new_singlesig_p2tr(StacksPublicKey::from_private(privk))
+            .map(TransactionAuth::Standard)
+    }
+
     pub fn from_p2wsh(privks: &[StacksPrivateKey], num_sigs: u16) -> Option<TransactionAuth> {
         let mut pubks = vec![];
         for privk in privks.iter() {
@@ -1005,6 +1728,41 @@ impl TransactionAuth {
         }
     }
 
+    /// Does the origin or sponsor use a taproot spending condition?
+    pub fn uses_p2tr(&self) -> bool {
+        self.origin().is_p2tr() || self.sponsor().map_or(false, |s| s.is_p2tr())
+    }
+
+    /// Does the origin or sponsor use an order-independent multisig spending condition?
+    pub fn uses_order_independent_multisig(&self) -> bool {
+        self.origin().is_order_independent()
+            || self.sponsor().map_or(false, |s| s.is_order_independent())
+    }
+
+    /// Check the multisig spending conditions against the constraints enforced from Stacks 3.0
+    /// on: each must obey its hash mode's constraints (see
+    /// `MultisigSpendingCondition::validate_consistency()`), and no two of its signatures may come
+    /// from the same public key. The signing keys are recovered against `initial_sighash`, so
+    /// this costs as much as `verify()` if there is a multisig spending condition.
+    pub fn validate_multisig_constraints(&self, initial_sighash: &Txid) -> Result<(), net_error> {
+        let is_multisig = |cond: &TransactionSpendingCondition| {
+            matches!(cond, TransactionSpendingCondition::Multisig(_))
+        };
+        if !is_multisig(self.origin()) && !self.sponsor().map_or(false, is_multisig) {
+            return Ok(());
+        }
+        let (origin_sighash, origin_keys) = self
+            .origin()
+            .verify_and_recover(initial_sighash, &TransactionAuthFlags::AuthStandard)?;
+        self.origin().validate_multisig_constraints(&origin_keys)?;
+        if let Some(sponsor) = self.sponsor() {
+            let (_, sponsor_keys) = sponsor
+                .verify_and_recover(&origin_sighash, &TransactionAuthFlags::AuthSponsored)?;
+            sponsor.validate_multisig_constraints(&sponsor_keys)?;
+        }
+        Ok(())
+    }
+
     pub fn sponsor(&self) -> Option<&TransactionSpendingCondition> {
         match *self {
             TransactionAuth::Standard(_) => None,
@@ -1012,6 +1770,15 @@ impl TransactionAuth {
         }
     }
 
+    /// Get the hash mode bytes of the origin and, if sponsored, the sponsor (in that order)
+    pub fn hash_mode(&self) -> Vec<u8> {
+        let mut hash_modes = vec![self.origin().hash_mode_byte()];
+        if let Some(sponsor) = self.sponsor() {
+            hash_modes.push(sponsor.hash_mode_byte());
+        }
+        hash_modes
+    }
+
     pub fn get_sponsor_nonce(&self) -> Option<u64> {
         match self.sponsor() {
             None => None,
@@ -1064,6 +1831,119 @@ impl TransactionAuth {
         }
     }
 
+    /// Verify a batch of auths, each against its own initial sighash.  Every auth is checked
+    /// independently, so one bad auth does not stop the rest from being verified.  The i-th
+    /// result is `Ok(true)` if the i-th auth is valid, or the verification error otherwise.
+    pub fn verify_auths(auths: &[(&TransactionAuth, Txid)]) -> Vec<Result<bool, net_error>> {
+        auths
+            .iter()
+            .map(|(auth, initial_sighash)| auth.verify(initial_sighash).map(|_| true))
+            .collect()
+    }
+
+    /// Verify only the origin spending condition, and return the origin's address and fee.
+    /// The sponsor's spending condition (if any) is NOT verified, which saves the EC operations
+    /// needed for a cheap admission check.  This must NOT be used to decide whether or not a
+    /// transaction is valid -- use `verify()` for that.
+    pub fn verify_origin_only(
+        &self,
+        initial_sighash: &Txid,
+        mainnet: bool,
+    ) -> Result<(StacksAddress, u64), net_error> {
+        self.verify_origin(initial_sighash)?;
+        let origin = self.origin();
+        Ok((origin.get_address(mainnet), origin.tx_fee()))
+    }
+
+    /// Number of bytes this auth takes up once serialized
+    pub fn serialized_size(&self) -> usize {
+        self.serialize_to_vec().len()
+    }
+
+    /// Check that this auth serializes to no more than `max_size` bytes.  Callers can use this to
+    /// reject oversized (e.g. giant multisig) auths before paying for `verify()`.
+    pub fn validate_size(&self, max_size: usize) -> Result<(), net_error> {
+        let size = self.serialized_size();
+        if size > max_size {
+            return Err(net_error::VerifyingError(format!(
+                "Transaction auth is {} bytes, which exceeds the maximum of {}",
+                size, max_size
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that every spending condition carries at least one signature
+    pub fn validate_nonempty_signatures(&self) -> Result<(), Error> {
+        let unsigned = match *self {
+            TransactionAuth::Standard(ref origin) => origin.num_signatures() == 0,
+            TransactionAuth::Sponsored(ref origin, ref sponsor) => {
+                origin.num_signatures() == 0 || sponsor.num_signatures() == 0
+            }
+        };
+        if unsigned {
+            return Err(Error::InvalidStacksTransaction(
+                "Transaction auth is missing signatures".into(),
+                false,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that the paying account offers at least `min_fee`
+    pub fn validate_min_fee(&self, min_fee: u64) -> Result<(), Error> {
+        if self.get_tx_fee() < min_fee {
+            return Err(Error::InvalidFee);
+        }
+        Ok(())
+    }
+
+    /// Check that a sponsor, if there is one, offers no more than `max_sponsor_fee`
+    pub fn validate_sponsor_fee(&self, max_sponsor_fee: u64) -> Result<(), Error> {
+        match self.sponsor() {
+            Some(sponsor) if sponsor.get_tx_fee() > max_sponsor_fee => {
+                Err(Error::InvalidStacksTransaction(
+                    format!(
+                        "Sponsor fee {} exceeds maximum of {}",
+                        sponsor.get_tx_fee(),
+                        max_sponsor_fee
+                    ),
+                    false,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the origin and sponsor are different accounts, and that no multisig spending
+    /// condition lists the same public key twice
+    pub fn validate_distinct_signers(&self) -> Result<(), Error> {
+        self.origin().validate_distinct_public_keys()?;
+        if let Some(sponsor) = self.sponsor() {
+            sponsor.validate_distinct_public_keys()?;
+            if sponsor.signer() == self.origin().signer() {
+                return Err(Error::InvalidStacksTransaction(
+                    "Transaction origin and sponsor are the same signer".into(),
+                    false,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check this auth against every requirement of the given relay policy
+    pub fn validate_relay_policy(&self, policy: &RelayPolicy) -> Result<(), Error> {
+        self.validate_nonempty_signatures()?;
+        self.validate_min_fee(policy.min_fee)?;
+        if let Some(max_sponsor_fee) = policy.max_sponsor_fee {
+            self.validate_sponsor_fee(max_sponsor_fee)?;
+        }
+        if policy.require_distinct_signers {
+            self.validate_distinct_signers()?;
+        }
+        Ok(())
+    }
+
     /// Clear out all transaction auth fields, nonces, and fee rates from the spending condition(s).
     pub fn clear(&mut self) -> () {
         match *self {
@@ -1979,12 +2859,317 @@ mod test {
         let spending_conditions = vec![spending_condition_p2wpkh_compressed];
         let spending_conditions_bytes = vec![spending_condition_p2wpkh_compressed_bytes];
 
-        for i in 0..spending_conditions.len() {
-            check_codec_and_corruption::<SinglesigSpendingCondition>(
-                &spending_conditions[i],
-                &spending_conditions_bytes[i],
-            );
-        }
+        for i in 0..spending_conditions.len() {
+            check_codec_and_corruption::<SinglesigSpendingCondition>(
+                &spending_conditions[i],
+                &spending_conditions_bytes[i],
+            );
+        }
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_p2tr() {
+        let spending_condition_p2tr_compressed = SinglesigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: SinglesigHashMode::P2TR,
+            key_encoding: TransactionPublicKeyEncoding::Compressed,
+            nonce: 345,
+            tx_fee: 567,
+            signature: MessageSignature::from_raw(&vec![0xfe; 65]),
+        };
+
+        let spending_condition_p2tr_compressed_bytes = vec![
+            // hash mode
+            SinglesigHashMode::P2TR as u8,
+            // signer
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            0x11,
+            // nonce
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x01,
+            0x59,
+            // fee rate
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x02,
+            0x37,
+            // key encoding
+            TransactionPublicKeyEncoding::Compressed as u8,
+            // signature
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+            0xfe,
+        ];
+
+        let spending_conditions = vec![spending_condition_p2tr_compressed];
+        let spending_conditions_bytes = vec![spending_condition_p2tr_compressed_bytes];
+
+        for i in 0..spending_conditions.len() {
+            check_codec_and_corruption::<SinglesigSpendingCondition>(
+                &spending_conditions[i],
+                &spending_conditions_bytes[i],
+            );
+        }
+
+        // taproot keys must be compressed
+        let mut bad_p2tr_uncompressed_bytes = spending_conditions_bytes[0].clone();
+        bad_p2tr_uncompressed_bytes[37] = TransactionPublicKeyEncoding::Uncompressed as u8;
+        assert!(SinglesigSpendingCondition::consensus_deserialize(
+            &mut &bad_p2tr_uncompressed_bytes[..]
+        )
+        .is_err());
+
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let pubk = StacksPublicKey::from_private(&privk);
+        let cond = TransactionSpendingCondition::new_singlesig_p2tr(pubk.clone()).unwrap();
+        assert!(cond.is_p2tr());
+        assert_eq!(
+            cond.signer(),
+            &Hash160::from_data(&pubk.to_bytes_compressed()[1..])
+        );
+
+        // taproot addresses use the singlesig versions, like P2PKH
+        let hash_mode = AddressHashMode::SerializeP2TR;
+        assert_eq!(
+            StacksAddress::from_public_keys(
+                hash_mode.to_version_mainnet(),
+                &hash_mode,
+                1,
+                &vec![pubk.clone()]
+            )
+            .unwrap(),
+            cond.address_mainnet()
+        );
+        assert_eq!(
+            StacksAddress::from_public_keys(
+                hash_mode.to_version_testnet(),
+                &hash_mode,
+                1,
+                &vec![pubk.clone()]
+            )
+            .unwrap(),
+            cond.address_testnet()
+        );
+
+        // uncompressed keys cannot make taproot spending conditions
+        let mut uncompressed_pubk = pubk;
+        uncompressed_pubk.set_compressed(false);
+        assert!(TransactionSpendingCondition::new_singlesig_p2tr(uncompressed_pubk).is_none());
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_parse_with_end() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let pubk = StacksPublicKey::from_private(&privk);
+        let multisig = |hash_mode| {
+            TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode,
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xff; 65]),
+                    ),
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xfe; 65]),
+                    ),
+                    TransactionAuthField::PublicKey(pubk.clone()),
+                ],
+                signatures_required: 2,
+            })
+        };
+
+        let conditions = vec![
+            TransactionSpendingCondition::new_singlesig_p2pkh(pubk.clone()).unwrap(),
+            TransactionSpendingCondition::new_singlesig_p2wpkh(pubk.clone()).unwrap(),
+            TransactionSpendingCondition::new_singlesig_p2tr(pubk.clone()).unwrap(),
+            multisig(MultisigHashMode::P2SH),
+            multisig(MultisigHashMode::P2WSH),
+        ];
+
+        for cond in conditions {
+            let cond_bytes = cond.serialize_to_vec();
+            let start = 7u32;
+            // surround the condition with unrelated bytes
+            let mut buf = vec![0xaa; start as usize];
+            buf.extend_from_slice(&cond_bytes);
+            buf.extend_from_slice(&[0xbb; 11]);
+
+            let (parsed, end) =
+                TransactionSpendingCondition::parse_with_end(&buf, start, buf.len() as u32)
+                    .unwrap();
+            assert_eq!(parsed, cond);
+            assert_eq!(end, start + cond_bytes.len() as u32);
+
+            // the condition can end exactly at the bound, but not past it
+            TransactionSpendingCondition::parse_with_end(&buf, start, end).unwrap();
+            assert!(TransactionSpendingCondition::parse_with_end(&buf, start, end - 1).is_err());
+        }
+
+        assert!(TransactionSpendingCondition::parse_with_end(&[0u8; 4], 5, 100).is_err());
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_too_many_auth_fields() {
+        for num_fields in [MAX_AUTH_FIELDS + 1, u32::MAX] {
+            let mut bytes = vec![MultisigHashMode::P2SH as u8];
+            bytes.extend_from_slice(&[0x11; 20]);
+            bytes.extend_from_slice(&123u64.to_be_bytes());
+            bytes.extend_from_slice(&456u64.to_be_bytes());
+            // the declared number of fields, with none following it
+            bytes.extend_from_slice(&num_fields.to_be_bytes());
+
+            // the field count is rejected before any field is read
+            match MultisigSpendingCondition::consensus_deserialize(&mut &bytes[..]) {
+                Err(codec_error::DeserializeError(_)) => {}
+                res => panic!("Expected a deserialize error, got {:?}", res),
+            }
+            match TransactionSpendingCondition::parse_with_end(&bytes, 0, bytes.len() as u32) {
+                Err(net_error::DeserializeError(_)) => {}
+                res => panic!("Expected a deserialize error, got {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_json() {
+        let pubk =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let p2sh = TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: MultisigHashMode::P2SH,
+            nonce: 123,
+            tx_fee: 456,
+            fields: vec![
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xff; 65]),
+                ),
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xfe; 65]),
+                ),
+                TransactionAuthField::PublicKey(pubk.clone()),
+            ],
+            signatures_required: 2,
+        });
+
+        let json = serde_json::to_value(&p2sh).unwrap();
+        let cond_json = &json["Multisig"];
+        assert_eq!(cond_json["hash_mode"], "P2SH");
+        assert_eq!(cond_json["signer"], "11".repeat(20));
+        assert_eq!(cond_json["fields"][0]["Signature"][1], "ff".repeat(65));
+        assert_eq!(cond_json["fields"][2]["PublicKey"], pubk.to_hex());
+
+        let decoded: TransactionSpendingCondition = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(decoded, p2sh);
+        // JSON is only a view of the condition; its consensus bytes are unchanged
+        assert_eq!(decoded.serialize_to_vec(), p2sh.serialize_to_vec());
+
+        // public keys serialized as structs still deserialize
+        let mut legacy_json = json;
+        legacy_json["Multisig"]["fields"][2]["PublicKey"] = serde_json::to_value(&pubk).unwrap();
+        let decoded: TransactionSpendingCondition = serde_json::from_value(legacy_json).unwrap();
+        assert_eq!(decoded, p2sh);
     }
 
     #[test]
@@ -3546,6 +4731,12 @@ mod test {
             &mut &bad_hash_mode_singlesig_bytes_parseable[..]
         )
         .is_ok());
+
+        // ...but not if the whole buffer must be consumed
+        assert!(TransactionSpendingCondition::consensus_deserialize_exact(
+            &bad_hash_mode_singlesig_bytes_parseable
+        )
+        .is_err());
     }
 
     #[test]
@@ -3633,4 +4824,680 @@ mod test {
             assert_eq!(next_pubkey, StacksPublicKey::from_private(&keys[i]));
         }
     }
+
+    #[test]
+    fn tx_stacks_auth_relay_policy() {
+        let origin = TransactionSpendingCondition::Singlesig(SinglesigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: SinglesigHashMode::P2PKH,
+            key_encoding: TransactionPublicKeyEncoding::Compressed,
+            nonce: 123,
+            tx_fee: 0,
+            signature: MessageSignature::from_raw(&vec![0xff; 65]),
+        });
+        let pubkey =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let sponsor = TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+            signer: Hash160([0x22; 20]),
+            hash_mode: MultisigHashMode::P2SH,
+            nonce: 456,
+            tx_fee: 1000,
+            fields: vec![
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xff; 65]),
+                ),
+                TransactionAuthField::PublicKey(pubkey.clone()),
+            ],
+            signatures_required: 1,
+        });
+        let policy = RelayPolicy {
+            min_fee: 1000,
+            max_sponsor_fee: Some(1000),
+            require_distinct_signers: true,
+        };
+
+        // passing auth
+        let auth = TransactionAuth::Sponsored(origin.clone(), sponsor.clone());
+        auth.validate_relay_policy(&policy).unwrap();
+
+        // min fee
+        let mut cheap_origin = origin.clone();
+        cheap_origin.set_tx_fee(999);
+        let auth = TransactionAuth::Standard(cheap_origin);
+        assert!(matches!(
+            auth.validate_relay_policy(&policy),
+            Err(Error::InvalidFee)
+        ));
+
+        // sponsor fee cap
+        let mut expensive_sponsor = sponsor.clone();
+        expensive_sponsor.set_tx_fee(1001);
+        let auth = TransactionAuth::Sponsored(origin.clone(), expensive_sponsor);
+        assert!(matches!(
+            auth.validate_relay_policy(&policy),
+            Err(Error::InvalidStacksTransaction(..))
+        ));
+        auth.validate_relay_policy(&RelayPolicy {
+            max_sponsor_fee: None,
+            ..policy.clone()
+        })
+        .unwrap();
+
+        // distinct signers: sponsor is the origin
+        let mut same_sponsor = origin.clone();
+        same_sponsor.set_tx_fee(1000);
+        let auth = TransactionAuth::Sponsored(origin.clone(), same_sponsor);
+        assert!(matches!(
+            auth.validate_relay_policy(&policy),
+            Err(Error::InvalidStacksTransaction(..))
+        ));
+        auth.validate_relay_policy(&RelayPolicy {
+            require_distinct_signers: false,
+            ..policy.clone()
+        })
+        .unwrap();
+
+        // distinct signers: repeated multisig public key
+        let mut repeated_key_sponsor = sponsor.clone();
+        if let TransactionSpendingCondition::Multisig(ref mut data) = repeated_key_sponsor {
+            data.fields.push(TransactionAuthField::PublicKey(pubkey));
+        }
+        let auth = TransactionAuth::Sponsored(origin.clone(), repeated_key_sponsor);
+        assert!(matches!(
+            auth.validate_relay_policy(&policy),
+            Err(Error::InvalidStacksTransaction(..))
+        ));
+
+        // non-empty signatures
+        let mut unsigned_origin = origin;
+        unsigned_origin.clear();
+        let auth = TransactionAuth::Sponsored(unsigned_origin, sponsor);
+        assert!(matches!(
+            auth.validate_relay_policy(&policy),
+            Err(Error::InvalidStacksTransaction(..))
+        ));
+    }
+
+    #[test]
+    fn tx_stacks_auth_validate_size() {
+        let singlesig = TransactionAuth::Standard(TransactionSpendingCondition::Singlesig(
+            SinglesigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode: SinglesigHashMode::P2PKH,
+                key_encoding: TransactionPublicKeyEncoding::Compressed,
+                nonce: 123,
+                tx_fee: 456,
+                signature: MessageSignature::from_raw(&vec![0xff; 65]),
+            },
+        ));
+        // auth flag (1) + singlesig spending condition (103)
+        assert_eq!(singlesig.serialized_size(), 104);
+        singlesig.validate_size(104).unwrap();
+        singlesig.validate_size(1024).unwrap();
+        assert!(singlesig.validate_size(103).is_err());
+
+        let pubkey =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let giant_multisig = TransactionAuth::Standard(TransactionSpendingCondition::Multisig(
+            MultisigSpendingCondition {
+                signer: Hash160([0x22; 20]),
+                hash_mode: MultisigHashMode::P2SH,
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![TransactionAuthField::PublicKey(pubkey); 500],
+                signatures_required: 1,
+            },
+        ));
+        assert!(giant_multisig.serialized_size() > 1024);
+        assert!(matches!(
+            giant_multisig.validate_size(1024),
+            Err(net_error::VerifyingError(..))
+        ));
+    }
+
+    #[test]
+    fn tx_stacks_multisig_validate_consistency() {
+        let compressed_pubkey =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let uncompressed_pubkey = PubKey::from_hex("04ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c771f112f919b00a6c6c5f51f7c63e1762fe9fac9b66ec75a053db7f51f4a52712b").unwrap();
+        let compressed_sig = TransactionAuthField::Signature(
+            TransactionPublicKeyEncoding::Compressed,
+            MessageSignature::from_raw(&vec![0xff; 65]),
+        );
+
+        let valid = MultisigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: MultisigHashMode::P2WSH,
+            nonce: 456,
+            tx_fee: 567,
+            fields: vec![
+                compressed_sig.clone(),
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xfe; 65]),
+                ),
+                TransactionAuthField::PublicKey(compressed_pubkey.clone()),
+            ],
+            signatures_required: 2,
+        };
+        valid.validate_consistency().unwrap();
+
+        let mut bytes = vec![];
+        valid.consensus_serialize(&mut bytes).unwrap();
+        assert_eq!(
+            MultisigSpendingCondition::consensus_deserialize(&mut &bytes[..]).unwrap(),
+            valid
+        );
+
+        // more signatures required than there are keys
+        let mut over_threshold = valid.clone();
+        over_threshold.signatures_required = 4;
+        match over_threshold.validate_consistency() {
+            Err(net_error::VerifyingError(msg)) => assert_eq!(
+                &msg,
+                "signatures required (4) must be between 1 and the number of distinct keys (3)"
+            ),
+            res => panic!("Expected an over-threshold error, got {:?}", res),
+        }
+        assert!(TransactionSpendingCondition::Multisig(over_threshold)
+            .validate_multisig_constraints(&[])
+            .is_err());
+
+        // repeated public keys are only counted once
+        let repeated_keys = MultisigSpendingCondition {
+            fields: vec![
+                compressed_sig.clone(),
+                TransactionAuthField::PublicKey(compressed_pubkey.clone()),
+                TransactionAuthField::PublicKey(compressed_pubkey.clone()),
+            ],
+            signatures_required: 3,
+            ..valid.clone()
+        };
+        assert!(repeated_keys.validate_consistency().is_err());
+
+        // no signatures required. The codec leaves this constraint to the epoch-gated checks.
+        let zero_required = MultisigSpendingCondition {
+            fields: vec![TransactionAuthField::PublicKey(compressed_pubkey.clone())],
+            signatures_required: 0,
+            ..valid.clone()
+        };
+        assert!(zero_required.validate_consistency().is_err());
+        let mut bytes = vec![];
+        zero_required.consensus_serialize(&mut bytes).unwrap();
+        assert_eq!(
+            MultisigSpendingCondition::consensus_deserialize(&mut &bytes[..]).unwrap(),
+            zero_required
+        );
+
+        // uncompressed public key
+        let uncompressed_key = MultisigSpendingCondition {
+            fields: vec![
+                compressed_sig.clone(),
+                TransactionAuthField::PublicKey(uncompressed_pubkey),
+            ],
+            signatures_required: 1,
+            ..valid.clone()
+        };
+        assert!(uncompressed_key.validate_consistency().is_err());
+        let mut bytes = vec![];
+        uncompressed_key.consensus_serialize(&mut bytes).unwrap();
+        assert!(MultisigSpendingCondition::consensus_deserialize(&mut &bytes[..]).is_err());
+
+        // uncompressed signature
+        let uncompressed_sig = MultisigSpendingCondition {
+            fields: vec![
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Uncompressed,
+                    MessageSignature::from_raw(&vec![0xff; 65]),
+                ),
+                TransactionAuthField::PublicKey(compressed_pubkey.clone()),
+            ],
+            signatures_required: 1,
+            ..valid.clone()
+        };
+        assert!(uncompressed_sig.validate_consistency().is_err());
+
+        // the same conditions are fine for P2SH
+        let p2sh = MultisigSpendingCondition {
+            hash_mode: MultisigHashMode::P2SH,
+            ..uncompressed_key
+        };
+        p2sh.validate_consistency().unwrap();
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_order_independent_multisig() {
+        let compressed_pubkey =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let uncompressed_pubkey = PubKey::from_hex("04ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c771f112f919b00a6c6c5f51f7c63e1762fe9fac9b66ec75a053db7f51f4a52712b").unwrap();
+
+        for (hash_mode, hash_mode_byte) in [
+            (MultisigHashMode::P2SHNonSequential, 0x05),
+            (MultisigHashMode::P2WSHNonSequential, 0x07),
+        ] {
+            assert!(hash_mode.is_order_independent());
+            let condition = TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode: hash_mode.clone(),
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xff; 65]),
+                    ),
+                    TransactionAuthField::PublicKey(compressed_pubkey.clone()),
+                ],
+                signatures_required: 1,
+            });
+            let bytes = condition.serialize_to_vec();
+            assert_eq!(bytes[0], hash_mode_byte);
+            assert_eq!(
+                TransactionSpendingCondition::consensus_deserialize(&mut &bytes[..]).unwrap(),
+                condition
+            );
+
+            // uncompressed keys are only allowed in the P2SH flavor
+            let uncompressed = MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode: hash_mode.clone(),
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xff; 65]),
+                    ),
+                    TransactionAuthField::PublicKey(uncompressed_pubkey.clone()),
+                ],
+                signatures_required: 1,
+            };
+            assert_eq!(
+                uncompressed.validate_consistency().is_err(),
+                hash_mode == MultisigHashMode::P2WSHNonSequential
+            );
+        }
+        assert!(!MultisigHashMode::P2SH.is_order_independent());
+        assert!(!MultisigHashMode::P2WSH.is_order_independent());
+    }
+
+    #[test]
+    fn tx_stacks_auth_size_delta() {
+        let p2pkh = TransactionSpendingCondition::Singlesig(SinglesigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: SinglesigHashMode::P2PKH,
+            key_encoding: TransactionPublicKeyEncoding::Compressed,
+            nonce: 123,
+            tx_fee: 456,
+            signature: MessageSignature::from_raw(&vec![0xff; 65]),
+        });
+        // 2-of-3, signed
+        let p2sh = TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+            signer: Hash160([0x11; 20]),
+            hash_mode: MultisigHashMode::P2SH,
+            nonce: 123,
+            tx_fee: 456,
+            fields: vec![
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xff; 65]),
+                ),
+                TransactionAuthField::Signature(
+                    TransactionPublicKeyEncoding::Compressed,
+                    MessageSignature::from_raw(&vec![0xfe; 65]),
+                ),
+                TransactionAuthField::PublicKey(
+                    PubKey::from_hex(
+                        "03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77",
+                    )
+                    .unwrap(),
+                ),
+            ],
+            signatures_required: 2,
+        });
+
+        // singlesig: hash mode (1) + signer (20) + nonce (8) + fee (8) + key encoding (1) +
+        //   signature (65) = 103 bytes
+        // multisig: hash mode (1) + signer (20) + nonce (8) + fee (8) + fields length prefix (4) +
+        //   2 signature fields (2 * 66) + 1 compressed public key field (34) + signatures
+        //   required (2) = 209 bytes
+        assert_eq!(auth_size_delta(&p2pkh, &p2sh), 106);
+        assert_eq!(auth_size_delta(&p2sh, &p2pkh), -106);
+        assert_eq!(auth_size_delta(&p2pkh, &p2pkh), 0);
+    }
+
+    #[test]
+    fn tx_stacks_spending_condition_address_from_bytes() {
+        let singlesig = |hash_mode| {
+            TransactionSpendingCondition::Singlesig(SinglesigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode,
+                key_encoding: TransactionPublicKeyEncoding::Compressed,
+                nonce: 123,
+                tx_fee: 456,
+                signature: MessageSignature::from_raw(&vec![0xff; 65]),
+            })
+        };
+        // 2-of-3, signed
+        let multisig = |hash_mode| {
+            TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode,
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xff; 65]),
+                    ),
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xfe; 65]),
+                    ),
+                    TransactionAuthField::PublicKey(
+                        PubKey::from_hex(
+                            "03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77",
+                        )
+                        .unwrap(),
+                    ),
+                ],
+                signatures_required: 2,
+            })
+        };
+
+        // (condition, mainnet version, testnet version)
+        let conditions = [
+            (
+                singlesig(SinglesigHashMode::P2PKH),
+                C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+                C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            ),
+            (
+                singlesig(SinglesigHashMode::P2WPKH),
+                C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+            ),
+            (
+                singlesig(SinglesigHashMode::P2TR),
+                C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+                C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            ),
+            (
+                multisig(MultisigHashMode::P2SH),
+                C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+            ),
+            (
+                multisig(MultisigHashMode::P2WSH),
+                C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+            ),
+            (
+                multisig(MultisigHashMode::P2SHNonSequential),
+                C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+            ),
+            (
+                multisig(MultisigHashMode::P2WSHNonSequential),
+                C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+            ),
+        ];
+
+        for (condition, mainnet_version, testnet_version) in conditions.iter() {
+            let bytes = condition.serialize_to_vec();
+            assert_eq!(
+                TransactionSpendingCondition::address_from_bytes(&bytes, true).unwrap(),
+                StacksAddress {
+                    version: *mainnet_version,
+                    bytes: Hash160([0x11; 20]),
+                }
+            );
+            assert_eq!(
+                TransactionSpendingCondition::address_from_bytes(&bytes, false).unwrap(),
+                StacksAddress {
+                    version: *testnet_version,
+                    bytes: Hash160([0x11; 20]),
+                }
+            );
+
+            // truncated or padded conditions are rejected
+            assert!(TransactionSpendingCondition::address_from_bytes(
+                &bytes[..bytes.len() - 1],
+                true
+            )
+            .is_err());
+            let mut padded_bytes = bytes.clone();
+            padded_bytes.push(0);
+            assert!(TransactionSpendingCondition::address_from_bytes(&padded_bytes, true).is_err());
+        }
+    }
+
+    #[test]
+    fn tx_stacks_multisig_signing_progress() {
+        let pubk_1 = StacksPublicKey::from_private(
+            &StacksPrivateKey::from_hex(
+                "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+            )
+            .unwrap(),
+        );
+        let pubk_2 = StacksPublicKey::from_private(
+            &StacksPrivateKey::from_hex(
+                "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+            )
+            .unwrap(),
+        );
+        let pubk_3 = StacksPublicKey::from_private(
+            &StacksPrivateKey::from_hex(
+                "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+            )
+            .unwrap(),
+        );
+
+        // 2-of-3, with no auth fields yet
+        let mut data = match TransactionSpendingCondition::new_multisig_p2sh(
+            2,
+            vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+        )
+        .unwrap()
+        {
+            TransactionSpendingCondition::Multisig(data) => data,
+            _ => panic!("expected a multisig condition"),
+        };
+        assert_eq!(data.signatures_present(), 0);
+        assert_eq!(data.remaining_signatures(), 2);
+        assert!(!data.is_complete());
+
+        // public keys don't count towards completion
+        data.push_public_key(pubk_1);
+        assert_eq!(data.signatures_present(), 0);
+        assert_eq!(data.remaining_signatures(), 2);
+        assert!(!data.is_complete());
+
+        data.push_signature(
+            TransactionPublicKeyEncoding::Compressed,
+            MessageSignature::from_raw(&vec![0xff; 65]),
+        );
+        assert_eq!(data.signatures_present(), 1);
+        assert_eq!(data.remaining_signatures(), 1);
+        assert!(!data.is_complete());
+
+        data.push_signature(
+            TransactionPublicKeyEncoding::Compressed,
+            MessageSignature::from_raw(&vec![0xfe; 65]),
+        );
+        assert_eq!(data.signatures_present(), 2);
+        assert_eq!(data.remaining_signatures(), 0);
+        assert!(data.is_complete());
+
+        // extra signatures don't underflow
+        data.push_signature(
+            TransactionPublicKeyEncoding::Compressed,
+            MessageSignature::from_raw(&vec![0xfd; 65]),
+        );
+        assert_eq!(data.signatures_present(), 3);
+        assert_eq!(data.remaining_signatures(), 0);
+        assert!(data.is_complete());
+
+        // popping a signature undoes progress
+        data.pop_auth_field();
+        data.pop_auth_field();
+        assert_eq!(data.signatures_present(), 1);
+        assert_eq!(data.remaining_signatures(), 1);
+        assert!(!data.is_complete());
+    }
+
+    #[test]
+    fn tx_stacks_auth_hash_mode() {
+        let singlesig = |hash_mode| {
+            TransactionSpendingCondition::Singlesig(SinglesigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode,
+                key_encoding: TransactionPublicKeyEncoding::Compressed,
+                nonce: 123,
+                tx_fee: 456,
+                signature: MessageSignature::from_raw(&vec![0xff; 65]),
+            })
+        };
+        let multisig = |hash_mode| {
+            TransactionSpendingCondition::Multisig(MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode,
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![
+                    TransactionAuthField::Signature(
+                        TransactionPublicKeyEncoding::Compressed,
+                        MessageSignature::from_raw(&vec![0xff; 65]),
+                    ),
+                    TransactionAuthField::PublicKey(
+                        PubKey::from_hex(
+                            "03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77",
+                        )
+                        .unwrap(),
+                    ),
+                ],
+                signatures_required: 1,
+            })
+        };
+
+        // (condition, hash mode byte, order-independent)
+        let conditions = [
+            (singlesig(SinglesigHashMode::P2PKH), 0x00, false),
+            (multisig(MultisigHashMode::P2SH), 0x01, false),
+            (singlesig(SinglesigHashMode::P2WPKH), 0x02, false),
+            (multisig(MultisigHashMode::P2WSH), 0x03, false),
+            (multisig(MultisigHashMode::P2SHNonSequential), 0x05, true),
+            (singlesig(SinglesigHashMode::P2TR), 0x06, false),
+            (multisig(MultisigHashMode::P2WSHNonSequential), 0x07, true),
+        ];
+
+        for (condition, hash_mode_byte, order_independent) in conditions.iter() {
+            assert_eq!(condition.hash_mode_byte(), *hash_mode_byte);
+            assert_eq!(condition.serialize_to_vec()[0], *hash_mode_byte);
+            assert_eq!(condition.is_order_independent(), *order_independent);
+
+            let standard = TransactionAuth::Standard(condition.clone());
+            assert_eq!(standard.hash_mode(), vec![*hash_mode_byte]);
+
+            let sponsored =
+                TransactionAuth::Sponsored(singlesig(SinglesigHashMode::P2PKH), condition.clone());
+            assert_eq!(sponsored.hash_mode(), vec![0x00, *hash_mode_byte]);
+        }
+    }
+
+    #[test]
+    fn tx_stacks_auth_back_to_back_deserialize() {
+        let origin =
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &StacksPrivateKey::from_hex(
+                    "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+                )
+                .unwrap(),
+            ))
+            .unwrap();
+        let sponsor =
+            TransactionSpendingCondition::new_singlesig_p2wpkh(StacksPublicKey::from_private(
+                &StacksPrivateKey::from_hex(
+                    "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+                )
+                .unwrap(),
+            ))
+            .unwrap();
+        let standard = TransactionAuth::Standard(origin.clone());
+        let sponsored = TransactionAuth::Sponsored(origin, sponsor);
+
+        let mut bytes = standard.serialize_to_vec();
+        bytes.extend(sponsored.serialize_to_vec());
+        bytes.push(0xff);
+
+        // each auth consumes exactly its own bytes, leaving the reader at the next one
+        let mut cursor = io::Cursor::new(&bytes);
+        assert_eq!(
+            TransactionAuth::consensus_deserialize(&mut cursor).unwrap(),
+            standard
+        );
+        assert_eq!(
+            cursor.position() as usize,
+            standard.serialize_to_vec().len()
+        );
+        assert_eq!(
+            TransactionAuth::consensus_deserialize(&mut cursor).unwrap(),
+            sponsored
+        );
+        assert_eq!(cursor.position() as usize, bytes.len() - 1);
+    }
+
+    #[test]
+    fn tx_stacks_multisig_try_push_auth_fields() {
+        let compressed_pubkey =
+            PubKey::from_hex("03ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c77")
+                .unwrap();
+        let uncompressed_pubkey = PubKey::from_hex("04ef2340518b5867b23598a9cf74611f8b98064f7d55cdb8c107c67b5efcbc5c771f112f919b00a6c6c5f51f7c63e1762fe9fac9b66ec75a053db7f51f4a52712b").unwrap();
+        let signature = MessageSignature::from_raw(&vec![0xff; 65]);
+
+        for hash_mode in [
+            MultisigHashMode::P2SH,
+            MultisigHashMode::P2WSH,
+            MultisigHashMode::P2SHNonSequential,
+            MultisigHashMode::P2WSHNonSequential,
+        ] {
+            let mut data = MultisigSpendingCondition {
+                signer: Hash160([0x11; 20]),
+                hash_mode: hash_mode.clone(),
+                nonce: 123,
+                tx_fee: 456,
+                fields: vec![],
+                signatures_required: 2,
+            };
+
+            // compressed keys are always accepted
+            data.try_push_signature(TransactionPublicKeyEncoding::Compressed, signature.clone())
+                .unwrap();
+            data.try_push_public_key(compressed_pubkey.clone()).unwrap();
+            assert_eq!(data.fields.len(), 2);
+
+            // uncompressed keys are rejected up front for the P2WSH flavors, and not pushed
+            let compressed_only = hash_mode.requires_compressed_keys();
+            assert_eq!(
+                data.try_push_signature(
+                    TransactionPublicKeyEncoding::Uncompressed,
+                    signature.clone()
+                )
+                .is_err(),
+                compressed_only
+            );
+            assert_eq!(
+                data.try_push_public_key(uncompressed_pubkey.clone())
+                    .is_err(),
+                compressed_only
+            );
+            assert_eq!(data.fields.len(), if compressed_only { 2 } else { 4 });
+        }
+    }
 }
@@ -563,46 +563,68 @@ impl StacksBlock {
         }
     }
 
-    /// Verify that all transactions are supported in the given epoch, as indicated by `epoch_id`
-    pub fn validate_transactions_static_epoch(
-        txs: &[StacksTransaction],
+    /// Verify that a transaction is supported in the given epoch, as indicated by `epoch_id`.
+    /// Shared by anchored blocks and microblocks.
+    pub fn validate_transaction_static_epoch(
+        tx: &StacksTransaction,
         epoch_id: StacksEpochId,
     ) -> bool {
-        for tx in txs.iter() {
-            if let TransactionPayload::Coinbase(_, ref recipient_opt, ref proof_opt) = &tx.payload {
-                if proof_opt.is_some() && epoch_id < StacksEpochId::Epoch30 {
-                    // not supported
-                    error!("Coinbase with VRF proof not supported before Stacks 3.0"; "txid" => %tx.txid());
-                    return false;
-                }
-                if proof_opt.is_none() && epoch_id >= StacksEpochId::Epoch30 {
-                    // not supported
-                    error!("Coinbase with VRF proof is required in Stacks 3.0 and later"; "txid" => %tx.txid());
-                    return false;
-                }
-                if recipient_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
-                    // not supported
-                    error!("Coinbase pay-to-alt-recipient not supported before Stacks 2.1"; "txid" => %tx.txid());
-                    return false;
-                }
+        if let TransactionPayload::Coinbase(_, ref recipient_opt, ref proof_opt) = &tx.payload {
+            if proof_opt.is_some() && epoch_id < StacksEpochId::Epoch30 {
+                // not supported
+                error!("Coinbase with VRF proof not supported before Stacks 3.0"; "txid" => %tx.txid());
+                return false;
             }
-            if let TransactionPayload::SmartContract(_, ref version_opt) = &tx.payload {
-                if version_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
-                    // not supported
-                    error!("Versioned smart contracts not supported before Stacks 2.1");
-                    return false;
-                }
+            if proof_opt.is_none() && epoch_id >= StacksEpochId::Epoch30 {
+                // not supported
+                error!("Coinbase with VRF proof is required in Stacks 3.0 and later"; "txid" => %tx.txid());
+                return false;
             }
-            if let TransactionPayload::TenureChange(..) = &tx.payload {
-                if epoch_id < StacksEpochId::Epoch30 {
-                    error!("TenureChange transaction not supported before Stacks 3.0"; "txid" => %tx.txid());
-                    return false;
-                }
+            if recipient_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
+                // not supported
+                error!("Coinbase pay-to-alt-recipient not supported before Stacks 2.1"; "txid" => %tx.txid());
+                return false;
+            }
+        }
+        if let TransactionPayload::SmartContract(_, ref version_opt) = &tx.payload {
+            if version_opt.is_some() && epoch_id < StacksEpochId::Epoch21 {
+                // not supported
+                error!("Versioned smart contracts not supported before Stacks 2.1");
+                return false;
+            }
+        }
+        if let TransactionPayload::TenureChange(..) = &tx.payload {
+            if epoch_id < StacksEpochId::Epoch30 {
+                error!("TenureChange transaction not supported before Stacks 3.0"; "txid" => %tx.txid());
+                return false;
+            }
+        }
+        if tx.auth.uses_p2tr() && epoch_id < StacksEpochId::Epoch30 {
+            error!("Taproot spending conditions not supported before Stacks 3.0"; "txid" => %tx.txid());
+            return false;
+        }
+        if tx.auth.uses_order_independent_multisig() && epoch_id < StacksEpochId::Epoch30 {
+            error!("Order-independent multisig spending conditions not supported before Stacks 3.0"; "txid" => %tx.txid());
+            return false;
+        }
+        if epoch_id >= StacksEpochId::Epoch30 {
+            if let Err(e) = tx.validate_multisig_constraints() {
+                error!("Multisig spending condition violates the Stacks 3.0 constraints"; "txid" => %tx.txid(), "error" => ?e);
+                return false;
             }
         }
         return true;
     }
 
+    /// Verify that all transactions are supported in the given epoch, as indicated by `epoch_id`
+    pub fn validate_transactions_static_epoch(
+        txs: &[StacksTransaction],
+        epoch_id: StacksEpochId,
+    ) -> bool {
+        txs.iter()
+            .all(|tx| StacksBlock::validate_transaction_static_epoch(tx, epoch_id))
+    }
+
     /// static sanity checks on transactions.
     pub fn validate_transactions_static(
         &self,
@@ -924,6 +946,13 @@ impl StacksMicroblock {
         }
         return true;
     }
+
+    /// Verify that all transactions are supported in the given epoch, as indicated by `epoch_id`.
+    /// A microblock's epoch is that of the anchored block that confirms it, so unlike the other
+    /// static checks, this one can only run once the microblock is processed.
+    pub fn validate_transactions_static_epoch(&self, epoch_id: StacksEpochId) -> bool {
+        StacksBlock::validate_transactions_static_epoch(&self.txs, epoch_id)
+    }
 }
 
 #[cfg(test)]
@@ -1796,6 +1825,54 @@ mod test {
             TransactionPayload::TenureChange(tenure_change_payload),
         );
 
+        let p2tr_auth = TransactionAuth::from_p2tr(&privk).unwrap();
+        let tx_transfer_p2tr = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            p2tr_auth,
+            TransactionPayload::TokenTransfer(
+                stx_address.into(),
+                123,
+                TokenTransferMemo([1u8; 34]),
+            ),
+        );
+
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let pubk = StacksPublicKey::from_private(&privk);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let sign_multisig = |condition: TransactionSpendingCondition,
+                             privks: &[&StacksPrivateKey]| {
+            let tx = StacksTransaction::new(
+                TransactionVersion::Testnet,
+                TransactionAuth::Standard(condition),
+                TransactionPayload::TokenTransfer(
+                    stx_address.into(),
+                    123,
+                    TokenTransferMemo([1u8; 34]),
+                ),
+            );
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            for privk in privks {
+                tx_signer.sign_origin(privk).unwrap();
+            }
+            tx_signer.get_tx().unwrap()
+        };
+        let tx_transfer_order_independent = sign_multisig(
+            TransactionSpendingCondition::new_multisig_order_independent_p2wsh(
+                2,
+                vec![pubk.clone(), pubk_2.clone()],
+            )
+            .unwrap(),
+            &[&privk, &privk_2],
+        );
+        let tx_transfer_duplicate_signer = sign_multisig(
+            TransactionSpendingCondition::new_multisig_p2sh(2, vec![pubk.clone(), pubk.clone()])
+                .unwrap(),
+            &[&privk, &privk],
+        );
+
         let dup_txs = vec![
             tx_coinbase.clone(),
             tx_transfer.clone(),
@@ -1810,6 +1887,9 @@ mod test {
         let nakamoto_coinbase = vec![tx_coinbase_proof.clone()];
         let tenure_change_tx = vec![tx_tenure_change.clone()];
         let nakamoto_txs = vec![tx_coinbase_proof.clone(), tx_tenure_change.clone()];
+        let p2tr_transfer = vec![tx_transfer_p2tr.clone()];
+        let order_independent_transfer = vec![tx_transfer_order_independent.clone()];
+        let duplicate_signer_transfer = vec![tx_transfer_duplicate_signer.clone()];
 
         assert!(!StacksBlock::validate_transactions_unique(&dup_txs));
         assert!(!StacksBlock::validate_transactions_network(
@@ -1863,6 +1943,105 @@ mod test {
             &nakamoto_txs,
             StacksEpochId::Epoch21
         ));
+        assert!(!StacksBlock::validate_transactions_static_epoch(
+            &p2tr_transfer,
+            StacksEpochId::Epoch25
+        ));
+        assert!(StacksBlock::validate_transactions_static_epoch(
+            &p2tr_transfer,
+            StacksEpochId::Epoch30
+        ));
+        assert!(!StacksBlock::validate_transactions_static_epoch(
+            &order_independent_transfer,
+            StacksEpochId::Epoch25
+        ));
+        assert!(StacksBlock::validate_transactions_static_epoch(
+            &order_independent_transfer,
+            StacksEpochId::Epoch30
+        ));
+        assert!(StacksBlock::validate_transactions_static_epoch(
+            &duplicate_signer_transfer,
+            StacksEpochId::Epoch25
+        ));
+        assert!(!StacksBlock::validate_transactions_static_epoch(
+            &duplicate_signer_transfer,
+            StacksEpochId::Epoch30
+        ));
+    }
+
+    #[test]
+    fn test_microblock_validate_transactions_static_epoch() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let stx_address = StacksAddress {
+            version: 0,
+            bytes: Hash160([0u8; 20]),
+        };
+        let mut tx_transfer_p2tr = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::from_p2tr(&privk).unwrap(),
+            TransactionPayload::TokenTransfer(
+                stx_address.into(),
+                123,
+                TokenTransferMemo([1u8; 34]),
+            ),
+        );
+        tx_transfer_p2tr.anchor_mode = TransactionAnchorMode::OffChainOnly;
+
+        let microblock =
+            StacksMicroblock::first_unsigned(&BlockHeaderHash([1u8; 32]), vec![tx_transfer_p2tr]);
+        assert!(microblock.validate_transactions_static(false, 0));
+        assert!(!microblock.validate_transactions_static_epoch(StacksEpochId::Epoch25));
+        assert!(microblock.validate_transactions_static_epoch(StacksEpochId::Epoch30));
+    }
+
+    #[test]
+    fn test_microblock_order_independent_multisig_epoch() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let stx_address = StacksAddress {
+            version: 0,
+            bytes: Hash160([0u8; 20]),
+        };
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::Standard(
+                TransactionSpendingCondition::new_multisig_order_independent_p2wsh(
+                    2,
+                    vec![
+                        StacksPublicKey::from_private(&privk),
+                        StacksPublicKey::from_private(&privk_2),
+                    ],
+                )
+                .unwrap(),
+            ),
+            TransactionPayload::TokenTransfer(
+                stx_address.into(),
+                123,
+                TokenTransferMemo([1u8; 34]),
+            ),
+        );
+        tx.anchor_mode = TransactionAnchorMode::OffChainOnly;
+        let mut tx_signer = StacksTransactionSigner::new(&tx);
+        tx_signer.sign_origin(&privk).unwrap();
+        tx_signer.sign_origin(&privk_2).unwrap();
+        let tx_transfer_order_independent = tx_signer.get_tx().unwrap();
+
+        let microblock = StacksMicroblock::first_unsigned(
+            &BlockHeaderHash([1u8; 32]),
+            vec![tx_transfer_order_independent],
+        );
+        assert!(microblock.validate_transactions_static(false, 0));
+        assert!(!microblock.validate_transactions_static_epoch(StacksEpochId::Epoch25));
+        assert!(microblock.validate_transactions_static_epoch(StacksEpochId::Epoch30));
     }
 
     // TODO:
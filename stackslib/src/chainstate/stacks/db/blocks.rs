@@ -4044,6 +4044,18 @@ impl StacksChainState {
         let mut receipts = vec![];
         for microblock in microblocks.iter() {
             debug!("Process microblock {}", &microblock.block_hash());
+            if !microblock.validate_transactions_static_epoch(clarity_tx.get_epoch()) {
+                let msg = format!(
+                    "Invalid microblock {}: one or more transactions are not supported in epoch {}",
+                    microblock.block_hash(),
+                    clarity_tx.get_epoch()
+                );
+                warn!("{}", &msg);
+                return Err((
+                    Error::InvalidStacksMicroblock(msg, microblock.block_hash()),
+                    microblock.block_hash(),
+                ));
+            }
             for (tx_index, tx) in microblock.txs.iter().enumerate() {
                 let (tx_fee, mut tx_receipt) =
                     StacksChainState::process_transaction(clarity_tx, tx, false, ast_rules)
@@ -6638,6 +6650,33 @@ impl StacksChainState {
         }
     }
 
+    /// Check that a tx's spending conditions are supported in the given epoch.
+    /// Taproot and order-independent multisig spending conditions are only valid in Stacks 3.0
+    /// and later, which also enforces the multisig constraints of
+    /// `TransactionAuth::validate_multisig_constraints()`.
+    fn check_auth_epoch(
+        tx: &StacksTransaction,
+        epoch_id: StacksEpochId,
+    ) -> Result<(), MemPoolRejection> {
+        if epoch_id >= StacksEpochId::Epoch30 {
+            return tx
+                .validate_multisig_constraints()
+                .map_err(|e| MemPoolRejection::FailedToValidate(Error::NetError(e)));
+        }
+        if tx.auth.uses_p2tr() {
+            return Err(MemPoolRejection::Other(
+                "Taproot spending conditions are not supported in this epoch".to_string(),
+            ));
+        }
+        if tx.auth.uses_order_independent_multisig() {
+            return Err(MemPoolRejection::Other(
+                "Order-independent multisig spending conditions are not supported in this epoch"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Given an outstanding clarity connection, can we append the tx to the chain state?
     /// Used when determining whether a transaction can be added to the mempool.
     fn can_include_tx<T: ClarityConnection>(
@@ -6653,6 +6692,9 @@ impl StacksChainState {
         StacksChainState::process_transaction_precheck(&chainstate_config, &tx)
             .map_err(|e| MemPoolRejection::FailedToValidate(e))?;
 
+        // its spending conditions must be supported in this epoch
+        StacksChainState::check_auth_epoch(tx, clarity_connection.get_epoch())?;
+
         // 3: it must pay a tx fee
         let fee = tx.get_tx_fee();
 
@@ -11983,6 +12025,70 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn mempool_check_auth_epoch() {
+        let privk_1 = StacksPrivateKey::new();
+        let privk_2 = StacksPrivateKey::new();
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let sign = |auth: TransactionAuth, privks: &[&StacksPrivateKey]| {
+            let tx = StacksTransaction::new(
+                TransactionVersion::Testnet,
+                auth,
+                TransactionPayload::TokenTransfer(
+                    StacksAddress {
+                        version: 0,
+                        bytes: Hash160([0u8; 20]),
+                    }
+                    .into(),
+                    123,
+                    TokenTransferMemo([1u8; 34]),
+                ),
+            );
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            for privk in privks {
+                tx_signer.sign_origin(privk).unwrap();
+            }
+            tx_signer.get_tx_incomplete()
+        };
+
+        let p2tr = sign(TransactionAuth::from_p2tr(&privk_1).unwrap(), &[]);
+        let order_independent = sign(
+            TransactionAuth::Standard(
+                TransactionSpendingCondition::new_multisig_order_independent_p2sh(
+                    2,
+                    vec![pubk_1.clone(), pubk_2.clone()],
+                )
+                .unwrap(),
+            ),
+            &[&privk_1, &privk_2],
+        );
+        let duplicate_signer = sign(
+            TransactionAuth::Standard(
+                TransactionSpendingCondition::new_multisig_p2sh(
+                    2,
+                    vec![pubk_1.clone(), pubk_1.clone()],
+                )
+                .unwrap(),
+            ),
+            &[&privk_1, &privk_1],
+        );
+
+        for tx in [&p2tr, &order_independent] {
+            assert!(matches!(
+                StacksChainState::check_auth_epoch(tx, StacksEpochId::Epoch25),
+                Err(MemPoolRejection::Other(_))
+            ));
+            StacksChainState::check_auth_epoch(tx, StacksEpochId::Epoch30).unwrap();
+        }
+
+        StacksChainState::check_auth_epoch(&duplicate_signer, StacksEpochId::Epoch25).unwrap();
+        assert!(matches!(
+            StacksChainState::check_auth_epoch(&duplicate_signer, StacksEpochId::Epoch30),
+            Err(MemPoolRejection::FailedToValidate(_))
+        ));
+    }
+
     // TODO(test): test multiple anchored blocks confirming the same microblock stream (in the same
     // place, and different places, with/without orphans)
     // TODO(test): process_next_staging_block
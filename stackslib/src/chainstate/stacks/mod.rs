@@ -437,10 +437,43 @@ impl TransactionPublicKeyEncoding {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TransactionAuthField {
-    PublicKey(StacksPublicKey),
+    PublicKey(
+        #[serde(
+            serialize_with = "pubkey_hex_serialize",
+            deserialize_with = "pubkey_hex_deserialize"
+        )]
+        StacksPublicKey,
+    ),
     Signature(TransactionPublicKeyEncoding, MessageSignature),
 }
 
+/// Public keys in auth fields are serialized as their hex encoding, which is compressed or not
+/// as the key is.  Keys serialized before this change are structs, and still deserialize.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AuthFieldPublicKey {
+    Hex(String),
+    Legacy(StacksPublicKey),
+}
+
+fn pubkey_hex_serialize<S: serde::Serializer>(
+    pubkey: &StacksPublicKey,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&pubkey.to_hex())
+}
+
+fn pubkey_hex_deserialize<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> Result<StacksPublicKey, D::Error> {
+    match AuthFieldPublicKey::deserialize(d)? {
+        AuthFieldPublicKey::Hex(key_hex) => {
+            StacksPublicKey::from_hex(&key_hex).map_err(serde::de::Error::custom)
+        }
+        AuthFieldPublicKey::Legacy(pubkey) => Ok(pubkey),
+    }
+}
+
 impl TransactionAuthField {
     pub fn is_public_key(&self) -> bool {
         match *self {
@@ -497,6 +530,9 @@ impl TransactionAuthField {
 pub enum SinglesigHashMode {
     P2PKH = 0x00,
     P2WPKH = 0x02,
+    /// A taproot key-path spend, authorized by the BIP-340 x-only encoding of a single key.
+    /// Only valid in Stacks 3.0 and later.
+    P2TR = 0x06,
 }
 
 #[repr(u8)]
@@ -504,6 +540,12 @@ pub enum SinglesigHashMode {
 pub enum MultisigHashMode {
     P2SH = 0x01,
     P2WSH = 0x03,
+    /// Like P2SH, but every signature is over the initial sighash, so signatures can be appended
+    /// in any order. Only valid in Stacks 3.0 and later.
+    P2SHNonSequential = 0x05,
+    /// Like P2WSH, but every signature is over the initial sighash, so signatures can be appended
+    /// in any order. Only valid in Stacks 3.0 and later.
+    P2WSHNonSequential = 0x07,
 }
 
 impl SinglesigHashMode {
@@ -511,6 +553,7 @@ impl SinglesigHashMode {
         match *self {
             SinglesigHashMode::P2PKH => AddressHashMode::SerializeP2PKH,
             SinglesigHashMode::P2WPKH => AddressHashMode::SerializeP2WPKH,
+            SinglesigHashMode::P2TR => AddressHashMode::SerializeP2TR,
         }
     }
 
@@ -518,6 +561,7 @@ impl SinglesigHashMode {
         match hm {
             AddressHashMode::SerializeP2PKH => Some(SinglesigHashMode::P2PKH),
             AddressHashMode::SerializeP2WPKH => Some(SinglesigHashMode::P2WPKH),
+            AddressHashMode::SerializeP2TR => Some(SinglesigHashMode::P2TR),
             _ => None,
         }
     }
@@ -526,6 +570,7 @@ impl SinglesigHashMode {
         match n {
             x if x == SinglesigHashMode::P2PKH as u8 => Some(SinglesigHashMode::P2PKH),
             x if x == SinglesigHashMode::P2WPKH as u8 => Some(SinglesigHashMode::P2WPKH),
+            x if x == SinglesigHashMode::P2TR as u8 => Some(SinglesigHashMode::P2TR),
             _ => None,
         }
     }
@@ -534,8 +579,12 @@ impl SinglesigHashMode {
 impl MultisigHashMode {
     pub fn to_address_hash_mode(&self) -> AddressHashMode {
         match *self {
-            MultisigHashMode::P2SH => AddressHashMode::SerializeP2SH,
-            MultisigHashMode::P2WSH => AddressHashMode::SerializeP2WSH,
+            MultisigHashMode::P2SH | MultisigHashMode::P2SHNonSequential => {
+                AddressHashMode::SerializeP2SH
+            }
+            MultisigHashMode::P2WSH | MultisigHashMode::P2WSHNonSequential => {
+                AddressHashMode::SerializeP2WSH
+            }
         }
     }
 
@@ -551,9 +600,32 @@ impl MultisigHashMode {
         match n {
             x if x == MultisigHashMode::P2SH as u8 => Some(MultisigHashMode::P2SH),
             x if x == MultisigHashMode::P2WSH as u8 => Some(MultisigHashMode::P2WSH),
+            x if x == MultisigHashMode::P2SHNonSequential as u8 => {
+                Some(MultisigHashMode::P2SHNonSequential)
+            }
+            x if x == MultisigHashMode::P2WSHNonSequential as u8 => {
+                Some(MultisigHashMode::P2WSHNonSequential)
+            }
             _ => None,
         }
     }
+
+    /// Whether each signature is made over the initial sighash instead of over the sighash
+    /// left by the previous signer
+    pub fn is_order_independent(&self) -> bool {
+        match *self {
+            MultisigHashMode::P2SH | MultisigHashMode::P2WSH => false,
+            MultisigHashMode::P2SHNonSequential | MultisigHashMode::P2WSHNonSequential => true,
+        }
+    }
+
+    /// Whether only compressed public keys may be used with this hash mode
+    pub fn requires_compressed_keys(&self) -> bool {
+        match *self {
+            MultisigHashMode::P2SH | MultisigHashMode::P2SHNonSequential => false,
+            MultisigHashMode::P2WSH | MultisigHashMode::P2WSHNonSequential => true,
+        }
+    }
 }
 
 /// A structure that encodes enough state to authenticate
@@ -858,7 +858,8 @@ impl StacksTransaction {
     }
 
     /// Sign a sighash and append the signature and public key to the given spending condition.
-    /// Returns the next sighash
+    /// Returns the next sighash, which is the given sighash itself if the condition is an
+    /// order-independent multisig
     fn sign_and_append(
         condition: &mut TransactionSpendingCondition,
         cur_sighash: &Txid,
@@ -882,7 +883,11 @@ impl StacksTransaction {
                     },
                     next_sig,
                 );
-                Ok(next_sighash)
+                if cond.hash_mode.is_order_independent() {
+                    Ok(cur_sighash.clone())
+                } else {
+                    Ok(next_sighash)
+                }
             }
             TransactionSpendingCondition::Singlesig(ref mut cond) => {
                 cond.set_signature(next_sig);
@@ -994,6 +999,13 @@ impl StacksTransaction {
         self.auth.verify(&self.verify_begin())
     }
 
+    /// Check this transaction's multisig spending conditions against the constraints enforced
+    /// from Stacks 3.0 on (see `TransactionAuth::validate_multisig_constraints()`)
+    pub fn validate_multisig_constraints(&self) -> Result<(), net_error> {
+        self.auth
+            .validate_multisig_constraints(&self.verify_begin())
+    }
+
     /// Verify the transaction's origin signatures only.
     /// Used by sponsors to get the next sig-hash to sign.
     pub fn verify_origin(&self) -> Result<Txid, net_error> {
@@ -1225,6 +1237,7 @@ mod test {
     use stacks_common::util::retry::{BoundReader, LogReader};
 
     use super::*;
+    use crate::burnchains::PrivateKey;
     use crate::chainstate::stacks::test::codec_all_transactions;
     use crate::chainstate::stacks::{
         StacksPublicKey as PubKey, C32_ADDRESS_VERSION_MAINNET_MULTISIG,
@@ -4437,12 +4450,7 @@ mod test {
     }
 
     #[test]
-    fn tx_stacks_transaction_sign_verify_sponsored_p2sh() {
-        let origin_privk = StacksPrivateKey::from_hex(
-            "807bbe9e471ac976592cc35e3056592ecc0f778ee653fced3b491a122dd8d59701",
-        )
-        .unwrap();
-
+    fn tx_stacks_transaction_verify_and_recover_p2sh() {
         let privk_1 = StacksPrivateKey::from_hex(
             "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
         )
@@ -4460,139 +4468,202 @@ mod test {
         let pubk_2 = StacksPublicKey::from_private(&privk_2);
         let pubk_3 = StacksPublicKey::from_private(&privk_3);
 
-        let random_sponsor = StacksPrivateKey::new(); // what the origin sees
-
-        let auth = TransactionAuth::Sponsored(
-            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
-                &origin_privk,
-            ))
-            .unwrap(),
-            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
-                &random_sponsor,
-            ))
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+            )
             .unwrap(),
         );
 
-        let real_sponsor = TransactionSpendingCondition::new_multisig_p2sh(
-            2,
-            vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
+
+        for tx in txs {
+            // keys 1 and 3 sign, and key 2 does not
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.append_origin(&pubk_2).unwrap();
+            tx_signer.sign_origin(&privk_3).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+
+            let initial_sighash = signed_tx.verify_begin();
+            let (next_sighash, recovered_keys) = signed_tx
+                .auth()
+                .origin()
+                .verify_and_recover(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                .unwrap();
+            assert_eq!(recovered_keys, vec![pubk_1.clone(), pubk_3.clone()]);
+            assert_eq!(
+                next_sighash,
+                signed_tx
+                    .auth()
+                    .origin()
+                    .verify(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_try_recover_pubkeys_p2sh() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
         )
         .unwrap();
 
-        let origin_address = auth.origin().address_mainnet();
-        let sponsor_address = real_sponsor.address_mainnet();
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
 
-        assert_eq!(
-            origin_address,
-            StacksAddress {
-                version: C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
-                bytes: Hash160::from_hex("3597aaa4bde720be93e3829aae24e76e7fcdfd3e").unwrap()
-            }
-        );
-        assert_eq!(
-            sponsor_address,
-            StacksAddress {
-                version: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
-                bytes: Hash160::from_hex("a23ea89d6529ac48ac766f720e480beec7f19273").unwrap()
-            }
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk_1.clone(), pubk_2.clone()],
+            )
+            .unwrap(),
         );
 
-        let txs = tx_stacks_transaction_test_txs(&auth);
-
-        for mut tx in txs {
-            assert_eq!(tx.auth().origin().num_signatures(), 0);
-            assert_eq!(tx.auth().sponsor().unwrap().num_signatures(), 0);
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
 
-            tx.set_tx_fee(123);
-            tx.set_sponsor_nonce(456).unwrap();
+        for tx in txs {
             let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.sign_origin(&privk_2).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
 
-            tx_signer.sign_origin(&origin_privk).unwrap();
+            let TransactionSpendingCondition::Multisig(data) = signed_tx.auth().origin() else {
+                panic!("Expected a multisig spending condition");
+            };
 
-            // sponsor sets and pays fee after origin signs
-            let mut origin_tx = tx_signer.get_tx_incomplete();
-            origin_tx.auth.set_sponsor(real_sponsor.clone()).unwrap();
-            origin_tx.set_tx_fee(456);
-            origin_tx.set_sponsor_nonce(789).unwrap();
-            tx_signer.resume(&origin_tx);
+            let initial_sighash = signed_tx.verify_begin();
+            let pubkeys = data
+                .try_recover_pubkeys(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                .unwrap();
+            assert_eq!(pubkeys, vec![pubk_1.clone(), pubk_2.clone()]);
 
-            tx_signer.sign_sponsor(&privk_1).unwrap();
-            tx_signer.sign_sponsor(&privk_2).unwrap();
-            tx_signer.append_sponsor(&pubk_3).unwrap();
+            let addr = StacksAddress::from_public_keys(
+                0,
+                &data.hash_mode.to_address_hash_mode(),
+                data.signatures_required as usize,
+                &pubkeys,
+            )
+            .unwrap();
+            assert_eq!(addr.bytes, data.signer);
+        }
+    }
 
-            tx.set_tx_fee(456);
-            tx.set_sponsor_nonce(789).unwrap();
-            let mut signed_tx = tx_signer.get_tx().unwrap();
+    #[test]
+    fn tx_stacks_transaction_verify_with_recovery_backend() {
+        use std::cell::Cell;
 
-            check_oversign_origin_singlesig(&mut signed_tx);
-            check_oversign_sponsor_multisig(&mut signed_tx);
+        use crate::chainstate::stacks::auth::{
+            RecoveryBackend, Secp256k1RecoveryBackend, VerifyContext,
+        };
 
-            assert_eq!(signed_tx.auth().origin().num_signatures(), 1);
-            assert_eq!(signed_tx.auth().sponsor().unwrap().num_signatures(), 2);
+        /// Delegates to the default backend, counting how often it is called
+        struct CountingRecoveryBackend {
+            calls: Cell<usize>,
+        }
 
-            // tx and signed_tx are otherwise equal
-            assert_eq!(tx.version, signed_tx.version);
-            assert_eq!(tx.chain_id, signed_tx.chain_id);
-            assert_eq!(tx.get_tx_fee(), signed_tx.get_tx_fee());
-            assert_eq!(tx.get_origin_nonce(), signed_tx.get_origin_nonce());
-            assert_eq!(tx.get_sponsor_nonce(), signed_tx.get_sponsor_nonce());
-            assert_eq!(tx.anchor_mode, signed_tx.anchor_mode);
-            assert_eq!(tx.post_condition_mode, signed_tx.post_condition_mode);
-            assert_eq!(tx.post_conditions, signed_tx.post_conditions);
-            assert_eq!(tx.payload, signed_tx.payload);
+        impl RecoveryBackend for CountingRecoveryBackend {
+            fn recover_to_pubkey(
+                &self,
+                msg_hash: &[u8],
+                sig: &MessageSignature,
+            ) -> Result<StacksPublicKey, &'static str> {
+                self.calls.set(self.calls.get() + 1);
+                Secp256k1RecoveryBackend.recover_to_pubkey(msg_hash, sig)
+            }
+        }
 
-            // auth is standard and first two auth fields are signatures for compressed keys.
-            // third field is the third public key
-            match signed_tx.auth {
-                TransactionAuth::Sponsored(ref origin, ref sponsor) => {
-                    match origin {
-                        TransactionSpendingCondition::Singlesig(ref data) => {
-                            assert_eq!(data.key_encoding, TransactionPublicKeyEncoding::Compressed);
-                            assert_eq!(data.signer, origin_address.bytes);
-                        }
-                        _ => assert!(false),
-                    }
-                    match sponsor {
-                        TransactionSpendingCondition::Multisig(ref data) => {
-                            assert_eq!(data.signer, sponsor_address.bytes);
-                            assert_eq!(data.fields.len(), 3);
-                            assert!(data.fields[0].is_signature());
-                            assert!(data.fields[1].is_signature());
-                            assert!(data.fields[2].is_public_key());
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
 
-                            assert_eq!(
-                                data.fields[0].as_signature().unwrap().0,
-                                TransactionPublicKeyEncoding::Compressed
-                            );
-                            assert_eq!(
-                                data.fields[1].as_signature().unwrap().0,
-                                TransactionPublicKeyEncoding::Compressed
-                            );
-                            assert_eq!(data.fields[2].as_public_key().unwrap(), pubk_3);
-                        }
-                        _ => assert!(false),
-                    }
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+
+        let auths = [
+            TransactionAuth::from_p2pkh(&privk_1).unwrap(),
+            TransactionAuth::Standard(
+                TransactionSpendingCondition::new_multisig_p2sh(
+                    2,
+                    vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+                )
+                .unwrap(),
+            ),
+        ];
+
+        for origin_auth in auths.iter() {
+            let txs = tx_stacks_transaction_test_txs(origin_auth);
+            for tx in txs {
+                let mut tx_signer = StacksTransactionSigner::new(&tx);
+                tx_signer.sign_origin(&privk_1).unwrap();
+                if let TransactionSpendingCondition::Multisig(_) = origin_auth.origin() {
+                    tx_signer.append_origin(&pubk_2).unwrap();
+                    tx_signer.sign_origin(&privk_3).unwrap();
                 }
-                _ => assert!(false),
-            };
+                let signed_tx = tx_signer.get_tx().unwrap();
+                let origin = signed_tx.auth().origin();
+                let initial_sighash = signed_tx.verify_begin();
+
+                let expected = origin
+                    .verify_and_recover_with(
+                        &VerifyContext::default(),
+                        &initial_sighash,
+                        &TransactionAuthFlags::AuthStandard,
+                    )
+                    .unwrap();
 
-            test_signature_and_corruption(&signed_tx, true, false);
-            test_signature_and_corruption(&signed_tx, false, true);
+                let mock = CountingRecoveryBackend {
+                    calls: Cell::new(0),
+                };
+                let ctx = VerifyContext { recovery: &mock };
+                let recovered = origin
+                    .verify_and_recover_with(
+                        &ctx,
+                        &initial_sighash,
+                        &TransactionAuthFlags::AuthStandard,
+                    )
+                    .unwrap();
+
+                assert_eq!(recovered, expected);
+                assert_eq!(mock.calls.get(), recovered.1.len());
+                assert_eq!(
+                    origin
+                        .verify_with(&ctx, &initial_sighash, &TransactionAuthFlags::AuthStandard)
+                        .unwrap(),
+                    expected.0
+                );
+            }
         }
     }
 
     #[test]
-    fn tx_stacks_transaction_sign_verify_standard_p2sh_uncompressed() {
+    fn tx_stacks_transaction_sign_verify_standard_order_independent_p2sh() {
         let privk_1 = StacksPrivateKey::from_hex(
-            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e0",
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
         )
         .unwrap();
         let privk_2 = StacksPrivateKey::from_hex(
-            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af",
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
         )
         .unwrap();
         let privk_3 = StacksPrivateKey::from_hex(
-            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d2",
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
         )
         .unwrap();
 
@@ -4600,92 +4671,732 @@ mod test {
         let pubk_2 = StacksPublicKey::from_private(&privk_2);
         let pubk_3 = StacksPublicKey::from_private(&privk_3);
 
-        let auth = TransactionAuth::Standard(
-            TransactionSpendingCondition::new_multisig_p2sh(
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_order_independent_p2sh(
                 2,
                 vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
             )
             .unwrap(),
         );
 
-        let origin_address = auth.origin().address_mainnet();
-
+        // same address as the sequential p2sh condition over the same keys
+        let origin_address = origin_auth.origin().address_mainnet();
         assert_eq!(
             origin_address,
             StacksAddress {
                 version: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
-                bytes: Hash160::from_hex("73a8b4a751a678fe83e9d35ce301371bb3d397f7").unwrap()
+                bytes: Hash160::from_hex("a23ea89d6529ac48ac766f720e480beec7f19273").unwrap()
             }
         );
 
-        let txs = tx_stacks_transaction_test_txs(&auth);
+        // sign the transaction with a single key, independently of the other signers
+        let sign_alone = |tx: &StacksTransaction, privk: &StacksPrivateKey| {
+            let mut tx_signer = StacksTransactionSigner::new(tx);
+            tx_signer.sign_origin(privk).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            match signed_tx.auth.origin() {
+                TransactionSpendingCondition::Multisig(ref data) => data.fields[0].clone(),
+                _ => panic!("Expected a multisig origin"),
+            }
+        };
 
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
         for tx in txs {
-            assert_eq!(tx.auth().origin().num_signatures(), 0);
-
             let mut tx_signer = StacksTransactionSigner::new(&tx);
-
             tx_signer.sign_origin(&privk_1).unwrap();
             tx_signer.sign_origin(&privk_2).unwrap();
             tx_signer.append_origin(&pubk_3).unwrap();
-
             let mut signed_tx = tx_signer.get_tx().unwrap();
 
             check_oversign_origin_multisig(&mut signed_tx);
             check_sign_no_sponsor(&mut signed_tx);
-
             assert_eq!(signed_tx.auth().origin().num_signatures(), 2);
-
-            // tx and signed_tx are otherwise equal
-            assert_eq!(tx.version, signed_tx.version);
-            assert_eq!(tx.chain_id, signed_tx.chain_id);
-            assert_eq!(tx.get_tx_fee(), signed_tx.get_tx_fee());
-            assert_eq!(tx.get_origin_nonce(), signed_tx.get_origin_nonce());
-            assert_eq!(tx.get_sponsor_nonce(), signed_tx.get_sponsor_nonce());
-            assert_eq!(tx.anchor_mode, signed_tx.anchor_mode);
-            assert_eq!(tx.post_condition_mode, signed_tx.post_condition_mode);
-            assert_eq!(tx.post_conditions, signed_tx.post_conditions);
-            assert_eq!(tx.payload, signed_tx.payload);
-
-            // auth is standard and first two auth fields are signatures for uncompressed keys.
-            // third field is the third public key
-            match signed_tx.auth {
-                TransactionAuth::Standard(ref origin) => match origin {
-                    TransactionSpendingCondition::Multisig(ref data) => {
-                        assert_eq!(data.signer, origin_address.bytes);
-                        assert_eq!(data.fields.len(), 3);
-                        assert!(data.fields[0].is_signature());
-                        assert!(data.fields[1].is_signature());
-                        assert!(data.fields[2].is_public_key());
-
-                        assert_eq!(
-                            data.fields[0].as_signature().unwrap().0,
-                            TransactionPublicKeyEncoding::Uncompressed
-                        );
-                        assert_eq!(
-                            data.fields[1].as_signature().unwrap().0,
-                            TransactionPublicKeyEncoding::Uncompressed
-                        );
-                        assert_eq!(data.fields[2].as_public_key().unwrap(), pubk_3);
-                    }
-                    _ => assert!(false),
-                },
-                _ => assert!(false),
-            };
-
             test_signature_and_corruption(&signed_tx, true, false);
+
+            // signatures collected in any order verify once placed in their keys' positions
+            let sig_2 = sign_alone(&tx, &privk_2);
+            let sig_1 = sign_alone(&tx, &privk_1);
+            let mut assembled_tx = tx.clone();
+            match assembled_tx.auth {
+                TransactionAuth::Standard(TransactionSpendingCondition::Multisig(ref mut data)) => {
+                    data.fields = vec![
+                        sig_1,
+                        sig_2,
+                        TransactionAuthField::PublicKey(pubk_3.clone()),
+                    ];
+                }
+                _ => panic!("Expected a multisig origin"),
+            }
+            assembled_tx.verify().unwrap();
+            assert_eq!(assembled_tx.auth, signed_tx.auth);
+
+            // a sequential condition does not accept independently collected signatures
+            let mut sequential_tx = assembled_tx.clone();
+            match sequential_tx.auth {
+                TransactionAuth::Standard(TransactionSpendingCondition::Multisig(ref mut data)) => {
+                    data.hash_mode = MultisigHashMode::P2SH;
+                }
+                _ => panic!("Expected a multisig origin"),
+            }
+            assert!(sequential_tx.verify().is_err());
         }
     }
 
     #[test]
-    fn tx_stacks_transaction_sign_verify_sponsored_p2sh_uncompressed() {
-        let origin_privk = StacksPrivateKey::from_hex(
-            "807bbe9e471ac976592cc35e3056592ecc0f778ee653fced3b491a122dd8d59701",
-        )
-        .unwrap();
-
+    fn tx_stacks_transaction_sighash_for_signer() {
         let privk_1 = StacksPrivateKey::from_hex(
-            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e0",
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+            )
+            .unwrap(),
+        );
+
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
+        for tx in txs {
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.append_origin(&pubk_1).unwrap();
+            tx_signer.sign_origin(&privk_2).unwrap();
+            tx_signer.sign_origin(&privk_3).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            let initial_sighash = signed_tx.verify_begin();
+            let origin = signed_tx.auth().origin();
+
+            // each signer signed exactly the sighash computed for its position
+            let fields = match origin {
+                TransactionSpendingCondition::Multisig(ref data) => data.fields.clone(),
+                _ => panic!("Expected a multisig origin"),
+            };
+            for (signer_index, privk) in [(1, &privk_2), (2, &privk_3)] {
+                let sighash = origin
+                    .sighash_for_signer(
+                        &initial_sighash,
+                        &TransactionAuthFlags::AuthStandard,
+                        signer_index,
+                    )
+                    .unwrap();
+                let sig = privk.sign(sighash.as_bytes()).unwrap();
+                assert_eq!(fields[signer_index].as_signature().unwrap().1, sig);
+            }
+            // the public key does not advance the rolling hash
+            assert_eq!(
+                origin
+                    .sighash_for_signer(&initial_sighash, &TransactionAuthFlags::AuthStandard, 0)
+                    .unwrap(),
+                origin
+                    .sighash_for_signer(&initial_sighash, &TransactionAuthFlags::AuthStandard, 1)
+                    .unwrap()
+            );
+
+            // the sighash for a signature to be appended is available too
+            origin
+                .sighash_for_signer(&initial_sighash, &TransactionAuthFlags::AuthStandard, 3)
+                .unwrap();
+            match origin.sighash_for_signer(
+                &initial_sighash,
+                &TransactionAuthFlags::AuthStandard,
+                4,
+            ) {
+                Err(net_error::SigningError(_)) => {}
+                res => panic!("Expected an out-of-bounds signer index, got {:?}", res),
+            }
+        }
+
+        let singlesig = TransactionSpendingCondition::new_singlesig_p2pkh(pubk_1).unwrap();
+        let initial_sighash = Txid([0x11; 32]);
+        assert_eq!(
+            singlesig
+                .sighash_for_signer(&initial_sighash, &TransactionAuthFlags::AuthStandard, 0)
+                .unwrap(),
+            TransactionSpendingCondition::make_sighash_presign(
+                &initial_sighash,
+                &TransactionAuthFlags::AuthStandard,
+                0,
+                0
+            )
+        );
+        assert!(singlesig
+            .sighash_for_signer(&initial_sighash, &TransactionAuthFlags::AuthStandard, 1)
+            .is_err());
+    }
+
+    #[test]
+    fn tx_stacks_transaction_expected_sighash_advances() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+        let pubks = vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()];
+
+        for (condition, order_independent) in [
+            (
+                TransactionSpendingCondition::new_multisig_p2sh(2, pubks.clone()).unwrap(),
+                false,
+            ),
+            (
+                TransactionSpendingCondition::new_multisig_order_independent_p2sh(2, pubks.clone())
+                    .unwrap(),
+                true,
+            ),
+        ] {
+            let origin_auth = TransactionAuth::Standard(condition);
+            let tx = tx_stacks_transaction_test_txs(&origin_auth).remove(0);
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.append_origin(&pubk_2).unwrap();
+            tx_signer.sign_origin(&privk_3).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            signed_tx.verify().unwrap();
+
+            let data = match signed_tx.auth().origin() {
+                TransactionSpendingCondition::Multisig(ref data) => data.clone(),
+                _ => panic!("Expected a multisig origin"),
+            };
+            let num_signature_fields = data
+                .fields
+                .iter()
+                .filter(|field| field.is_signature())
+                .count();
+            assert_eq!(num_signature_fields, usize::from(data.signatures_required));
+            if order_independent {
+                assert_eq!(data.expected_sighash_advances(), 0);
+            } else {
+                assert_eq!(
+                    usize::from(data.expected_sighash_advances()),
+                    num_signature_fields
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_canonicalize_fields() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+        let pubks = vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()];
+
+        for condition in [
+            TransactionSpendingCondition::new_multisig_p2sh(2, pubks.clone()).unwrap(),
+            TransactionSpendingCondition::new_multisig_order_independent_p2sh(2, pubks.clone())
+                .unwrap(),
+        ] {
+            let origin_auth = TransactionAuth::Standard(condition);
+            let tx = tx_stacks_transaction_test_txs(&origin_auth).remove(0);
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.append_origin(&pubk_2).unwrap();
+            tx_signer.sign_origin(&privk_3).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            let initial_sighash = signed_tx.verify_begin();
+
+            let signed = match signed_tx.auth().origin() {
+                TransactionSpendingCondition::Multisig(ref data) => data.clone(),
+                _ => panic!("Expected a multisig origin"),
+            };
+            let shuffled = |order: [usize; 3]| {
+                let mut condition = signed.clone();
+                condition.fields = order.iter().map(|i| signed.fields[*i].clone()).collect();
+                condition
+            };
+
+            let mut assembled_1 = shuffled([2, 1, 0]);
+            let mut assembled_2 = shuffled([1, 2, 0]);
+            assert!(assembled_1
+                .verify(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                .is_err());
+            assert_ne!(
+                assembled_1.serialize_to_vec(),
+                assembled_2.serialize_to_vec()
+            );
+
+            for assembled in [&mut assembled_1, &mut assembled_2] {
+                assembled
+                    .canonicalize_fields(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                    .unwrap();
+                assembled
+                    .verify(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                    .unwrap();
+            }
+            assert_eq!(
+                assembled_1.serialize_to_vec(),
+                assembled_2.serialize_to_vec()
+            );
+            assert_eq!(assembled_1, signed);
+
+            // fields that do not verify in any order are left untouched
+            let mut unsigned = shuffled([2, 1, 0]);
+            unsigned.fields.pop();
+            let before = unsigned.clone();
+            assert!(unsigned
+                .canonicalize_fields(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                .is_err());
+            assert_eq!(unsigned, before);
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_verify_auths() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+
+        let sign_standard = |privk: &StacksPrivateKey| {
+            let origin_auth = TransactionAuth::Standard(
+                TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                    privk,
+                ))
+                .unwrap(),
+            );
+            let tx = tx_stacks_transaction_test_txs(&origin_auth).remove(0);
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(privk).unwrap();
+            tx_signer.get_tx().unwrap()
+        };
+
+        let signed_tx_1 = sign_standard(&privk_1);
+        let signed_tx_2 = sign_standard(&privk_2);
+        let unsigned_tx = {
+            let mut tx = signed_tx_1.clone();
+            tx.auth = TransactionAuth::Standard(
+                TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                    &privk_1,
+                ))
+                .unwrap(),
+            );
+            tx
+        };
+
+        let auths = [
+            (signed_tx_1.auth(), signed_tx_1.verify_begin()),
+            // signed, but checked against the wrong sighash
+            (signed_tx_1.auth(), Txid([0x01; 32])),
+            (unsigned_tx.auth(), unsigned_tx.verify_begin()),
+            (signed_tx_2.auth(), signed_tx_2.verify_begin()),
+        ];
+        let results = TransactionAuth::verify_auths(&auths);
+        assert_eq!(results.len(), auths.len());
+        assert_eq!(results[0].as_ref().unwrap(), &true);
+        assert!(results[1].is_err());
+        assert!(results[2].is_err());
+        assert_eq!(results[3].as_ref().unwrap(), &true);
+
+        assert!(TransactionAuth::verify_auths(&[]).is_empty());
+    }
+
+    #[test]
+    fn tx_stacks_transaction_verify_auths_parallel() {
+        use crate::chainstate::stacks::auth::verify_auths_parallel;
+
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &privk,
+            ))
+            .unwrap(),
+        );
+
+        let mut auths = vec![];
+        for (i, tx) in tx_stacks_transaction_test_txs(&origin_auth)
+            .into_iter()
+            .enumerate()
+        {
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            match i % 3 {
+                0 => auths.push((signed_tx.auth.clone(), signed_tx.verify_begin())),
+                // signed, but checked against the wrong sighash
+                1 => auths.push((signed_tx.auth.clone(), Txid([0x01; 32]))),
+                // not signed at all
+                _ => auths.push((tx.auth.clone(), tx.verify_begin())),
+            }
+        }
+
+        let results = verify_auths_parallel(&auths);
+        assert_eq!(results.len(), auths.len());
+        for ((auth, initial_sighash), result) in auths.iter().zip(results.iter()) {
+            match auth.verify(initial_sighash) {
+                Ok(()) => assert_eq!(result.as_ref().unwrap(), &true),
+                Err(e) => assert_eq!(
+                    format!("{:?}", result.as_ref().unwrap_err()),
+                    format!("{:?}", e)
+                ),
+            }
+        }
+        assert!(results.iter().any(|r| r.is_ok()));
+        assert!(results.iter().any(|r| r.is_err()));
+
+        assert!(verify_auths_parallel(&[]).is_empty());
+    }
+
+    #[test]
+    fn tx_stacks_transaction_multisig_duplicate_signatures() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let pubk = StacksPublicKey::from_private(&privk);
+
+        // a degenerate 2-of-2 key set, satisfied by one key signing twice
+        let sequential_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(2, vec![pubk.clone(), pubk.clone()])
+                .unwrap(),
+        );
+        // an order-independent condition ends up with the very same signature twice
+        let order_independent_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_order_independent_p2sh(
+                2,
+                vec![pubk.clone(), pubk.clone()],
+            )
+            .unwrap(),
+        );
+        for auth in [sequential_auth, order_independent_auth] {
+            for tx in tx_stacks_transaction_test_txs(&auth) {
+                let mut tx_signer = StacksTransactionSigner::new(&tx);
+                tx_signer.sign_origin(&privk).unwrap();
+                tx_signer.sign_origin(&privk).unwrap();
+                let signed_tx = tx_signer.get_tx().unwrap();
+
+                // this is only rejected from Stacks 3.0 on, so it still parses and verifies
+                let bytes = signed_tx.serialize_to_vec();
+                assert_eq!(
+                    StacksTransaction::consensus_deserialize(&mut &bytes[..]).unwrap(),
+                    signed_tx
+                );
+                signed_tx.verify().unwrap();
+
+                match signed_tx.validate_multisig_constraints() {
+                    Err(net_error::VerifyingError(msg)) => {
+                        assert_eq!(&msg, "Multiple signatures from the same public key")
+                    }
+                    res => panic!("Expected a duplicate signing key, got {:?}", res),
+                }
+            }
+        }
+
+        // distinct signing keys are fine
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let distinct_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk.clone(), StacksPublicKey::from_private(&privk_2)],
+            )
+            .unwrap(),
+        );
+        for tx in tx_stacks_transaction_test_txs(&distinct_auth) {
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            tx_signer.sign_origin(&privk_2).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            signed_tx.verify().unwrap();
+            signed_tx.validate_multisig_constraints().unwrap();
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_sign_verify_sponsored_p2sh() {
+        let origin_privk = StacksPrivateKey::from_hex(
+            "807bbe9e471ac976592cc35e3056592ecc0f778ee653fced3b491a122dd8d59701",
+        )
+        .unwrap();
+
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+
+        let random_sponsor = StacksPrivateKey::new(); // what the origin sees
+
+        let auth = TransactionAuth::Sponsored(
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &origin_privk,
+            ))
+            .unwrap(),
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &random_sponsor,
+            ))
+            .unwrap(),
+        );
+
+        let real_sponsor = TransactionSpendingCondition::new_multisig_p2sh(
+            2,
+            vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+        )
+        .unwrap();
+
+        let origin_address = auth.origin().address_mainnet();
+        let sponsor_address = real_sponsor.address_mainnet();
+
+        assert_eq!(
+            origin_address,
+            StacksAddress {
+                version: C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+                bytes: Hash160::from_hex("3597aaa4bde720be93e3829aae24e76e7fcdfd3e").unwrap()
+            }
+        );
+        assert_eq!(
+            sponsor_address,
+            StacksAddress {
+                version: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                bytes: Hash160::from_hex("a23ea89d6529ac48ac766f720e480beec7f19273").unwrap()
+            }
+        );
+
+        let txs = tx_stacks_transaction_test_txs(&auth);
+
+        for mut tx in txs {
+            assert_eq!(tx.auth().origin().num_signatures(), 0);
+            assert_eq!(tx.auth().sponsor().unwrap().num_signatures(), 0);
+
+            tx.set_tx_fee(123);
+            tx.set_sponsor_nonce(456).unwrap();
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+
+            tx_signer.sign_origin(&origin_privk).unwrap();
+
+            // sponsor sets and pays fee after origin signs
+            let mut origin_tx = tx_signer.get_tx_incomplete();
+            origin_tx.auth.set_sponsor(real_sponsor.clone()).unwrap();
+            origin_tx.set_tx_fee(456);
+            origin_tx.set_sponsor_nonce(789).unwrap();
+            tx_signer.resume(&origin_tx);
+
+            tx_signer.sign_sponsor(&privk_1).unwrap();
+            tx_signer.sign_sponsor(&privk_2).unwrap();
+            tx_signer.append_sponsor(&pubk_3).unwrap();
+
+            tx.set_tx_fee(456);
+            tx.set_sponsor_nonce(789).unwrap();
+            let mut signed_tx = tx_signer.get_tx().unwrap();
+
+            check_oversign_origin_singlesig(&mut signed_tx);
+            check_oversign_sponsor_multisig(&mut signed_tx);
+
+            assert_eq!(signed_tx.auth().origin().num_signatures(), 1);
+            assert_eq!(signed_tx.auth().sponsor().unwrap().num_signatures(), 2);
+
+            // tx and signed_tx are otherwise equal
+            assert_eq!(tx.version, signed_tx.version);
+            assert_eq!(tx.chain_id, signed_tx.chain_id);
+            assert_eq!(tx.get_tx_fee(), signed_tx.get_tx_fee());
+            assert_eq!(tx.get_origin_nonce(), signed_tx.get_origin_nonce());
+            assert_eq!(tx.get_sponsor_nonce(), signed_tx.get_sponsor_nonce());
+            assert_eq!(tx.anchor_mode, signed_tx.anchor_mode);
+            assert_eq!(tx.post_condition_mode, signed_tx.post_condition_mode);
+            assert_eq!(tx.post_conditions, signed_tx.post_conditions);
+            assert_eq!(tx.payload, signed_tx.payload);
+
+            // auth is standard and first two auth fields are signatures for compressed keys.
+            // third field is the third public key
+            match signed_tx.auth {
+                TransactionAuth::Sponsored(ref origin, ref sponsor) => {
+                    match origin {
+                        TransactionSpendingCondition::Singlesig(ref data) => {
+                            assert_eq!(data.key_encoding, TransactionPublicKeyEncoding::Compressed);
+                            assert_eq!(data.signer, origin_address.bytes);
+                        }
+                        _ => assert!(false),
+                    }
+                    match sponsor {
+                        TransactionSpendingCondition::Multisig(ref data) => {
+                            assert_eq!(data.signer, sponsor_address.bytes);
+                            assert_eq!(data.fields.len(), 3);
+                            assert!(data.fields[0].is_signature());
+                            assert!(data.fields[1].is_signature());
+                            assert!(data.fields[2].is_public_key());
+
+                            assert_eq!(
+                                data.fields[0].as_signature().unwrap().0,
+                                TransactionPublicKeyEncoding::Compressed
+                            );
+                            assert_eq!(
+                                data.fields[1].as_signature().unwrap().0,
+                                TransactionPublicKeyEncoding::Compressed
+                            );
+                            assert_eq!(data.fields[2].as_public_key().unwrap(), pubk_3);
+                        }
+                        _ => assert!(false),
+                    }
+                }
+                _ => assert!(false),
+            };
+
+            test_signature_and_corruption(&signed_tx, true, false);
+            test_signature_and_corruption(&signed_tx, false, true);
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_sign_verify_standard_p2sh_uncompressed() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e0",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d2",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+
+        let auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+            )
+            .unwrap(),
+        );
+
+        let origin_address = auth.origin().address_mainnet();
+
+        assert_eq!(
+            origin_address,
+            StacksAddress {
+                version: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+                bytes: Hash160::from_hex("73a8b4a751a678fe83e9d35ce301371bb3d397f7").unwrap()
+            }
+        );
+
+        let txs = tx_stacks_transaction_test_txs(&auth);
+
+        for tx in txs {
+            assert_eq!(tx.auth().origin().num_signatures(), 0);
+
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.sign_origin(&privk_2).unwrap();
+            tx_signer.append_origin(&pubk_3).unwrap();
+
+            let mut signed_tx = tx_signer.get_tx().unwrap();
+
+            check_oversign_origin_multisig(&mut signed_tx);
+            check_sign_no_sponsor(&mut signed_tx);
+
+            assert_eq!(signed_tx.auth().origin().num_signatures(), 2);
+
+            // tx and signed_tx are otherwise equal
+            assert_eq!(tx.version, signed_tx.version);
+            assert_eq!(tx.chain_id, signed_tx.chain_id);
+            assert_eq!(tx.get_tx_fee(), signed_tx.get_tx_fee());
+            assert_eq!(tx.get_origin_nonce(), signed_tx.get_origin_nonce());
+            assert_eq!(tx.get_sponsor_nonce(), signed_tx.get_sponsor_nonce());
+            assert_eq!(tx.anchor_mode, signed_tx.anchor_mode);
+            assert_eq!(tx.post_condition_mode, signed_tx.post_condition_mode);
+            assert_eq!(tx.post_conditions, signed_tx.post_conditions);
+            assert_eq!(tx.payload, signed_tx.payload);
+
+            // auth is standard and first two auth fields are signatures for uncompressed keys.
+            // third field is the third public key
+            match signed_tx.auth {
+                TransactionAuth::Standard(ref origin) => match origin {
+                    TransactionSpendingCondition::Multisig(ref data) => {
+                        assert_eq!(data.signer, origin_address.bytes);
+                        assert_eq!(data.fields.len(), 3);
+                        assert!(data.fields[0].is_signature());
+                        assert!(data.fields[1].is_signature());
+                        assert!(data.fields[2].is_public_key());
+
+                        assert_eq!(
+                            data.fields[0].as_signature().unwrap().0,
+                            TransactionPublicKeyEncoding::Uncompressed
+                        );
+                        assert_eq!(
+                            data.fields[1].as_signature().unwrap().0,
+                            TransactionPublicKeyEncoding::Uncompressed
+                        );
+                        assert_eq!(data.fields[2].as_public_key().unwrap(), pubk_3);
+                    }
+                    _ => assert!(false),
+                },
+                _ => assert!(false),
+            };
+
+            test_signature_and_corruption(&signed_tx, true, false);
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_sign_verify_sponsored_p2sh_uncompressed() {
+        let origin_privk = StacksPrivateKey::from_hex(
+            "807bbe9e471ac976592cc35e3056592ecc0f778ee653fced3b491a122dd8d59701",
+        )
+        .unwrap();
+
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e0",
         )
         .unwrap();
         let privk_2 = StacksPrivateKey::from_hex(
@@ -5121,6 +5832,69 @@ mod test {
         }
     }
 
+    #[test]
+    fn tx_stacks_transaction_sign_verify_standard_p2tr() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_singlesig_p2tr(StacksPublicKey::from_private(&privk))
+                .unwrap(),
+        );
+
+        let origin_address = origin_auth.origin().address_mainnet();
+        assert_eq!(
+            origin_address,
+            StacksAddress {
+                version: C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+                bytes: Hash160::from_data(
+                    &StacksPublicKey::from_private(&privk).to_bytes_compressed()[1..]
+                )
+            }
+        );
+
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
+
+        for tx in txs {
+            assert_eq!(tx.auth().origin().num_signatures(), 0);
+
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            let mut signed_tx = tx_signer.get_tx().unwrap();
+
+            // try to over-sign
+            check_oversign_origin_singlesig(&mut signed_tx);
+            check_sign_no_sponsor(&mut signed_tx);
+
+            assert_eq!(signed_tx.auth().origin().num_signatures(), 1);
+
+            // tx and signed_tx are otherwise equal
+            assert_eq!(tx.version, signed_tx.version);
+            assert_eq!(tx.get_tx_fee(), signed_tx.get_tx_fee());
+            assert_eq!(tx.get_origin_nonce(), signed_tx.get_origin_nonce());
+            assert_eq!(tx.get_sponsor_nonce(), signed_tx.get_sponsor_nonce());
+            assert_eq!(tx.anchor_mode, signed_tx.anchor_mode);
+            assert_eq!(tx.post_condition_mode, signed_tx.post_condition_mode);
+            assert_eq!(tx.post_conditions, signed_tx.post_conditions);
+            assert_eq!(tx.payload, signed_tx.payload);
+
+            // auth is standard and public key is compressed
+            match signed_tx.auth {
+                TransactionAuth::Standard(ref origin) => match origin {
+                    TransactionSpendingCondition::Singlesig(ref data) => {
+                        assert_eq!(data.signer, origin_address.bytes);
+                        assert_eq!(data.key_encoding, TransactionPublicKeyEncoding::Compressed);
+                    }
+                    _ => assert!(false),
+                },
+                _ => assert!(false),
+            };
+
+            test_signature_and_corruption(&signed_tx, true, false);
+        }
+    }
+
     #[test]
     fn tx_stacks_transaction_sign_verify_sponsored_p2wpkh() {
         let origin_privk = StacksPrivateKey::from_hex(
@@ -5475,6 +6249,187 @@ mod test {
         }
     }
 
+    #[test]
+    fn tx_stacks_transaction_verify_origin_only_standard() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &privk,
+            ))
+            .unwrap(),
+        );
+        let origin_address = origin_auth.origin().address_mainnet();
+
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
+        for mut tx in txs {
+            tx.set_tx_fee(123);
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+
+            let (address, fee) = signed_tx
+                .auth
+                .verify_origin_only(&signed_tx.verify_begin(), true)
+                .unwrap();
+            assert_eq!(address, origin_address);
+            assert_eq!(fee, 123);
+
+            // an unsigned origin does not verify
+            assert!(tx
+                .auth
+                .verify_origin_only(&tx.verify_begin(), true)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_verify_origin_only_sponsored() {
+        let privk = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_sponsor = StacksPrivateKey::from_hex(
+            "807bbe9e471ac976592cc35e3056592ecc0f778ee653fced3b491a122dd8d59701",
+        )
+        .unwrap();
+
+        let auth = TransactionAuth::Sponsored(
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &privk,
+            ))
+            .unwrap(),
+            TransactionSpendingCondition::new_singlesig_p2pkh(StacksPublicKey::from_private(
+                &privk_sponsor,
+            ))
+            .unwrap(),
+        );
+        let origin_address = auth.origin().address_testnet();
+
+        let txs = tx_stacks_transaction_test_txs(&auth);
+        for tx in txs {
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk).unwrap();
+            let origin_tx = tx_signer.get_tx_incomplete();
+
+            let mut sponsor_auth = TransactionSpendingCondition::new_singlesig_p2pkh(
+                StacksPublicKey::from_private(&privk_sponsor),
+            )
+            .unwrap();
+            sponsor_auth.set_tx_fee(456);
+
+            // the sponsor has not signed, so the full auth does not verify, but the origin does
+            let (address, fee) = origin_tx
+                .auth
+                .verify_origin_only(&origin_tx.verify_begin(), false)
+                .unwrap();
+            assert_eq!(address, origin_address);
+            assert_eq!(fee, origin_tx.auth.origin().tx_fee());
+            assert!(origin_tx.verify().is_err());
+
+            let mut tx_sponsor_signer =
+                StacksTransactionSigner::new_sponsor(&origin_tx, sponsor_auth).unwrap();
+            tx_sponsor_signer.sign_sponsor(&privk_sponsor).unwrap();
+            let signed_tx = tx_sponsor_signer.get_tx().unwrap();
+
+            // the sponsor's fee is not reported
+            let (address, fee) = signed_tx
+                .auth
+                .verify_origin_only(&signed_tx.verify_begin(), false)
+                .unwrap();
+            assert_eq!(address, origin_address);
+            assert_eq!(fee, signed_tx.auth.origin().tx_fee());
+            assert_ne!(fee, signed_tx.get_tx_fee());
+            signed_tx.verify().unwrap();
+        }
+    }
+
+    #[test]
+    fn tx_stacks_transaction_multisig_verify_against() {
+        let privk_1 = StacksPrivateKey::from_hex(
+            "6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001",
+        )
+        .unwrap();
+        let privk_2 = StacksPrivateKey::from_hex(
+            "2a584d899fed1d24e26b524f202763c8ab30260167429f157f1c119f550fa6af01",
+        )
+        .unwrap();
+        let privk_3 = StacksPrivateKey::from_hex(
+            "d5200dee706ee53ae98a03fba6cf4fdcc5084c30cfa9e1b3462dcdeaa3e0f1d201",
+        )
+        .unwrap();
+
+        let pubk_1 = StacksPublicKey::from_private(&privk_1);
+        let pubk_2 = StacksPublicKey::from_private(&privk_2);
+        let pubk_3 = StacksPublicKey::from_private(&privk_3);
+
+        let origin_auth = TransactionAuth::Standard(
+            TransactionSpendingCondition::new_multisig_p2sh(
+                2,
+                vec![pubk_1.clone(), pubk_2.clone(), pubk_3.clone()],
+            )
+            .unwrap(),
+        );
+        let signer = origin_auth.origin().address_mainnet().bytes;
+        // the signer hash of a rotated key set
+        let rotated_signer = TransactionSpendingCondition::new_multisig_p2sh(
+            2,
+            vec![pubk_3.clone(), pubk_2.clone(), pubk_1.clone()],
+        )
+        .unwrap()
+        .address_mainnet()
+        .bytes;
+        assert_ne!(signer, rotated_signer);
+        let other_signer = Hash160([0x01; 20]);
+
+        let txs = tx_stacks_transaction_test_txs(&origin_auth);
+        for tx in txs {
+            let mut tx_signer = StacksTransactionSigner::new(&tx);
+            tx_signer.sign_origin(&privk_1).unwrap();
+            tx_signer.sign_origin(&privk_2).unwrap();
+            tx_signer.append_origin(&pubk_3).unwrap();
+            let signed_tx = tx_signer.get_tx().unwrap();
+            let initial_sighash = signed_tx.verify_begin();
+
+            let condition = match signed_tx.auth.origin() {
+                TransactionSpendingCondition::Multisig(ref data) => data,
+                _ => panic!("Expected a multisig condition"),
+            };
+            let next_sighash = condition
+                .verify(&initial_sighash, &TransactionAuthFlags::AuthStandard)
+                .unwrap();
+
+            // matches one of the two allowed signers
+            assert_eq!(
+                condition
+                    .verify_against(
+                        &initial_sighash,
+                        &TransactionAuthFlags::AuthStandard,
+                        &[rotated_signer.clone(), signer.clone()],
+                    )
+                    .unwrap(),
+                next_sighash
+            );
+
+            // matches neither of the allowed signers
+            match condition.verify_against(
+                &initial_sighash,
+                &TransactionAuthFlags::AuthStandard,
+                &[rotated_signer.clone(), other_signer.clone()],
+            ) {
+                Err(net_error::VerifyingError(msg)) => {
+                    assert!(msg.starts_with("Hash of public key(s) is not an allowed signer"))
+                }
+                res => panic!("Expected a verifying error, got {res:?}"),
+            }
+            assert!(condition
+                .verify_against(&initial_sighash, &TransactionAuthFlags::AuthStandard, &[])
+                .is_err());
+        }
+    }
+
     // TODO(test): test with different tx versions
     // TODO(test): test error values for signing and verifying
 }
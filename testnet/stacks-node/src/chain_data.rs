@@ -166,7 +166,7 @@ impl MinerStats {
             windowed_block_commits,
             windowed_missed_commits,
             burn_blocks,
-        );
+        )?;
 
         Ok(burn_dist)
     }
@@ -297,6 +297,31 @@ mod tests {
         assert_eq!(config.burnchain.affirmation_overrides.len(), 5);
         assert_eq!(config.burnchain.affirmation_overrides[&413], affirmation);
     }
+
+    #[test]
+    fn should_load_miner_signing_threshold_ratio() {
+        let config = Config::from_config_file(ConfigFile::from_str("").unwrap()).unwrap();
+        assert_eq!(config.miner.signing_threshold_ratio, 0.7);
+
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [miner]
+                signing_threshold_ratio = 0.8
+                "#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(config.miner.signing_threshold_ratio, 0.8);
+
+        for ratio in ["0.0", "1.5"] {
+            let config_file =
+                ConfigFile::from_str(&format!("[miner]\nsigning_threshold_ratio = {ratio}\n"))
+                    .unwrap();
+            assert!(Config::from_config_file(config_file).is_err());
+        }
+    }
 }
 
 impl ConfigFile {
@@ -2234,6 +2259,9 @@ pub struct MinerConfig {
     pub max_reorg_depth: u64,
     /// Amount of time while mining in nakamoto to wait for signers to respond to a proposed block
     pub wait_on_signers: Duration,
+    /// The share of the signers' key ids needed to sign a block. Must match the
+    /// `signing_threshold_ratio` the signers are configured with.
+    pub signing_threshold_ratio: f64,
 }
 
 impl Default for MinerConfig {
@@ -2263,6 +2291,7 @@ impl Default for MinerConfig {
             max_reorg_depth: 3,
             // TODO: update to a sane value based on stackerdb benchmarking
             wait_on_signers: Duration::from_secs(200),
+            signing_threshold_ratio: 0.7,
         }
     }
 }
@@ -2586,10 +2615,19 @@ pub struct MinerConfigFile {
     pub filter_origins: Option<String>,
     pub max_reorg_depth: Option<u64>,
     pub wait_on_signers_ms: Option<u64>,
+    pub signing_threshold_ratio: Option<f64>,
 }
 
 impl MinerConfigFile {
     fn into_config_default(self, miner_default_config: MinerConfig) -> Result<MinerConfig, String> {
+        let signing_threshold_ratio = self
+            .signing_threshold_ratio
+            .unwrap_or(miner_default_config.signing_threshold_ratio);
+        if !(signing_threshold_ratio > 0.0 && signing_threshold_ratio <= 1.0) {
+            return Err(format!(
+                "miner.signing_threshold_ratio must be in (0, 1], got {signing_threshold_ratio}"
+            ));
+        }
         Ok(MinerConfig {
             first_attempt_time_ms: self
                 .first_attempt_time_ms
@@ -2685,6 +2723,7 @@ impl MinerConfigFile {
                 .wait_on_signers_ms
                 .map(Duration::from_millis)
                 .unwrap_or(miner_default_config.wait_on_signers),
+            signing_threshold_ratio,
         })
     }
 }
@@ -416,6 +416,7 @@ mod tests {
                 AddressHashMode::SerializeP2WSH => {
                     TransactionAuth::from_p2wsh(&self.secret_keys, self.threshold)
                 }
+                AddressHashMode::SerializeP2TR => TransactionAuth::from_p2tr(&self.secret_keys[0]),
             }
         }
 
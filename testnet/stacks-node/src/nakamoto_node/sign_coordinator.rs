@@ -87,9 +87,12 @@ impl Drop for SignCoordinator {
 }
 
 impl NakamotoSigningParams {
+    /// `signing_threshold_ratio` is the share of the key ids needed to sign, and must match the
+    /// ratio the signers use.
     pub fn parse(
         is_mainnet: bool,
         reward_set: &[NakamotoSignerEntry],
+        signing_threshold_ratio: f64,
     ) -> Result<Self, ChainstateError> {
         let parsed = SignerEntries::parse(is_mainnet, reward_set).map_err(|e| {
             ChainstateError::InvalidStacksBlock(format!(
@@ -104,7 +107,7 @@ impl NakamotoSigningParams {
             .count_signers()
             .expect("FATAL: more than u32::max() signers in the reward set");
         let threshold = parsed
-            .get_signing_threshold()
+            .get_threshold(signing_threshold_ratio)
             .expect("FATAL: more than u32::max() signers in the reward set");
 
         Ok(NakamotoSigningParams {
@@ -216,7 +219,11 @@ impl SignCoordinator {
             signer_key_ids,
             signer_public_keys,
             wsts_public_keys,
-        } = NakamotoSigningParams::parse(is_mainnet, reward_set_signers.as_slice())?;
+        } = NakamotoSigningParams::parse(
+            is_mainnet,
+            reward_set_signers.as_slice(),
+            config.miner.signing_threshold_ratio,
+        )?;
         debug!(
             "Initializing miner/coordinator";
             "num_signers" => num_signers,